@@ -0,0 +1,64 @@
+//! How long getting the metadata of every mod in a profile takes, before and after it's cached
+//!
+//! This requests the metadata of real Modrinth projects, so it needs network access.
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use libium::{config::structs::ModIdentifier, metadata};
+use std::time::Instant;
+
+const PROJECTS: [&str; 10] = [
+    "AANobbMI", // Sodium
+    "gvQqBUqZ", // Lithium
+    "P7dR8mSH", // Fabric API
+    "YL57xq9U", // Iris
+    "mOgUt4GM", // Mod Menu
+    "9s6osm5g", // Cloth Config
+    "NNAgCjsB", // Entity Culling
+    "uXXizFIs", // FerriteCore
+    "5ZwdcRci", // ImmediatelyFast
+    "fQEb0iXm", // Krypton
+];
+/// How many times the cached metadata is read
+const ITERATIONS: u32 = 1000;
+
+fn main() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let mut profile = common::profile("Benchmark", "mods");
+    for id in PROJECTS {
+        profile.push_mod(
+            id.to_owned(),
+            ModIdentifier::ModrinthProject(id.to_owned()),
+            false,
+            Vec::new(),
+        );
+    }
+
+    runtime.block_on(async {
+        let start = Instant::now();
+        metadata::prefetch(&profile).await.unwrap();
+        println!(
+            "Prefetching {} projects: {:?}",
+            profile.mods.len(),
+            start.elapsed()
+        );
+
+        // The gallery, authors, and metadata of each mod used to be fetched separately
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            for mod_ in &profile.mods {
+                metadata::project_metadata(&mod_.identifier).await.unwrap();
+                metadata::gallery(&mod_.identifier).await.unwrap();
+                metadata::authors(&mod_.identifier).await.unwrap();
+            }
+        }
+        println!(
+            "Reading the cached metadata, gallery, and authors of every mod: {:?} per iteration",
+            start.elapsed() / ITERATIONS
+        );
+    });
+}
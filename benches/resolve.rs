@@ -0,0 +1,65 @@
+//! How long resolving every mod in a profile takes when the Modrinth versions are requested in batches,
+//! compared to requesting them for each mod separately
+//!
+//! This resolves real Modrinth projects, so it needs network access.
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use libium::{
+    config::{filters::FileSelection, structs::ModIdentifier},
+    upgrade::mod_downloadable::resolve_all,
+};
+use std::time::Instant;
+
+const PROJECTS: [&str; 10] = [
+    "AANobbMI", // Sodium
+    "gvQqBUqZ", // Lithium
+    "P7dR8mSH", // Fabric API
+    "YL57xq9U", // Iris
+    "mOgUt4GM", // Mod Menu
+    "9s6osm5g", // Cloth Config
+    "NNAgCjsB", // Entity Culling
+    "uXXizFIs", // FerriteCore
+    "5ZwdcRci", // ImmediatelyFast
+    "fQEb0iXm", // Krypton
+];
+
+fn main() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let mut profile = common::profile("Benchmark", "mods");
+    for id in PROJECTS {
+        profile.push_mod(
+            id.to_owned(),
+            ModIdentifier::ModrinthProject(id.to_owned()),
+            false,
+            Vec::new(),
+        );
+    }
+
+    runtime.block_on(async {
+        let start = Instant::now();
+        for mod_ in &profile.mods {
+            mod_.fetch_download_file(profile.filters.clone(), FileSelection::default())
+                .await
+                .unwrap();
+        }
+        println!(
+            "Resolving {} mods separately: {:?}",
+            profile.mods.len(),
+            start.elapsed()
+        );
+
+        let start = Instant::now();
+        let plan = resolve_all(&profile, FileSelection::default()).await;
+        assert!(plan.errors.is_empty(), "{:?}", plan.errors);
+        println!(
+            "Resolving {} mods with batched requests: {:?}",
+            profile.mods.len(),
+            start.elapsed()
+        );
+    });
+}
@@ -1,42 +1,459 @@
 use crate::{
     config::{
         filters::{Filter, ReleaseChannel},
-        structs::{ModIdentifier, ModLoader, Profile},
+        structs::{
+            ContentType, DuplicateStrategy, License, ModIdentifier, ModLoader, ModOrigin, Profile,
+            ProfileLocked, ProviderKind,
+        },
     },
     iter_ext::IterExt as _,
-    upgrade::{check, Metadata},
-    CURSEFORGE_API, GITHUB_API, MODRINTH_API,
+    metadata,
+    upgrade::{
+        check, filter_assets, from_gh_contents, from_gh_releases, from_mr_version,
+        mod_downloadable::modrinth_versions_of, DownloadData, Metadata,
+    },
+    GITHUB_API, MODRINTH_API,
+};
+#[cfg(feature = "curseforge")]
+use crate::{upgrade::try_from_cf_file, CURSEFORGE_API};
+use ferinth::structures::version::Version;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{read_to_string, remove_file, OpenOptions},
+    io::Write as _,
+    path::Path,
+    str::FromStr,
+    time::{Duration, Instant},
 };
-use serde::Deserialize;
-use std::{collections::HashMap, str::FromStr};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error(
         "The developer of this project has denied third party applications from downloading it"
     )]
-    /// The user can manually download the mod and place it in the `user` folder of the output directory to mitigate this.
+    /// The user can manually download the mod and place it in the [`user` folder](crate::config::structs::Profile::user_mods_path) of the output directory to mitigate this.
     /// However, they will have to manually update the mod.
     DistributionDenied,
     #[error("The project has already been added")]
     AlreadyAdded,
+    #[error("The profile is locked")]
+    /// The lock can be overridden using [`Profile::override_lock`].
+    ProfileLocked,
     #[error("The project is not compatible because {_0}")]
     Incompatible(#[from] check::Error),
+    #[error("{0} is not a valid identifier")]
+    InvalidIdentifier(String),
+    #[error("{0} is not a recognised platform")]
+    /// Only project pages on Modrinth, CurseForge, and GitHub can be parsed, see [`parse_url()`].
+    UnknownHost(String),
     #[error("The project does not exist")]
     DoesNotExist,
+    #[error("The project does not have a version {0}")]
+    VersionDoesNotExist(String),
     #[error("The project is not a mod")]
     NotAMod,
+    #[error("The repository has releases, but none of them have JAR files")]
+    /// Releases without any assets, such as source-only tags, are skipped when looking for files.
+    NoReleaseJars,
+    #[error("None of the repository's release assets match the asset filter {0}")]
+    NoMatchingAssets(String),
+    #[error("The project is a {0:?}, which this profile does not accept")]
+    /// Data packs and resource packs are only accepted if the profile opts into them.
+    UnsupportedProjectType(ProjectType),
+    #[error("The project is not publicly available since its status is {0:?}")]
+    /// Modrinth projects that are drafts, rejected, withheld, or still being reviewed can't be added,
+    /// unless a [Modrinth token](crate::set_modrinth_token) is used, since only their authors can get them then.
+    /// Unlisted projects can still be added.
+    NotPublished(ProjectStatus),
     #[error("GitHub: {0}")]
     GitHubError(String),
+    #[error(
+        "The GitHub API rate limit has been exceeded{}",
+        reset_at.map(|reset_at| format!(", it resets at {reset_at}")).unwrap_or_default()
+    )]
+    /// The user can wait until `reset_at`, or provide a GitHub personal access token to get a higher rate limit.
+    RateLimited {
+        /// The Unix timestamp at which the rate limit resets, if it could be determined
+        reset_at: Option<u64>,
+    },
+    #[error(
+        "The Modrinth API rate limit has been exceeded{}",
+        reset_at.map(|reset_at| format!(", it resets at {reset_at}")).unwrap_or_default()
+    )]
+    /// The user should wait until `reset_at` before sending more requests to Modrinth.
+    ModrinthRateLimited {
+        /// The Unix timestamp at which the rate limit resets, if it could be determined
+        reset_at: Option<u64>,
+    },
     #[error("GitHub: {0:#?}")]
-    OctocrabError(#[from] octocrab::Error),
+    OctocrabError(Box<octocrab::Error>),
     #[error("Modrinth: {0}")]
-    ModrinthError(#[from] ferinth::Error),
+    ModrinthError(ferinth::Error),
+    #[cfg(feature = "curseforge")]
     #[error("CurseForge: {0}")]
-    CurseForgeError(#[from] furse::Error),
+    CurseForgeError(furse::Error),
+    #[error("CurseForge support is not enabled in this build")]
+    CurseForgeDisabled,
+    #[error("Mods from {0} are not allowed in this profile")]
+    ProviderNotAllowed(ProviderKind),
+    #[error("Network: {0}")]
+    NetworkError(reqwest::Error),
+    #[error("IO: {0}")]
+    IOError(#[from] std::io::Error),
 }
 type Result<T> = std::result::Result<T, Error>;
 
+/// The kind of an [`Error`], without the underlying source errors
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    DistributionDenied,
+    AlreadyAdded,
+    ProfileLocked,
+    Incompatible,
+    InvalidIdentifier,
+    UnknownHost,
+    DoesNotExist,
+    VersionDoesNotExist,
+    NotAMod,
+    NoReleaseJars,
+    NoMatchingAssets,
+    UnsupportedProjectType,
+    NotPublished,
+    GitHubError,
+    RateLimited,
+    OctocrabError,
+    ModrinthError,
+    CurseForgeError,
+    CurseForgeDisabled,
+    ProviderNotAllowed,
+    NetworkError,
+    IOError,
+}
+
+impl Error {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::DistributionDenied => ErrorKind::DistributionDenied,
+            Error::AlreadyAdded => ErrorKind::AlreadyAdded,
+            Error::ProfileLocked => ErrorKind::ProfileLocked,
+            Error::Incompatible(_) => ErrorKind::Incompatible,
+            Error::InvalidIdentifier(_) => ErrorKind::InvalidIdentifier,
+            Error::UnknownHost(_) => ErrorKind::UnknownHost,
+            Error::DoesNotExist => ErrorKind::DoesNotExist,
+            Error::VersionDoesNotExist(_) => ErrorKind::VersionDoesNotExist,
+            Error::NotAMod => ErrorKind::NotAMod,
+            Error::NoReleaseJars => ErrorKind::NoReleaseJars,
+            Error::NoMatchingAssets(_) => ErrorKind::NoMatchingAssets,
+            Error::UnsupportedProjectType(_) => ErrorKind::UnsupportedProjectType,
+            Error::NotPublished(_) => ErrorKind::NotPublished,
+            Error::GitHubError(_) => ErrorKind::GitHubError,
+            Error::RateLimited { .. } | Error::ModrinthRateLimited { .. } => ErrorKind::RateLimited,
+            Error::OctocrabError(_) => ErrorKind::OctocrabError,
+            Error::ModrinthError(_) => ErrorKind::ModrinthError,
+            #[cfg(feature = "curseforge")]
+            Error::CurseForgeError(_) => ErrorKind::CurseForgeError,
+            Error::CurseForgeDisabled => ErrorKind::CurseForgeDisabled,
+            Error::ProviderNotAllowed(_) => ErrorKind::ProviderNotAllowed,
+            Error::NetworkError(_) => ErrorKind::NetworkError,
+            Error::IOError(_) => ErrorKind::IOError,
+        }
+    }
+
+    /// Whether this error is likely to be temporary, such as a timeout, a server error, or a rate limit,
+    /// so adding the mod again may succeed
+    ///
+    /// Errors about the project itself, like [`Error::DoesNotExist`] or [`Error::DistributionDenied`], are never transient.
+    pub fn is_transient(&self) -> bool {
+        let is_transient = |err: &reqwest::Error| {
+            err.is_timeout()
+                || err.is_connect()
+                || err.status().is_some_and(|status| {
+                    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+                })
+        };
+        match self {
+            Error::RateLimited { .. } | Error::ModrinthRateLimited { .. } => true,
+            Error::NetworkError(err) => is_transient(err),
+            Error::ModrinthError(ferinth::Error::ReqwestError(err)) => is_transient(err),
+            #[cfg(feature = "curseforge")]
+            Error::CurseForgeError(furse::Error::ReqwestError(err)) => is_transient(err),
+            _ => false,
+        }
+    }
+
+    /// The Unix timestamp at which the rate limit that caused this error resets, if it is known
+    pub fn rate_limit_reset(&self) -> Option<u64> {
+        match self {
+            Error::RateLimited { reset_at } | Error::ModrinthRateLimited { reset_at } => *reset_at,
+            _ => None,
+        }
+    }
+}
+
+/// How to retry adding mods that failed with a [transient](Error::is_transient) error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first one
+    pub max_attempts: usize,
+    /// The time to wait before the first retry, which doubles for every retry after it
+    pub initial_delay: Duration,
+    /// The longest time to wait before a retry, even if a rate limit resets later than that
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Retry up to `max_attempts` times in total, immediately one after the other
+    pub fn immediate(max_attempts: usize) -> Self {
+        Self {
+            max_attempts,
+            initial_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        }
+    }
+
+    /// Get the time to wait after `attempts` attempts have failed, the last one with `err`
+    ///
+    /// This backs off exponentially, but waits until the rate limit resets if `err` is from a rate limit.
+    ///
+    /// ```
+    /// # use libium::add::{Error, RetryPolicy};
+    /// # use std::time::Duration;
+    /// let policy = RetryPolicy {
+    ///     max_attempts: 5,
+    ///     initial_delay: Duration::from_secs(1),
+    ///     max_delay: Duration::from_secs(5),
+    /// };
+    /// let err = Error::ModrinthRateLimited { reset_at: None };
+    /// assert_eq!(policy.delay(1, &err), Duration::from_secs(1));
+    /// assert_eq!(policy.delay(2, &err), Duration::from_secs(2));
+    /// assert_eq!(policy.delay(4, &err), Duration::from_secs(5));
+    /// assert_eq!(RetryPolicy::immediate(5).delay(4, &err), Duration::ZERO);
+    /// ```
+    pub fn delay(&self, attempts: usize, err: &Error) -> Duration {
+        let backoff = self
+            .initial_delay
+            .saturating_mul(1 << attempts.saturating_sub(1).min(16));
+        let until_reset = err
+            .rate_limit_reset()
+            .and_then(|reset_at| {
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .ok()?;
+                Duration::from_secs(reset_at).checked_sub(now)
+            })
+            .unwrap_or_default();
+        backoff.max(until_reset).min(self.max_delay)
+    }
+}
+
+/// A non-fatal issue with a mod that was successfully added
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddWarning {
+    /// The mod is not compatible with the profile, but was added since the compatibility checks were disabled
+    ForceAdded,
+    /// The project was already added from another platform, so it was recorded as the fallback of the mod with this name
+    MergedAsFallback(String),
+    /// The project is archived or abandoned, so it is unlikely to be updated
+    Archived,
+    /// The Modrinth project is unlisted, so it doesn't appear in search results
+    Unlisted,
+}
+
+/// A mod that was successfully added, along with any non-fatal issues with it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddedMod {
+    pub name: String,
+    pub warnings: Vec<AddWarning>,
+}
+
+impl AddedMod {
+    /// The name of the mod to display to the user
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Find what to warn the user about the mod of `identifier` in `profile` that was just added,
+    /// in addition to the `warnings` about its project
+    fn new(
+        profile: &Profile,
+        name: String,
+        identifier: &ModIdentifier,
+        mut warnings: Vec<AddWarning>,
+    ) -> Self {
+        if let Some(mod_) = profile
+            .mods
+            .iter()
+            .find(|mod_| mod_.fallback.as_ref() == Some(identifier))
+        {
+            warnings.push(AddWarning::MergedAsFallback(mod_.name.clone()));
+        } else if profile
+            .mods
+            .iter()
+            .any(|mod_| &mod_.identifier == identifier && mod_.force_added)
+        {
+            warnings.push(AddWarning::ForceAdded);
+        }
+        Self { name, warnings }
+    }
+}
+
+/// The result of successfully adding a mod with [`add_single_with_retries()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddOutcome {
+    /// The name of the mod that was added
+    pub name: String,
+    /// The non-fatal issues with the mod
+    pub warnings: Vec<AddWarning>,
+    /// The number of times the mod was tried to be added, including the successful one
+    pub attempts: usize,
+    /// The time taken by all the attempts
+    pub total_duration: Duration,
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        if Some(StatusCode::NOT_FOUND) == err.status() {
+            Self::DoesNotExist
+        } else {
+            Self::NetworkError(err)
+        }
+    }
+}
+
+impl From<ProfileLocked> for Error {
+    fn from(_: ProfileLocked) -> Self {
+        Error::ProfileLocked
+    }
+}
+
+impl From<octocrab::Error> for Error {
+    fn from(err: octocrab::Error) -> Self {
+        if let octocrab::Error::GitHub { source, .. } = &err {
+            if source.status_code == StatusCode::NOT_FOUND {
+                Self::DoesNotExist
+            } else if (source.status_code == StatusCode::FORBIDDEN
+                || source.status_code == StatusCode::TOO_MANY_REQUESTS)
+                && source.message.to_lowercase().contains("rate limit")
+            {
+                Self::RateLimited { reset_at: None }
+            } else {
+                Self::OctocrabError(Box::new(err))
+            }
+        } else {
+            Self::OctocrabError(Box::new(err))
+        }
+    }
+}
+
+/// Convert `err` like `From`, but fill in the time at which the rate limit resets if GitHub's rate limit was exceeded
+///
+/// Octocrab doesn't expose the response headers, so the reset time is fetched from GitHub's rate limit endpoint,
+/// which does not count against the rate limit.
+async fn github_error(err: impl Into<Error>) -> Error {
+    match err.into() {
+        Error::RateLimited { reset_at: None } => Error::RateLimited {
+            reset_at: GITHUB_API.ratelimit().get().await.ok().map(|limit| {
+                limit
+                    .resources
+                    .graphql
+                    .unwrap_or(limit.resources.core)
+                    .reset
+            }),
+        },
+        err => err,
+    }
+}
+
+/// Convert `err` like `From`, but fill in the time at which the rate limit resets if Modrinth's rate limit was exceeded
+async fn modrinth_error(err: impl Into<Error>) -> Error {
+    match err.into() {
+        Error::ModrinthRateLimited { reset_at: None } => Error::ModrinthRateLimited {
+            reset_at: crate::health::modrinth_rate_limit()
+                .await
+                .ok()
+                .flatten()
+                .map(|limit| limit.reset_at),
+        },
+        err => err,
+    }
+}
+
+#[cfg(feature = "curseforge")]
+impl From<furse::Error> for Error {
+    fn from(err: furse::Error) -> Self {
+        if let furse::Error::ReqwestError(source) = &err {
+            if Some(StatusCode::NOT_FOUND) == source.status() {
+                Self::DoesNotExist
+            } else {
+                Self::CurseForgeError(err)
+            }
+        } else {
+            Self::CurseForgeError(err)
+        }
+    }
+}
+
+impl From<metadata::Error> for Error {
+    fn from(err: metadata::Error) -> Self {
+        match err {
+            metadata::Error::ModrinthError(err) => err.into(),
+            #[cfg(feature = "curseforge")]
+            metadata::Error::CurseForgeError(err) => err.into(),
+            metadata::Error::CurseForgeDisabled => Self::CurseForgeDisabled,
+            metadata::Error::GitHubError(err) => (*err).into(),
+        }
+    }
+}
+
+impl From<ferinth::Error> for Error {
+    fn from(err: ferinth::Error) -> Self {
+        if let ferinth::Error::ReqwestError(source) = &err {
+            if Some(StatusCode::NOT_FOUND) == source.status() {
+                Self::DoesNotExist
+            } else if Some(StatusCode::TOO_MANY_REQUESTS) == source.status() {
+                Self::ModrinthRateLimited { reset_at: None }
+            } else {
+                Self::ModrinthError(err)
+            }
+        } else {
+            Self::ModrinthError(err)
+        }
+    }
+}
+
+/// A serialisable report of a project that failed to be added
+///
+/// Can be created from the unsuccessful projects returned by [`add()`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AddFailure {
+    pub identifier: String,
+    pub kind: ErrorKind,
+    /// The error message as it would be displayed to the user
+    pub message: String,
+}
+
+impl From<(String, Error)> for AddFailure {
+    fn from((identifier, error): (String, Error)) -> Self {
+        Self {
+            identifier,
+            kind: error.kind(),
+            message: error.to_string(),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 struct GraphQlResponse {
     data: HashMap<String, Option<ResponseData>>,
@@ -48,17 +465,28 @@ struct GraphQlResponse {
 struct GraphQLError {
     #[serde(rename = "type")]
     type_: String,
+    #[serde(default)]
     path: Vec<String>,
     message: String,
 }
 
 #[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 struct ResponseData {
     owner: OwnerData,
     name: String,
+    is_archived: bool,
+    license_info: Option<LicenseData>,
     releases: ReleaseConnection,
 }
 #[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct LicenseData {
+    spdx_id: Option<String>,
+    name: String,
+    url: Option<url::Url>,
+}
+#[derive(Deserialize, Debug)]
 struct OwnerData {
     login: String,
 }
@@ -83,24 +511,333 @@ struct ReleaseAsset {
     name: String,
 }
 
-pub fn parse_id(id: String) -> ModIdentifier {
-    if let Ok(id) = id.parse() {
-        ModIdentifier::CurseForgeProject(id)
+/// Classify `id` into the platform it is most likely from
+///
+/// Numbers are CurseForge project IDs, `owner/name` is a GitHub repository, and anything else is a Modrinth project.
+/// An `@` followed by a version pins the project to that version, e.g. `sodium@mc1.21-0.6.0`.
+/// Modrinth versions can be a version ID or version number, while CurseForge and GitHub pins must be a file or asset ID.
+///
+/// Surrounding whitespace is ignored, and Modrinth slugs are lowercased.
+/// The case of GitHub repositories is preserved.
+///
+/// Returns [`Error::InvalidIdentifier`] if `id`, its project, or its pin is empty,
+/// or if a CurseForge or GitHub pin isn't an ID.
+pub fn parse_id(id: String) -> Result<ModIdentifier> {
+    let id = id.trim();
+    let invalid = || Error::InvalidIdentifier(id.to_owned());
+    if let Some((project, pin)) = id.split_once('@') {
+        let pin = pin.trim();
+        if pin.is_empty() {
+            return Err(invalid());
+        }
+        match parse_id(project.to_owned()).map_err(|_| invalid())? {
+            ModIdentifier::CurseForgeProject(project) => {
+                Ok(ModIdentifier::PinnedCurseForgeProject(
+                    project,
+                    pin.parse().map_err(|_| invalid())?,
+                ))
+            }
+            ModIdentifier::GitHubRepository(owner, repo) => {
+                Ok(ModIdentifier::PinnedGitHubRepository(
+                    (owner, repo),
+                    pin.parse().map_err(|_| invalid())?,
+                ))
+            }
+            ModIdentifier::ModrinthProject(project) => Ok(ModIdentifier::PinnedModrinthProject(
+                project,
+                pin.to_owned(),
+            )),
+            _ => Err(invalid()),
+        }
+    } else if id.is_empty() {
+        Err(invalid())
+    } else if let Ok(id) = id.parse() {
+        Ok(ModIdentifier::CurseForgeProject(id))
     } else {
-        let split = id.split('/').collect_vec();
-        if split.len() == 2 {
+        let split = id.split('/').map(str::trim).collect_vec();
+        Ok(if split.len() == 2 {
             ModIdentifier::GitHubRepository(split[0].to_owned(), split[1].to_owned())
+        } else if is_modrinth_id(id) {
+            ModIdentifier::ModrinthProject(id.to_owned())
         } else {
-            ModIdentifier::ModrinthProject(id)
+            ModIdentifier::ModrinthProject(id.to_lowercase())
+        })
+    }
+}
+
+/// Parse `id` like [`parse_id()`], or using [`parse_url()`] if it's the URL of a project page
+pub async fn parse_id_or_url(id: String) -> Result<ModIdentifier> {
+    match url::Url::parse(id.trim()) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => parse_url(&url).await,
+        _ => parse_id(id),
+    }
+}
+
+/// The kinds of projects in the paths of Modrinth's project pages, e.g. `https://modrinth.com/mod/sodium`
+const MODRINTH_PROJECT_TYPES: [&str; 7] = [
+    "mod",
+    "plugin",
+    "datapack",
+    "resourcepack",
+    "shader",
+    "modpack",
+    "project",
+];
+
+/// Get the identifier of the project whose page on Modrinth, CurseForge, or GitHub is at `url`
+///
+/// Links to a specific version or file, like `https://modrinth.com/mod/sodium/version/mc1.21-0.6.0`,
+/// are pinned to that version or file.
+/// CurseForge pages are identified by their slug, so their project ID has to be requested from CurseForge.
+///
+/// Returns [`Error::UnknownHost`] if the URL isn't from one of these platforms,
+/// and [`Error::InvalidIdentifier`] if it isn't the URL of a project.
+pub async fn parse_url(url: &url::Url) -> Result<ModIdentifier> {
+    let invalid = || Error::InvalidIdentifier(url.to_string());
+    let host = url.host_str().ok_or_else(invalid)?;
+    let segments = url
+        .path_segments()
+        .into_iter()
+        .flatten()
+        .filter(|segment| !segment.is_empty())
+        .collect_vec();
+
+    match host.trim_start_matches("www.") {
+        "modrinth.com" => match segments.as_slice() {
+            [project_type, ..] if !MODRINTH_PROJECT_TYPES.contains(project_type) => None,
+            [_, project, "version", version, ..] => {
+                parse_id_as(ProviderKind::Modrinth, &format!("{project}@{version}"))
+            }
+            [_, project, ..] => parse_id_as(ProviderKind::Modrinth, project),
+            _ => None,
+        }
+        .ok_or_else(invalid),
+        "github.com" => match segments.as_slice() {
+            [owner, repo, ..] => Ok(ModIdentifier::GitHubRepository(
+                (*owner).to_owned(),
+                repo.trim_end_matches(".git").to_owned(),
+            )),
+            _ => Err(invalid()),
+        },
+        "curseforge.com" => {
+            let (project, file) = match segments.as_slice() {
+                ["projects", project, ..] => {
+                    return project
+                        .parse()
+                        .map(ModIdentifier::CurseForgeProject)
+                        .map_err(|_| invalid())
+                }
+                ["minecraft", class, slug, "files", file, ..] => {
+                    ((*class, *slug), Some(file.parse().map_err(|_| invalid())?))
+                }
+                ["minecraft", class, slug, ..] => ((*class, *slug), None),
+                _ => return Err(invalid()),
+            };
+            let project = curseforge_project_id(project.0, project.1).await?;
+            Ok(match file {
+                Some(file) => ModIdentifier::PinnedCurseForgeProject(project, file),
+                None => ModIdentifier::CurseForgeProject(project),
+            })
+        }
+        host => Err(Error::UnknownHost(host.to_owned())),
+    }
+}
+
+/// Get the ID of the CurseForge project with `slug`, in the class that is at `class` in the project's URL
+#[cfg(feature = "curseforge")]
+async fn curseforge_project_id(class: &str, slug: &str) -> Result<i32> {
+    #[derive(Deserialize)]
+    struct SearchResponse {
+        data: Vec<SearchHit>,
+    }
+    #[derive(Deserialize)]
+    struct SearchHit {
+        id: i32,
+        slug: String,
+    }
+
+    let mut query = vec![
+        ("gameId", CURSEFORGE_MINECRAFT_GAME_ID.to_string()),
+        ("slug", slug.to_owned()),
+    ];
+    // Slugs are only unique within a class
+    if let Some(class_id) = match class {
+        "mc-mods" => Some(CURSEFORGE_MODS_CLASS_ID),
+        "texture-packs" => Some(12),
+        "shaders" => Some(6552),
+        "data-packs" => Some(6945),
+        _ => None,
+    } {
+        query.push(("classId", class_id.to_string()));
+    }
+    let response: SearchResponse = crate::HTTP_CLIENT
+        .get("https://api.curseforge.com/v1/mods/search")
+        .header("x-api-key", crate::curseforge_api_key())
+        .query(&query)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    response
+        .data
+        .into_iter()
+        .find(|hit| hit.slug.eq_ignore_ascii_case(slug))
+        .map(|hit| hit.id)
+        .ok_or(Error::DoesNotExist)
+}
+
+#[cfg(not(feature = "curseforge"))]
+async fn curseforge_project_id(_class: &str, _slug: &str) -> Result<i32> {
+    Err(Error::CurseForgeDisabled)
+}
+
+/// Split a pasted list of identifiers into the individual identifiers, which can then be parsed using [`parse_id()`]
+///
+/// Identifiers can be separated by newlines or commas, and surrounding whitespace is ignored.
+/// A `#` at the start of a line or an identifier, or after whitespace, starts a comment that lasts until the end of the line,
+/// so URLs with fragments and `@version` pins are kept intact.
+pub fn parse_identifier_list(input: &str) -> Vec<String> {
+    input
+        .lines()
+        .map(|line| {
+            let comment = line
+                .char_indices()
+                .find(|&(i, c)| {
+                    c == '#'
+                        && line[..i]
+                            .chars()
+                            .next_back()
+                            .is_none_or(|c| c.is_whitespace() || c == ',')
+                })
+                .map_or(line.len(), |(i, _)| i);
+            &line[..comment]
+        })
+        .flat_map(|line| line.split(','))
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(ToOwned::to_owned)
+        .collect_vec()
+}
+
+/// How identifiers are assigned to platforms by [`parse_id_with_mode()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DispatchMode {
+    /// Use the platform of the `mr:`, `cf:`, or `gh:` prefix if there is one, otherwise guess it like [`parse_id()`]
+    Heuristic,
+    /// Require a `mr:`, `cf:`, or `gh:` prefix
+    Strict,
+    /// Parse every identifier as being from the provided platform
+    Explicit(ProviderKind),
+}
+
+/// Parse `id` into an identifier according to `mode`
+///
+/// Returns [`Error::InvalidIdentifier`] if `id` doesn't have a prefix in strict mode,
+/// or if it doesn't have the format of its platform's identifiers.
+pub fn parse_id_with_mode(id: &str, mode: DispatchMode) -> Result<ModIdentifier> {
+    let invalid = || Error::InvalidIdentifier(id.to_owned());
+    let prefixed = id
+        .trim()
+        .split_once(':')
+        .and_then(|(prefix, rest)| Some((ProviderKind::from_prefix(prefix)?, rest)));
+
+    match (mode, prefixed) {
+        (DispatchMode::Explicit(provider), Some((prefix, rest))) if prefix == provider => {
+            parse_id_as(provider, rest).ok_or_else(invalid)
         }
+        (DispatchMode::Explicit(provider), _) => parse_id_as(provider, id).ok_or_else(invalid),
+        (_, Some((provider, rest))) => parse_id_as(provider, rest).ok_or_else(invalid),
+        (DispatchMode::Heuristic, None) => parse_id(id.to_owned()),
+        (DispatchMode::Strict, None) => Err(invalid()),
     }
 }
 
+/// Parse `id` as an identifier from `provider`, returning `None` if it doesn't have the right format
+/// or if mods can't be added from `provider`
+///
+/// Pins are written with an `@` like in [`parse_id()`].
+pub fn parse_id_as(provider: ProviderKind, id: &str) -> Option<ModIdentifier> {
+    let (project, pin) = match id.split_once('@') {
+        Some((project, pin)) => (project.trim(), Some(pin.trim())),
+        None => (id.trim(), None),
+    };
+    if project.is_empty() || pin.is_some_and(str::is_empty) {
+        return None;
+    }
+
+    Some(match provider {
+        ProviderKind::CurseForge => {
+            let project = project.parse().ok()?;
+            match pin {
+                Some(pin) => ModIdentifier::PinnedCurseForgeProject(project, pin.parse().ok()?),
+                None => ModIdentifier::CurseForgeProject(project),
+            }
+        }
+        ProviderKind::Modrinth => {
+            let project = if is_modrinth_id(project) {
+                project.to_owned()
+            } else {
+                project.to_lowercase()
+            };
+            match pin {
+                Some(pin) => ModIdentifier::PinnedModrinthProject(project, pin.to_owned()),
+                None => ModIdentifier::ModrinthProject(project),
+            }
+        }
+        ProviderKind::GitHub => {
+            let (owner, repo) = project.split_once('/')?;
+            let (owner, repo) = (owner.trim().to_owned(), repo.trim().to_owned());
+            if owner.is_empty() || repo.is_empty() || repo.contains('/') {
+                return None;
+            }
+            match pin {
+                Some(pin) => {
+                    ModIdentifier::PinnedGitHubRepository((owner, repo), pin.parse().ok()?)
+                }
+                None => ModIdentifier::GitHubRepository(owner, repo),
+            }
+        }
+        ProviderKind::GitLab | ProviderKind::Url => return None,
+    })
+}
+
+/// Whether `id` looks like a Modrinth project ID rather than a slug
+///
+/// Project IDs are case-sensitive, so they must not be lowercased like slugs.
+pub(crate) fn is_modrinth_id(id: &str) -> bool {
+    id.len() == 8 && id.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Whether the Modrinth `project` is the one identified by `id`, which can be its project ID or its slug
+fn is_modrinth_project(project: &Project, id: &str) -> bool {
+    project.id == id || project.slug.eq_ignore_ascii_case(id)
+}
+
+/// Get the identifiers in `ids` that kept their case because they looked like a [project ID](is_modrinth_id),
+/// but aren't the ID of any of `projects`, as lowercase slugs
+fn modrinth_slugs_to_retry(ids: &[String], projects: &[Project]) -> Vec<String> {
+    ids.iter()
+        .filter(|id| id.chars().any(|c| c.is_ascii_uppercase()))
+        .filter(|id| {
+            !projects
+                .iter()
+                .any(|project| is_modrinth_project(project, id))
+        })
+        .map(|id| id.to_lowercase())
+        .collect_vec()
+}
+
 /// Adds mods from `identifiers`, and returns successful mods with their names, and unsuccessful mods with an error
 ///
 /// Classifies the `identifiers` into the appropriate platforms, sends batch requests to get the necessary information,
 /// checks details about the projects, and adds them to `profile` if suitable.
+/// The versions of Modrinth projects are also requested in batches, and checked for compatibility locally.
 /// Performs checks on the mods to see whether they're compatible with the profile if `perform_checks` is true
+///
+/// Modrinth projects can be identified by their slug or their project ID, but are always stored using their project ID.
+///
+/// Identifiers from platforms that the profile doesn't [allow](Profile::allows_provider) return [`Error::ProviderNotAllowed`].
 pub async fn add(
     profile: &mut Profile,
     identifiers: Vec<ModIdentifier>,
@@ -108,38 +845,134 @@ pub async fn add(
     override_profile: bool,
     filters: Vec<Filter>,
 ) -> Result<(Vec<String>, Vec<(String, Error)>)> {
+    let (added, errors) = add_with_warnings(
+        profile,
+        identifiers,
+        perform_checks,
+        override_profile,
+        filters,
+    )
+    .await?;
+    Ok((added.into_iter().map(|mod_| mod_.name).collect(), errors))
+}
+
+/// Add the mods listed in the file at `path` like [`add()`]
+///
+/// The file is split into identifiers using [`parse_identifier_list()`], which are parsed using [`parse_id_or_url()`].
+/// Returns [`Error::IOError`] without adding anything if the file can't be read,
+/// while the errors of individual mods are returned along with the successful ones.
+pub async fn add_from_file(
+    profile: &mut Profile,
+    path: &Path,
+    perform_checks: bool,
+    override_profile: bool,
+    filters: Vec<Filter>,
+) -> Result<(Vec<String>, Vec<(String, Error)>)> {
+    let mut identifiers = Vec::new();
+    let mut errors = Vec::new();
+    for id in parse_identifier_list(&read_to_string(path)?) {
+        match parse_id_or_url(id.clone()).await {
+            Ok(identifier) => identifiers.push(identifier),
+            Err(err) => errors.push((id, err)),
+        }
+    }
+    let (added, add_errors) = add(
+        profile,
+        identifiers,
+        perform_checks,
+        override_profile,
+        filters,
+    )
+    .await?;
+    errors.extend(add_errors);
+    Ok((added, errors))
+}
+
+/// Add mods from `identifiers` like [`add()`], but also return the [non-fatal issues](AddWarning) with the successful mods
+pub async fn add_with_warnings(
+    profile: &mut Profile,
+    identifiers: Vec<ModIdentifier>,
+    perform_checks: bool,
+    override_profile: bool,
+    filters: Vec<Filter>,
+) -> Result<(Vec<AddedMod>, Vec<(String, Error)>)> {
+    profile.check_unlocked()?;
     let mut mr_ids = Vec::new();
+    #[cfg(feature = "curseforge")]
     let mut cf_ids = Vec::new();
     let mut gh_ids = Vec::new();
+    let mut pinned_ids = Vec::new();
     let mut errors = Vec::new();
 
     for id in identifiers {
+        if !profile.allows_provider(id.provider()) {
+            errors.push((id.to_string(), Error::ProviderNotAllowed(id.provider())));
+            continue;
+        }
         match id {
+            #[cfg(feature = "curseforge")]
             ModIdentifier::CurseForgeProject(id) => cf_ids.push(id),
+            #[cfg(not(feature = "curseforge"))]
+            ModIdentifier::CurseForgeProject(id) => {
+                errors.push((id.to_string(), Error::CurseForgeDisabled))
+            }
             ModIdentifier::ModrinthProject(id) => mr_ids.push(id),
             ModIdentifier::GitHubRepository(o, r) => gh_ids.push((o, r)),
-
-            _ => todo!("Adding pinned projects is not supported yet"),
+            pinned_id => pinned_ids.push(pinned_id),
         }
     }
 
+    #[cfg(feature = "curseforge")]
     let cf_projects = if !cf_ids.is_empty() {
         cf_ids.sort_unstable();
         cf_ids.dedup();
-        CURSEFORGE_API.get_mods(cf_ids.clone()).await?
+        let projects = CURSEFORGE_API.get_mods(cf_ids.clone()).await?;
+        metadata::cache_curseforge_projects(&projects);
+        projects
     } else {
         Vec::new()
     };
 
-    let mr_projects = if !mr_ids.is_empty() {
+    let mut mr_projects = if !mr_ids.is_empty() {
         mr_ids.sort_unstable();
         mr_ids.dedup();
-        MODRINTH_API
+        match MODRINTH_API
             .get_multiple_projects(&mr_ids.iter().map(AsRef::as_ref).collect_vec())
-            .await?
+            .await
+        {
+            Ok(projects) => projects,
+            Err(err) => return Err(modrinth_error(err).await),
+        }
     } else {
         Vec::new()
     };
+    // Project IDs and slugs look alike, so identifiers that kept their case
+    // because they looked like an ID are retried as slugs if they weren't found
+    let mr_slugs = modrinth_slugs_to_retry(&mr_ids, &mr_projects);
+    if !mr_slugs.is_empty() {
+        match MODRINTH_API
+            .get_multiple_projects(&mr_slugs.iter().map(AsRef::as_ref).collect_vec())
+            .await
+        {
+            Ok(projects) => mr_projects.extend(projects),
+            Err(err) => return Err(modrinth_error(err).await),
+        }
+    }
+    // Both the ID and slug of a project may have been provided
+    mr_projects.sort_unstable_by(|a, b| a.id.cmp(&b.id));
+    mr_projects.dedup_by(|a, b| a.id == b.id);
+    metadata::cache_modrinth_projects(&mr_projects);
+
+    // The versions of all the projects are requested together, rather than listing each project's versions,
+    // before anything is added so that the profile isn't modified if this fails
+    let mut mr_versions = if !mr_projects.is_empty() {
+        match modrinth_versions_of(&mr_projects).await {
+            Ok(versions) => versions,
+            Err(err) => return Err(modrinth_error(err).await),
+        }
+    } else {
+        HashMap::new()
+    };
 
     let gh_repos =
         {
@@ -152,6 +985,12 @@ pub async fn add(
                         login
                     }}
                     name
+                    isArchived
+                    licenseInfo {{
+                        spdxId
+                        name
+                        url
+                    }}
                     releases(first: 100) {{
                         nodes {{
                             name
@@ -171,9 +1010,13 @@ pub async fn add(
 
             // Send the query
             let response: GraphQlResponse = if !gh_ids.is_empty() {
-                GITHUB_API
+                match GITHUB_API
                     .graphql(&HashMap::from([("query", graphql_query)]))
-                    .await?
+                    .await
+                {
+                    Ok(response) => response,
+                    Err(err) => return Err(github_error(err).await),
+                }
             } else {
                 GraphQlResponse {
                     data: HashMap::new(),
@@ -181,6 +1024,11 @@ pub async fn add(
                 }
             };
 
+            // GitHub doesn't return any data for the whole query once the rate limit is exceeded
+            if response.errors.iter().any(|v| v.type_ == "RATE_LIMITED") {
+                return Err(github_error(Error::RateLimited { reset_at: None }).await);
+            }
+
             errors.extend(response.errors.into_iter().map(|v| {
                 (
                     {
@@ -202,9 +1050,34 @@ pub async fn add(
                 .data
                 .into_values()
                 .flatten()
+                .filter(|d| {
+                    let result = check_release_jars(
+                        d.releases.nodes.len(),
+                        d.releases.nodes.iter().flat_map(|release| {
+                            release
+                                .release_assets
+                                .nodes
+                                .iter()
+                                .map(|asset| asset.name.as_str())
+                        }),
+                    );
+                    match result {
+                        Ok(()) => true,
+                        Err(err) => {
+                            errors.push((format!("{}/{}", d.owner.login, d.name), err));
+                            false
+                        }
+                    }
+                })
                 .map(|d| {
                     (
                         (d.owner.login, d.name),
+                        d.is_archived,
+                        d.license_info.map(|license| License {
+                            id: license.spdx_id.unwrap_or_default(),
+                            name: license.name,
+                            url: license.url,
+                        }),
                         d.releases
                             .nodes
                             .into_iter()
@@ -232,6 +1105,7 @@ pub async fn add(
                                             .filter_map(|s| ModLoader::from_str(s).ok())
                                             .collect_vec(),
                                         filename: asset.name,
+                                        version: None,
                                     }
                                 })
                             })
@@ -241,42 +1115,55 @@ pub async fn add(
                 .collect_vec()
         };
 
-    let mut success_names = Vec::new();
+    let mut added = Vec::new();
 
-    for project in cf_projects {
-        if let Some(i) = cf_ids.iter().position(|&id| id == project.id) {
-            cf_ids.swap_remove(i);
-        }
+    #[cfg(feature = "curseforge")]
+    {
+        for project in cf_projects {
+            if let Some(i) = cf_ids.iter().position(|&id| id == project.id) {
+                cf_ids.swap_remove(i);
+            }
 
-        match curseforge(
-            &project,
-            profile,
-            perform_checks,
-            override_profile,
-            filters.clone(),
-        )
-        .await
-        {
-            Ok(_) => success_names.push(project.name),
-            Err(err) => errors.push((format!("{} ({})", project.name, project.id), err)),
+            match curseforge(
+                &project,
+                profile,
+                perform_checks,
+                override_profile,
+                filters.clone(),
+            )
+            .await
+            {
+                Ok(_) => added.push(AddedMod::new(
+                    profile,
+                    project.name,
+                    &ModIdentifier::CurseForgeProject(project.id),
+                    if matches!(
+                        project.status,
+                        furse::structures::mod_structs::ModStatus::Abandoned
+                            | furse::structures::mod_structs::ModStatus::Inactive
+                    ) {
+                        vec![AddWarning::Archived]
+                    } else {
+                        Vec::new()
+                    },
+                )),
+                Err(err) => errors.push((format!("{} ({})", project.name, project.id), err)),
+            }
         }
+        errors.extend(
+            cf_ids
+                .iter()
+                .map(|id| (id.to_string(), Error::DoesNotExist)),
+        );
     }
-    errors.extend(
-        cf_ids
-            .iter()
-            .map(|id| (id.to_string(), Error::DoesNotExist)),
-    );
 
     for project in mr_projects {
-        if let Some(i) = mr_ids
-            .iter()
-            .position(|id| id == &project.id || project.slug.eq_ignore_ascii_case(id))
-        {
-            mr_ids.swap_remove(i);
-        }
+        let versions = mr_versions.remove(&project.id).unwrap_or_default();
+        mr_ids.retain(|id| !is_modrinth_project(&project, id));
 
-        match modrinth(
+        match modrinth_with_versions(
             &project,
+            Ok(versions),
             profile,
             perform_checks,
             override_profile,
@@ -284,7 +1171,16 @@ pub async fn add(
         )
         .await
         {
-            Ok(_) => success_names.push(project.title),
+            Ok(_) => added.push(AddedMod::new(
+                profile,
+                project.title,
+                &ModIdentifier::ModrinthProject(project.id),
+                match project.status {
+                    ProjectStatus::Archived => vec![AddWarning::Archived],
+                    ProjectStatus::Unlisted => vec![AddWarning::Unlisted],
+                    _ => Vec::new(),
+                },
+            )),
             Err(err) => errors.push((format!("{} ({})", project.title, project.id), err)),
         }
     }
@@ -294,7 +1190,7 @@ pub async fn add(
             .map(|id| (id.to_string(), Error::DoesNotExist)),
     );
 
-    for (repo, asset_names) in gh_repos {
+    for (repo, archived, license, asset_names) in gh_repos {
         match github(
             &repo,
             profile,
@@ -304,35 +1200,467 @@ pub async fn add(
         )
         .await
         {
-            Ok(_) => success_names.push(format!("{}/{}", repo.0, repo.1)),
+            Ok(_) => {
+                // The repository may have been recorded as another mod's fallback instead
+                if let Some(mod_) = profile.mods.iter_mut().find(|mod_| {
+                    matches!(
+                        &mod_.identifier,
+                        ModIdentifier::GitHubRepository(owner, name)
+                            if owner == &repo.0 && name == &repo.1,
+                    )
+                }) {
+                    mod_.license = license;
+                }
+                let identifier = ModIdentifier::GitHubRepository(repo.0.clone(), repo.1.clone());
+                added.push(AddedMod::new(
+                    profile,
+                    format!("{}/{}", repo.0, repo.1),
+                    &identifier,
+                    if archived {
+                        vec![AddWarning::Archived]
+                    } else {
+                        Vec::new()
+                    },
+                ))
+            }
             Err(err) => errors.push((format!("{}/{}", repo.0, repo.1), err)),
         }
     }
 
-    Ok((success_names, errors))
+    // Pinned projects are rare, so they are fetched individually
+    for id in pinned_ids {
+        match pinned(&id, profile, override_profile, filters.clone()).await {
+            Ok(name) => added.push(AddedMod::new(profile, name, &id, Vec::new())),
+            Err(err) => errors.push((id.to_string(), err)),
+        }
+    }
+
+    Ok((added, errors))
 }
 
-/// Check if the repo of `repo_handler` exists, releases mods, and is compatible with `profile`.
-/// If so, add it to the `profile`.
+/// Add mods from `identifiers` like [`add()`], along with the mods they require, recursively
 ///
-/// Returns the name of the repository to display to the user
-pub async fn github(
-    id: &(impl AsRef<str> + ToString, impl AsRef<str> + ToString),
+/// Only [required dependencies](Mod::required_dependencies) are added, optional and embedded ones are ignored.
+/// Dependencies that are already in the profile, including as a fallback, are skipped,
+/// and each project is only added once so projects that depend on each other don't recurse forever.
+/// Dependencies are added using the profile's filters, and their [origin](Mod::origin) is the mod that required them.
+/// Returns the names of the mods from `identifiers` that were added, each with the names of the dependencies added for it.
+pub async fn add_with_dependencies(
+    profile: &mut Profile,
+    identifiers: Vec<ModIdentifier>,
+    perform_checks: bool,
+    override_profile: bool,
+    filters: Vec<Filter>,
+) -> Result<(Vec<(String, Vec<String>)>, Vec<(String, Error)>)> {
+    let mut visited = profile
+        .mods
+        .iter()
+        .flat_map(|mod_| [Some(&mod_.identifier), mod_.fallback.as_ref()])
+        .flatten()
+        .map(ModIdentifier::unpinned)
+        .collect::<HashSet<_>>();
+
+    let start = profile.mods.len();
+    let (_, mut errors) = add_with_warnings(
+        profile,
+        identifiers,
+        perform_checks,
+        override_profile,
+        filters,
+    )
+    .await?;
+    let added = start..profile.mods.len();
+    visited.extend(
+        profile.mods[added.clone()]
+            .iter()
+            .map(|mod_| mod_.identifier.unpinned()),
+    );
+
+    let mut results = Vec::new();
+    for index in added {
+        let mut dependencies = Vec::new();
+        let mut dependents = vec![index];
+        while let Some(dependent) = dependents.pop() {
+            let required = profile.mods[dependent]
+                .required_dependencies
+                .iter()
+                .filter(|dependency| visited.insert(dependency.unpinned()))
+                .cloned()
+                .collect_vec();
+            if required.is_empty() {
+                continue;
+            }
+
+            let start = profile.mods.len();
+            let (_, dependency_errors) =
+                add_with_warnings(profile, required, perform_checks, false, Vec::new()).await?;
+            errors.extend(dependency_errors);
+            let of = profile.mods[dependent].name.clone();
+            for index in start..profile.mods.len() {
+                let mod_ = &mut profile.mods[index];
+                mod_.origin = Some(ModOrigin::Dependency { of: of.clone() });
+                dependencies.push(mod_.name.clone());
+                dependents.push(index);
+            }
+        }
+        results.push((profile.mods[index].name.clone(), dependencies));
+    }
+    Ok((results, errors))
+}
+
+/// Add the mod of `identifier` like [`add()`], and return its name
+pub async fn add_single(
+    profile: &mut Profile,
+    identifier: ModIdentifier,
+    perform_checks: bool,
+    override_profile: bool,
+    filters: Vec<Filter>,
+) -> Result<AddedMod> {
+    add_single_with_retries(
+        profile,
+        identifier,
+        perform_checks,
+        override_profile,
+        filters,
+        1,
+    )
+    .await
+    .map(|outcome| AddedMod {
+        name: outcome.name,
+        warnings: outcome.warnings,
+    })
+}
+
+/// Add the mod of `identifier` like [`add_single()`], trying again up to `max_attempts` times in total
+/// if it fails with a [transient](Error::is_transient) error
+///
+/// The attempts are made immediately one after the other, use [`add_single_with_policy()`] to wait between them.
+/// Returns the number of attempts it took and how long they took along with the mod's name.
+pub async fn add_single_with_retries(
+    profile: &mut Profile,
+    identifier: ModIdentifier,
+    perform_checks: bool,
+    override_profile: bool,
+    filters: Vec<Filter>,
+    max_attempts: usize,
+) -> Result<AddOutcome> {
+    add_single_with_policy(
+        profile,
+        identifier,
+        perform_checks,
+        override_profile,
+        filters,
+        RetryPolicy::immediate(max_attempts),
+    )
+    .await
+}
+
+/// Add the mod of `identifier` like [`add_single()`], retrying it according to `policy`
+/// if it fails with a [transient](Error::is_transient) error
+///
+/// Returns the number of attempts it took and how long they took, including the delays, along with the mod's name.
+pub async fn add_single_with_policy(
+    profile: &mut Profile,
+    identifier: ModIdentifier,
+    perform_checks: bool,
+    override_profile: bool,
+    filters: Vec<Filter>,
+    policy: RetryPolicy,
+) -> Result<AddOutcome> {
+    let start = Instant::now();
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let err = match add_with_warnings(
+            profile,
+            vec![identifier.clone()],
+            perform_checks,
+            override_profile,
+            filters.clone(),
+        )
+        .await
+        {
+            Ok((mut added, mut errors)) => match added.pop() {
+                Some(AddedMod { name, warnings }) => {
+                    return Ok(AddOutcome {
+                        name,
+                        warnings,
+                        attempts,
+                        total_duration: start.elapsed(),
+                    })
+                }
+                None => errors.pop().map_or(Error::DoesNotExist, |(_, err)| err),
+            },
+            Err(err) => err,
+        };
+        if !err.is_transient() || attempts >= policy.max_attempts {
+            return Err(err);
+        }
+        tokio::time::sleep(policy.delay(attempts, &err)).await;
+    }
+}
+
+/// Add mods from `identifiers` like [`add()`], retrying according to `policy` if the requests to a platform fail
+/// with a [transient](Error::is_transient) error
+///
+/// The projects, and the versions of Modrinth projects, are requested from each platform together before any mods
+/// are added, so a transient failure of those requests leaves the profile unchanged and the whole call is retried.
+/// Errors of individual mods, such as failing to list a CurseForge project's files, are returned along with
+/// the successful ones without being retried.
+pub async fn add_with_policy(
+    profile: &mut Profile,
+    identifiers: Vec<ModIdentifier>,
+    perform_checks: bool,
+    override_profile: bool,
+    filters: Vec<Filter>,
+    policy: RetryPolicy,
+) -> Result<(Vec<String>, Vec<(String, Error)>)> {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match add(
+            profile,
+            identifiers.clone(),
+            perform_checks,
+            override_profile,
+            filters.clone(),
+        )
+        .await
+        {
+            Err(err) if err.is_transient() && attempts < policy.max_attempts => {
+                tokio::time::sleep(policy.delay(attempts, &err)).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Add the projects in `entries` pinned to their versions, e.g. from a lockfile
+///
+/// Each entry is a project identifier in any form that [`parse_id()`] accepts, and the version to pin it to.
+/// The pinned versions are checked to exist, and entries with invalid pins return [`Error::InvalidIdentifier`].
+/// Returns the successfully added mods' names and the unsuccessful entries with their errors, like [`add()`].
+pub async fn add_many_pinned(
+    profile: &mut Profile,
+    entries: Vec<(String, String)>,
+    override_profile: bool,
+    filters: Vec<Filter>,
+) -> Result<(Vec<String>, Vec<(String, Error)>)> {
+    let mut identifiers = Vec::new();
+    let mut errors = Vec::new();
+    for (project, version) in entries {
+        match parse_id(project.clone())
+            .ok()
+            .and_then(|identifier| identifier.pinned_to(version.trim()))
+        {
+            Some(identifier) => identifiers.push(identifier),
+            None => {
+                let entry = format!("{project}@{version}");
+                errors.push((entry.clone(), Error::InvalidIdentifier(entry)));
+            }
+        }
+    }
+
+    let (success_names, add_errors) =
+        add(profile, identifiers, false, override_profile, filters).await?;
+    errors.extend(add_errors);
+    Ok((success_names, errors))
+}
+
+/// The number of identifiers [`add_resumable()`] adds before recording its progress
+const RESUMABLE_CHUNK_SIZE: usize = 20;
+
+/// Like [`add()`], but records the identifiers that have been processed to the sidecar file at `queue_path`
+///
+/// If the add is interrupted, calling this again with the same `identifiers` and `queue_path`
+/// skips the identifiers that were already processed.
+/// `identifiers` are added in chunks, and `chunk_added` is called with the profile after each one,
+/// which should write the config so that the sidecar file doesn't get ahead of it.
+/// The sidecar file is removed once all the identifiers have been processed.
+pub async fn add_resumable(
+    profile: &mut Profile,
+    identifiers: Vec<ModIdentifier>,
+    perform_checks: bool,
+    override_profile: bool,
+    filters: Vec<Filter>,
+    queue_path: &Path,
+    mut chunk_added: impl FnMut(&Profile) -> std::io::Result<()>,
+) -> Result<(Vec<String>, Vec<(String, Error)>)> {
+    let completed = match read_to_string(queue_path) {
+        Ok(contents) => contents.lines().map(ToOwned::to_owned).collect_vec(),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err.into()),
+    };
+    let remaining = identifiers
+        .into_iter()
+        .filter(|id| !completed.contains(&id.to_string()))
+        .collect_vec();
+
+    let mut queue_file = OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(queue_path)?;
+    let mut success_names = Vec::new();
+    let mut errors = Vec::new();
+    for chunk in remaining.chunks(RESUMABLE_CHUNK_SIZE) {
+        let (successes, failures) = add(
+            profile,
+            chunk.to_vec(),
+            perform_checks,
+            override_profile,
+            filters.clone(),
+        )
+        .await?;
+        success_names.extend(successes);
+        errors.extend(failures);
+        chunk_added(profile)?;
+
+        for id in chunk {
+            writeln!(queue_file, "{id}")?;
+        }
+        queue_file.sync_all()?;
+    }
+
+    remove_file(queue_path)?;
+    Ok((success_names, errors))
+}
+
+/// Fetch the project of the pinned `id`, check if its pinned version exists, and add it to `profile`
+///
+/// Compatibility checks are not performed since the user explicitly chose the version.
+/// Returns the name of the project to display to the user, or [`Error::InvalidIdentifier`] if `id` isn't pinned.
+pub async fn pinned(
+    id: &ModIdentifier,
+    profile: &mut Profile,
+    override_profile: bool,
+    filters: Vec<Filter>,
+) -> Result<String> {
+    profile.check_unlocked()?;
+    Ok(match id {
+        #[cfg(not(feature = "curseforge"))]
+        ModIdentifier::PinnedCurseForgeProject(..) => return Err(Error::CurseForgeDisabled),
+        #[cfg(feature = "curseforge")]
+        ModIdentifier::PinnedCurseForgeProject(project_id, file_id) => {
+            let project = metadata::curseforge_project(*project_id).await?;
+            if check_duplicate(profile, id).await? {
+                return Ok(project.name);
+            }
+            check_curseforge_project(&project, profile)?;
+            CURSEFORGE_API.get_mod_file(project.id, *file_id).await?;
+
+            profile.push_mod(
+                project.name.trim().to_string(),
+                ModIdentifier::PinnedCurseForgeProject(project.id, *file_id),
+                override_profile,
+                filters,
+            );
+            project.name
+        }
+        ModIdentifier::PinnedModrinthProject(project_id, version) => {
+            let project = metadata::modrinth_project(project_id).await?;
+            if check_duplicate(profile, id).await? {
+                return Ok(project.title);
+            }
+            check_modrinth_project(&project, profile)?;
+            let version_id =
+                modrinth_version_id(&project, version, profile, override_profile, &filters).await?;
+
+            profile.push_mod(
+                project.title.trim().to_owned(),
+                ModIdentifier::PinnedModrinthProject(project.id, version_id),
+                override_profile,
+                filters,
+            );
+            project.title
+        }
+        ModIdentifier::PinnedGitHubRepository(repo, asset_id) => {
+            if check_duplicate(profile, id).await? {
+                return Ok(format!("{}/{}", repo.0, repo.1));
+            }
+            check_github_repo(repo, profile)?;
+            if let Err(err) = GITHUB_API
+                .repos(&repo.0, &repo.1)
+                .release_assets()
+                .get(*asset_id as u64)
+                .await
+            {
+                return Err(github_error(err).await);
+            }
+
+            profile.push_mod(
+                repo.1.trim().to_string(),
+                ModIdentifier::PinnedGitHubRepository(repo.clone(), *asset_id),
+                override_profile,
+                filters,
+            );
+            format!("{}/{}", repo.0, repo.1)
+        }
+        _ => return Err(Error::InvalidIdentifier(id.to_string())),
+    })
+}
+
+/// Resolve `version` to the ID of one of the Modrinth `project`'s versions
+///
+/// `version` can be a version ID or a version number.
+/// If multiple versions have the same version number (e.g. for different game versions),
+/// the newest one compatible with `profile` is preferred.
+pub async fn modrinth_version_id(
+    project: &Project,
+    version: &str,
+    profile: &Profile,
+    override_profile: bool,
+    filters: &[Filter],
+) -> Result<String> {
+    if project.versions.iter().any(|id| id == version) {
+        return Ok(version.to_owned());
+    }
+
+    let mut versions = MODRINTH_API
+        .list_versions(&project.id)
+        .await?
+        .into_iter()
+        .filter(|v| v.version_number == version)
+        .collect_vec();
+    if versions.is_empty() {
+        return Err(Error::VersionDoesNotExist(version.to_owned()));
+    }
+
+    let metadata = versions
+        .iter()
+        .map(|v| from_mr_version(v.clone()).0)
+        .collect_vec();
+    // Fall back to the newest version if none of them are compatible
+    let index = check::select_latest(
+        metadata.iter(),
+        compatibility_filters(profile, override_profile, filters),
+    )
+    .await
+    .unwrap_or_default();
+
+    Ok(versions.swap_remove(index).id)
+}
+
+/// Check if the repo of `repo_handler` exists, releases mods, and is compatible with `profile`.
+/// If so, add it to the `profile`.
+///
+/// Returns the name of the repository to display to the user
+pub async fn github(
+    id: &(impl AsRef<str> + ToString, impl AsRef<str> + ToString),
     profile: &mut Profile,
     perform_checks: Option<Vec<Metadata>>,
     override_profile: bool,
     filters: Vec<Filter>,
 ) -> Result<()> {
-    // Check if project has already been added
-    if profile.mods.iter().any(|mod_| {
-        mod_.name.eq_ignore_ascii_case(id.1.as_ref())
-            || matches!(
-                &mod_.identifier,
-                ModIdentifier::GitHubRepository(owner, repo) if owner == id.0.as_ref() && repo == id.1.as_ref(),
-            )
-    }) {
-        return Err(Error::AlreadyAdded);
+    profile.check_unlocked()?;
+    if check_duplicate(
+        profile,
+        &ModIdentifier::GitHubRepository(id.0.to_string(), id.1.to_string()),
+    )
+    .await?
+    {
+        return Ok(());
     }
+    check_github_repo(id, profile)?;
 
     if let Some(download_files) = perform_checks {
         // Check if the repo is compatible
@@ -342,7 +1670,10 @@ pub async fn github(
                 profile.filters.clone()
             } else {
                 [profile.filters.clone(), filters.clone()].concat()
-            },
+            }
+            .into_iter()
+            .filter_map(|filter| filter.for_provider(ProviderKind::GitHub))
+            .collect_vec(),
         )
         .await?;
     }
@@ -358,7 +1689,225 @@ pub async fn github(
     Ok(())
 }
 
-use ferinth::structures::project::{Project, ProjectType};
+/// The directory that Gradle builds mods to, which some repositories commit their builds in
+pub const DEFAULT_GITHUB_CONTENTS_PATH: &str = "build/libs";
+
+/// Like [`github()`], but falls back to the JAR files in the `path` directory of the repository's default branch
+/// if none of its releases are compatible
+///
+/// This is slower since the releases and contents are fetched separately, so it should only be used if the user opts in.
+/// Returns [`Error::NotAMod`] if the repository has no releases, or [`Error::NoReleaseJars`] if none of them have JAR files,
+/// and the directory doesn't have any JAR files either.
+pub async fn github_with_contents(
+    id: &(impl AsRef<str> + ToString, impl AsRef<str> + ToString),
+    profile: &mut Profile,
+    path: &str,
+    perform_checks: bool,
+    override_profile: bool,
+    filters: Vec<Filter>,
+) -> Result<()> {
+    profile.check_unlocked()?;
+    if check_duplicate(
+        profile,
+        &ModIdentifier::GitHubRepository(id.0.to_string(), id.1.to_string()),
+    )
+    .await?
+    {
+        return Ok(());
+    }
+    check_github_repo(id, profile)?;
+    let repo_handler = GITHUB_API.repos(id.0.as_ref(), id.1.as_ref());
+    let check_filters = if override_profile {
+        profile.filters.clone()
+    } else {
+        [profile.filters.clone(), filters.clone()].concat()
+    }
+    .into_iter()
+    .filter_map(|filter| filter.for_provider(ProviderKind::GitHub))
+    .collect_vec();
+
+    let (release_count, releases) = match repo_handler.releases().list().send().await {
+        Ok(page) => (page.items.len(), from_gh_releases(page.items)),
+        Err(err) => return Err(github_error(err).await),
+    };
+    let releases_compatible =
+        check::select_latest(releases.iter().map(|(m, _)| m), check_filters.clone()).await;
+
+    let (result, contents_path) = if releases_compatible.is_ok() {
+        (releases_compatible, None)
+    } else {
+        let contents = match repo_handler.get_content().path(path).send().await {
+            Ok(contents) => from_gh_contents(contents.items),
+            Err(err) => match Error::from(err) {
+                // The directory doesn't exist
+                Error::DoesNotExist => Vec::new(),
+                err => return Err(github_error(err).await),
+            },
+        };
+        if contents.is_empty() {
+            check_release_jars(
+                release_count,
+                releases.iter().map(|(m, _)| m.filename.as_str()),
+            )?;
+            (releases_compatible, None)
+        } else {
+            (
+                check::select_latest(contents.iter().map(|(m, _)| m), check_filters).await,
+                Some(path.to_owned()),
+            )
+        }
+    };
+    let force_added = force_added(result, perform_checks)?;
+
+    let mod_ = profile.push_mod(
+        id.1.as_ref().trim().to_string(),
+        ModIdentifier::GitHubRepository(id.0.to_string(), id.1.to_string()),
+        override_profile,
+        filters,
+    );
+    mod_.force_added = force_added;
+    mod_.github_contents_path = contents_path;
+    mod_.slug = Some(id.1.as_ref().to_lowercase());
+
+    Ok(())
+}
+
+/// Like [`github()`], but only uses the release assets with filenames matching the `asset_filter` regex,
+/// for repositories that publish several mods in each release
+///
+/// Returns [`Error::NoMatchingAssets`] if none of the assets of the repository's releases match the filter.
+pub async fn github_with_asset_filter(
+    id: &(impl AsRef<str> + ToString, impl AsRef<str> + ToString),
+    profile: &mut Profile,
+    asset_filter: &str,
+    perform_checks: bool,
+    override_profile: bool,
+    filters: Vec<Filter>,
+) -> Result<()> {
+    profile.check_unlocked()?;
+    if check_duplicate(
+        profile,
+        &ModIdentifier::GitHubRepository(id.0.to_string(), id.1.to_string()),
+    )
+    .await?
+    {
+        return Ok(());
+    }
+    check_github_repo(id, profile)?;
+    let releases = match GITHUB_API
+        .repos(id.0.as_ref(), id.1.as_ref())
+        .releases()
+        .list()
+        .send()
+        .await
+    {
+        Ok(page) => page.items,
+        Err(err) => return Err(github_error(err).await),
+    };
+    let release_count = releases.len();
+    let releases = from_gh_releases(releases);
+    check_release_jars(
+        release_count,
+        releases.iter().map(|(m, _)| m.filename.as_str()),
+    )?;
+    let releases = filter_assets(releases, asset_filter).map_err(check::Error::from)?;
+    if releases.is_empty() {
+        return Err(Error::NoMatchingAssets(asset_filter.to_owned()));
+    }
+    let check_filters = if override_profile {
+        profile.filters.clone()
+    } else {
+        [profile.filters.clone(), filters.clone()].concat()
+    }
+    .into_iter()
+    .filter_map(|filter| filter.for_provider(ProviderKind::GitHub))
+    .collect_vec();
+    let force_added = force_added(
+        check::select_latest(releases.iter().map(|(m, _)| m), check_filters).await,
+        perform_checks,
+    )?;
+
+    let mod_ = profile.push_mod(
+        id.1.as_ref().trim().to_string(),
+        ModIdentifier::GitHubRepository(id.0.to_string(), id.1.to_string()),
+        override_profile,
+        filters,
+    );
+    mod_.force_added = force_added;
+    mod_.asset_filter = Some(asset_filter.to_owned());
+    mod_.slug = Some(id.1.as_ref().to_lowercase());
+
+    Ok(())
+}
+
+/// Check that the project of `identifier` isn't already in `profile` from another platform, see [`metadata::same_project`]
+///
+/// If the profile's [`DuplicateStrategy`] is [`Fallback`](DuplicateStrategy::Fallback) and the existing mod doesn't have
+/// a fallback yet, `identifier` is recorded as its fallback and `true` is returned. Otherwise [`Error::AlreadyAdded`] is returned.
+/// Mods whose projects don't exist anymore are skipped.
+async fn check_duplicate(profile: &mut Profile, identifier: &ModIdentifier) -> Result<bool> {
+    for index in 0..profile.mods.len() {
+        if profile.mods[index].identifier.provider() == identifier.provider() {
+            continue;
+        }
+        match metadata::same_project(&profile.mods[index].identifier, identifier)
+            .await
+            .map_err(Error::from)
+        {
+            Ok(true) => (),
+            Ok(false) | Err(Error::DoesNotExist | Error::CurseForgeDisabled) => continue,
+            Err(err) => return Err(err),
+        }
+        let mod_ = &mut profile.mods[index];
+        return if profile.duplicate_strategy == DuplicateStrategy::Fallback
+            && mod_.fallback.is_none()
+        {
+            mod_.fallback = Some(identifier.clone());
+            Ok(true)
+        } else {
+            Err(Error::AlreadyAdded)
+        };
+    }
+    Ok(false)
+}
+
+/// Check that some of the `release_count` GitHub releases with assets of `asset_names` have JAR files
+///
+/// Returns [`Error::NotAMod`] if there are no releases, and [`Error::NoReleaseJars`] if none of them have JAR files.
+fn check_release_jars<'a>(
+    release_count: usize,
+    mut asset_names: impl Iterator<Item = &'a str>,
+) -> Result<()> {
+    if release_count == 0 {
+        Err(Error::NotAMod)
+    } else if !asset_names.any(|name| name.ends_with(".jar")) {
+        Err(Error::NoReleaseJars)
+    } else {
+        Ok(())
+    }
+}
+
+/// Check if the repo of `id` has not already been added
+fn check_github_repo(
+    id: &(impl AsRef<str> + ToString, impl AsRef<str> + ToString),
+    profile: &Profile,
+) -> Result<()> {
+    // Check if project has already been added
+    if profile.mods.iter().any(|mod_| {
+        matches!(
+            &mod_.identifier,
+            ModIdentifier::GitHubRepository(owner, repo)
+            | ModIdentifier::PinnedGitHubRepository((owner, repo), _)
+                if owner == id.0.as_ref() && repo == id.1.as_ref(),
+        )
+    }) {
+        Err(Error::AlreadyAdded)
+    } else {
+        Ok(())
+    }
+}
+
+use ferinth::structures::project::{Project, ProjectStatus, ProjectType};
 
 /// Check if the project of `project_id` has not already been added, is a mod, and is compatible with `profile`.
 /// If so, add it to the `profile`.
@@ -369,144 +1918,815 @@ pub async fn modrinth(
     override_profile: bool,
     filters: Vec<Filter>,
 ) -> Result<()> {
+    let versions = MODRINTH_API.list_versions(&project.id).await;
+    modrinth_with_versions(
+        project,
+        versions,
+        profile,
+        perform_checks,
+        override_profile,
+        filters,
+    )
+    .await
+}
+
+/// Add the Modrinth `project` like [`modrinth()`], using its `versions` that have already been requested
+async fn modrinth_with_versions(
+    project: &Project,
+    versions: ferinth::Result<Vec<Version>>,
+    profile: &mut Profile,
+    perform_checks: bool,
+    override_profile: bool,
+    filters: Vec<Filter>,
+) -> Result<()> {
+    profile.check_unlocked()?;
+    if check_duplicate(profile, &ModIdentifier::ModrinthProject(project.id.clone())).await? {
+        return Ok(());
+    }
+    check_modrinth_project(project, profile)?;
+    let content_type = match project.project_type {
+        ProjectType::ResourcePack => ContentType::ResourcePack,
+        ProjectType::Shader => ContentType::ShaderPack,
+        ProjectType::Datapack => ContentType::DataPack,
+        _ => ContentType::Mod,
+    };
+
+    // Check if the project is compatible,
+    // checking every version individually since the project's game versions and loaders
+    // are a union that doesn't say which combinations are actually available
+    let versions = versions?.into_iter().map(from_mr_version).collect_vec();
+    let latest = check::select_latest(
+        versions.iter().map(|(metadata, _)| metadata),
+        content_type.applicable_filters(compatibility_filters(profile, override_profile, &filters)),
+    )
+    .await;
+    let required_dependencies = latest
+        .as_ref()
+        .map(|&i| required_dependencies(&versions[i].1))
+        .unwrap_or_default();
+    let force_added = force_added(latest, perform_checks)?;
+
+    // Add it to the profile
+    let mod_ = profile.push_mod(
+        project.title.trim().to_owned(),
+        ModIdentifier::ModrinthProject(project.id.clone()),
+        override_profile,
+        filters,
+    );
+    mod_.force_added = force_added;
+    mod_.required_dependencies = required_dependencies;
+    mod_.slug = Some(project.slug.clone());
+    mod_.license = Some(License {
+        id: project.license.id.clone(),
+        name: project.license.name.clone(),
+        url: project.license.url.clone(),
+    });
+    mod_.content_type = content_type;
+    Ok(())
+}
+
+/// Check if the Modrinth `project` has not already been added and is a mod,
+/// or a data pack, resource pack, or shader pack if `profile` accepts those
+fn check_modrinth_project(project: &Project, profile: &Profile) -> Result<()> {
     // Check if project has already been added
     if profile.mods.iter().any(|mod_| {
-        mod_.name.eq_ignore_ascii_case(&project.title)
-            || matches!(
-                &mod_.identifier,
-                ModIdentifier::ModrinthProject(id) if id == &project.id,
-            )
+        matches!(
+            &mod_.identifier,
+            ModIdentifier::ModrinthProject(id)
+            | ModIdentifier::PinnedModrinthProject(id, _) if id == &project.id,
+        )
     }) {
         Err(Error::AlreadyAdded)
 
-    // Check if the project is a mod
-    } else if project.project_type != ProjectType::Mod {
-        Err(Error::NotAMod)
+    // Check if the project is of a type the profile accepts
+    } else if !match project.project_type {
+        ProjectType::Mod => true,
+        ProjectType::Datapack => profile.allow_datapacks,
+        ProjectType::ResourcePack => profile.allow_resourcepacks,
+        ProjectType::Shader => profile.allow_shaderpacks,
+        _ => false,
+    } {
+        Err(Error::UnsupportedProjectType(project.project_type.clone()))
 
-    // Check if the project is compatible
+    // Check if the project can be downloaded by the public,
+    // authenticated users can only get the private projects they have access to
+    } else if crate::modrinth_token().is_none()
+        && matches!(
+            project.status,
+            ProjectStatus::Draft
+                | ProjectStatus::Rejected
+                | ProjectStatus::Processing
+                | ProjectStatus::Withheld
+        )
+    {
+        Err(Error::NotPublished(project.status.clone()))
     } else {
-        if perform_checks {
-            check::select_latest(
-                [Metadata {
-                    filename: "".to_owned(),
-                    title: "".to_owned(),
-                    description: "".to_owned(),
-                    game_versions: project.game_versions.clone(),
-                    loaders: project
-                        .loaders
-                        .iter()
-                        .filter_map(|s| ModLoader::from_str(s).ok())
-                        .collect_vec(),
-                    channel: ReleaseChannel::Release,
-                }]
-                .iter(),
-                if override_profile {
-                    profile.filters.clone()
-                } else {
-                    [profile.filters.clone(), filters.clone()].concat()
-                }
-                .iter()
-                .filter(|f| {
-                    matches!(
-                        f,
-                        Filter::GameVersionStrict(_)
-                            | Filter::GameVersionMinor(_)
-                            | Filter::ModLoaderAny(_)
-                            | Filter::ModLoaderPrefer(_)
-                    )
-                })
-                .cloned()
-                .collect_vec(),
-            )
-            .await?;
-        }
-        // Add it to the profile
-        profile.push_mod(
-            project.title.trim().to_owned(),
-            ModIdentifier::ModrinthProject(project.id.clone()),
-            override_profile,
-            filters,
-        );
         Ok(())
     }
 }
 
 /// Check if the mod of `project_id` has not already been added, is a mod, and is compatible with `profile`.
 /// If so, add it to the `profile`.
+#[cfg(feature = "curseforge")]
 pub async fn curseforge(
     project: &furse::structures::mod_structs::Mod,
     profile: &mut Profile,
     perform_checks: bool,
     override_profile: bool,
     filters: Vec<Filter>,
+) -> Result<()> {
+    profile.check_unlocked()?;
+    if check_duplicate(profile, &ModIdentifier::CurseForgeProject(project.id)).await? {
+        return Ok(());
+    }
+    check_curseforge_project(project, profile)?;
+
+    // Check if the mod is compatible
+    let force_added = force_added(
+        check::select_latest(
+            [Metadata {
+                filename: "".to_owned(),
+                title: "".to_owned(),
+                description: "".to_owned(),
+                game_versions: project
+                    .latest_files_indexes
+                    .iter()
+                    .map(|i| i.game_version.clone())
+                    .collect_vec(),
+                loaders: project
+                    .latest_files_indexes
+                    .iter()
+                    .filter_map(|i| {
+                        i.mod_loader
+                            .as_ref()
+                            .and_then(|l| ModLoader::from_str(&format!("{:?}", l)).ok())
+                    })
+                    .collect_vec(),
+                channel: ReleaseChannel::Release,
+                version: None,
+            }]
+            .iter(),
+            compatibility_filters(profile, override_profile, &filters),
+        )
+        .await,
+        perform_checks,
+    )?;
+
+    // Get the dependencies of the latest compatible file
+    let latest_files = project
+        .latest_files
+        .iter()
+        .cloned()
+        .filter_map(|file| try_from_cf_file(file).ok())
+        .collect_vec();
+    let latest_file = check::select_latest(
+        latest_files.iter().map(|(metadata, _)| metadata),
+        compatibility_filters(profile, override_profile, &filters),
+    )
+    .await
+    .ok()
+    .map(|i| &latest_files[i]);
+    let required_dependencies = latest_file
+        .map(|(_, download_data)| required_dependencies(download_data))
+        .unwrap_or_default();
+    let min_java = latest_file.and_then(|(metadata, _)| min_java(&metadata.game_versions));
+
+    let mod_ = profile.push_mod(
+        project.name.trim().to_string(),
+        ModIdentifier::CurseForgeProject(project.id),
+        override_profile,
+        filters,
+    );
+    mod_.force_added = force_added;
+    mod_.required_dependencies = required_dependencies;
+    mod_.slug = Some(project.slug.clone());
+    mod_.min_java = min_java;
+
+    Ok(())
+}
+
+/// Get the lowest Java version in a CurseForge file's `game_versions`, which lists them like `Java 17`
+#[cfg(feature = "curseforge")]
+fn min_java(game_versions: &[String]) -> Option<u8> {
+    game_versions
+        .iter()
+        .filter_map(|version| version.strip_prefix("Java "))
+        .filter_map(|version| version.parse().ok())
+        .min()
+}
+
+/// CurseForge's ID for Minecraft
+#[cfg(feature = "curseforge")]
+pub(crate) const CURSEFORGE_MINECRAFT_GAME_ID: i32 = 432;
+/// CurseForge's ID for the Minecraft: Java Edition "Mods" class
+#[cfg(feature = "curseforge")]
+pub(crate) const CURSEFORGE_MODS_CLASS_ID: usize = 6;
+
+/// Check if the CurseForge `project` has not already been added, can be downloaded by third-parties, and is a mod
+#[cfg(feature = "curseforge")]
+fn check_curseforge_project(
+    project: &furse::structures::mod_structs::Mod,
+    profile: &Profile,
 ) -> Result<()> {
     // Check if project has already been added
     if profile.mods.iter().any(|mod_| {
-        mod_.name.eq_ignore_ascii_case(&project.name)
-            || ModIdentifier::CurseForgeProject(project.id) == mod_.identifier
+        matches!(
+            mod_.identifier,
+            ModIdentifier::CurseForgeProject(id)
+            | ModIdentifier::PinnedCurseForgeProject(id, _) if id == project.id,
+        )
     }) {
         Err(Error::AlreadyAdded)
 
-    // Check if it can be downloaded by third-parties
-    } else if Some(false) == project.allow_mod_distribution {
+    // Check if it can be downloaded by third-parties, or from the CDN if allowed
+    } else if Some(false) == project.allow_mod_distribution
+        && !crate::upgrade::curseforge_edge_fallback()
+    {
         Err(Error::DistributionDenied)
 
-    // Check if the project is a Minecraft mod
-    } else if !project.links.website_url.as_str().contains("mc-mods") {
+    // Check if the project is a Minecraft: Java Edition mod, and not a modpack, plugin, world, etc
+    } else if project.game_id != CURSEFORGE_MINECRAFT_GAME_ID
+        || project.class_id.map_or(
+            !project.links.website_url.as_str().contains("mc-mods"),
+            |class_id| class_id != CURSEFORGE_MODS_CLASS_ID,
+        )
+    {
         Err(Error::NotAMod)
+    } else {
+        Ok(())
+    }
+}
 
-    // Check if the mod is compatible
+/// Get the projects that `download_data` requires, without the versions they are pinned to
+fn required_dependencies(download_data: &DownloadData) -> Vec<ModIdentifier> {
+    let mut dependencies = Vec::new();
+    for dependency in download_data
+        .dependencies
+        .iter()
+        .map(ModIdentifier::unpinned)
+    {
+        if !dependencies.contains(&dependency) {
+            dependencies.push(dependency);
+        }
+    }
+    dependencies
+}
+
+/// Get the game version and mod loader filters that apply to a new mod with `filters`
+fn compatibility_filters(
+    profile: &Profile,
+    override_profile: bool,
+    filters: &[Filter],
+) -> Vec<Filter> {
+    if override_profile {
+        profile.filters.clone()
     } else {
-        if perform_checks {
-            check::select_latest(
-                [Metadata {
-                    filename: "".to_owned(),
-                    title: "".to_owned(),
-                    description: "".to_owned(),
-                    game_versions: project
-                        .latest_files_indexes
-                        .iter()
-                        .map(|i| i.game_version.clone())
-                        .collect_vec(),
-                    loaders: project
-                        .latest_files_indexes
-                        .iter()
-                        .filter_map(|i| {
-                            i.mod_loader
-                                .as_ref()
-                                .and_then(|l| ModLoader::from_str(&format!("{:?}", l)).ok())
-                        })
-                        .collect_vec(),
-                    channel: ReleaseChannel::Release,
-                }]
-                .iter(),
-                if override_profile {
-                    profile.filters.clone()
-                } else {
-                    [profile.filters.clone(), filters.clone()].concat()
-                }
-                .iter()
-                .filter(|f| {
-                    matches!(
-                        f,
-                        Filter::GameVersionStrict(_)
-                            | Filter::GameVersionMinor(_)
-                            | Filter::ModLoaderAny(_)
-                            | Filter::ModLoaderPrefer(_)
-                    )
-                })
-                .cloned()
-                .collect_vec(),
+        [profile.filters.as_slice(), filters].concat()
+    }
+    .into_iter()
+    .filter(|f| {
+        matches!(
+            f,
+            Filter::GameVersionStrict(_)
+                | Filter::GameVersionMinor(_)
+                | Filter::GameVersionSet(_)
+                | Filter::ModLoaderAny(_)
+                | Filter::ModLoaderPrefer(_)
+        )
+    })
+    .collect_vec()
+}
+
+/// Returns whether the mod is being force added, i.e. whether it's incompatible but `perform_checks` is false
+///
+/// Errors that aren't caused by incompatibility are always returned.
+fn force_added(result: check::Result<usize>, perform_checks: bool) -> Result<bool> {
+    match result {
+        Ok(_) => Ok(false),
+        Err(err) if err.is_incompatibility() && !perform_checks => Ok(true),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> Profile {
+        Profile::new(
+            "Profile".to_owned(),
+            "mods".into(),
+            vec!["1.20.1".to_owned()],
+            ModLoader::Fabric,
+        )
+    }
+
+    /// A public Fabric Modrinth project of `project_type` for 1.20.1 and 1.21
+    fn modrinth_project(id: &str, slug: &str, project_type: &str) -> Project {
+        serde_json::from_value(serde_json::json!({
+            "slug": slug,
+            "title": slug,
+            "description": "",
+            "categories": [],
+            "client_side": "required",
+            "server_side": "optional",
+            "body": "",
+            "status": "approved",
+            "requested_status": null,
+            "additional_categories": [],
+            "issues_url": null,
+            "source_url": null,
+            "wiki_url": null,
+            "discord_url": null,
+            "donation_urls": [],
+            "project_type": project_type,
+            "downloads": 0,
+            "icon_url": null,
+            "color": null,
+            "thread_id": null,
+            "monetization_status": null,
+            "id": id,
+            "team": "team",
+            "published": "2023-01-01T00:00:00Z",
+            "updated": "2024-06-01T00:00:00Z",
+            "approved": null,
+            "followers": 0,
+            "license": { "id": "MIT", "name": "MIT License", "url": null },
+            "versions": [],
+            "game_versions": ["1.20.1", "1.21"],
+            "loaders": ["fabric"],
+            "gallery": [],
+        }))
+        .unwrap()
+    }
+
+    /// A CurseForge project of the game `game_id` in the class `class_id`, at `website_url`
+    #[cfg(feature = "curseforge")]
+    fn curseforge_project(
+        game_id: i32,
+        class_id: Option<i32>,
+        website_url: &str,
+    ) -> furse::structures::mod_structs::Mod {
+        serde_json::from_value(serde_json::json!({
+            "id": 900100,
+            "gameId": game_id,
+            "name": "Example",
+            "slug": "example",
+            "links": {
+                "websiteUrl": website_url,
+                "wikiUrl": null,
+                "issuesUrl": null,
+                "sourceUrl": null,
+            },
+            "summary": "",
+            "status": 4,
+            "downloadCount": 0,
+            "isFeatured": false,
+            "primaryCategoryId": 0,
+            "categories": [],
+            "classId": class_id,
+            "authors": [],
+            "logo": null,
+            "screenshots": [],
+            "mainFileId": 0,
+            "latestFiles": [],
+            "latestFilesIndexes": [],
+            "dateCreated": "2023-01-01T00:00:00Z",
+            "dateModified": "2023-01-01T00:00:00Z",
+            "dateReleased": "2023-01-01T00:00:00Z",
+            "allowModDistribution": true,
+            "gamePopularityRank": 0,
+            "isAvailable": true,
+            "thumbsUpCount": 0,
+            "latestEarlyAccessFilesIndexes": [],
+        }))
+        .unwrap()
+    }
+
+    /// A Fabric version of the Modrinth project `project_id` for `game_versions`, which requires `dependencies`
+    fn modrinth_version(
+        project_id: &str,
+        id: &str,
+        game_versions: &[&str],
+        published: &str,
+        dependencies: &[&str],
+    ) -> Version {
+        serde_json::from_value(serde_json::json!({
+            "name": id,
+            "version_number": id,
+            "changelog": null,
+            "dependencies": dependencies.iter().map(|dependency| serde_json::json!({
+                "version_id": null,
+                "project_id": dependency,
+                "file_name": null,
+                "dependency_type": "required",
+            })).collect::<Vec<_>>(),
+            "game_versions": game_versions,
+            "version_type": "release",
+            "loaders": ["fabric"],
+            "featured": false,
+            "status": "listed",
+            "requested_status": null,
+            "id": id,
+            "project_id": project_id,
+            "author_id": "author",
+            "date_published": published,
+            "downloads": 0,
+            "files": [{
+                "hashes": { "sha512": "", "sha1": "" },
+                "url": format!("https://cdn.modrinth.com/data/{project_id}/versions/{id}/{id}.jar"),
+                "filename": format!("{id}.jar"),
+                "primary": true,
+                "size": 1024,
+                "file_type": null,
+            }],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_id_trims() {
+        assert_eq!(
+            parse_id("  sodium \n".to_owned()).unwrap(),
+            ModIdentifier::ModrinthProject("sodium".to_owned())
+        );
+        assert_eq!(
+            parse_id(" 394468 ".to_owned()).unwrap(),
+            ModIdentifier::CurseForgeProject(394468)
+        );
+        assert_eq!(
+            parse_id(" CaffeineMC / sodium ".to_owned()).unwrap(),
+            ModIdentifier::GitHubRepository("CaffeineMC".to_owned(), "sodium".to_owned())
+        );
+    }
+
+    #[test]
+    fn parse_id_uppercase() {
+        // Slugs are lowercased, but project IDs and GitHub repositories keep their case
+        assert_eq!(
+            parse_id("Sodium-Extra".to_owned()).unwrap(),
+            ModIdentifier::ModrinthProject("sodium-extra".to_owned())
+        );
+        assert_eq!(
+            parse_id("AANobbMI".to_owned()).unwrap(),
+            ModIdentifier::ModrinthProject("AANobbMI".to_owned())
+        );
+        assert_eq!(
+            parse_id("CaffeineMC/Sodium".to_owned()).unwrap(),
+            ModIdentifier::GitHubRepository("CaffeineMC".to_owned(), "Sodium".to_owned())
+        );
+    }
+
+    #[tokio::test]
+    async fn parse_id_url() {
+        let parse = |id: &str| parse_id_or_url(id.to_owned());
+        assert_eq!(
+            parse(" https://modrinth.com/mod/Sodium ").await.unwrap(),
+            ModIdentifier::ModrinthProject("sodium".to_owned())
+        );
+        assert_eq!(
+            parse("https://modrinth.com/mod/sodium/version/mc1.21-0.6.0")
+                .await
+                .unwrap(),
+            ModIdentifier::PinnedModrinthProject("sodium".to_owned(), "mc1.21-0.6.0".to_owned())
+        );
+        assert_eq!(
+            parse("https://github.com/CaffeineMC/sodium.git")
+                .await
+                .unwrap(),
+            ModIdentifier::GitHubRepository("CaffeineMC".to_owned(), "sodium".to_owned())
+        );
+        assert_eq!(
+            parse("https://www.curseforge.com/projects/394468")
+                .await
+                .unwrap(),
+            ModIdentifier::CurseForgeProject(394468)
+        );
+        // Without a scheme, URLs aren't recognised
+        assert_eq!(
+            parse("modrinth.com/mod/sodium").await.unwrap(),
+            ModIdentifier::ModrinthProject("modrinth.com/mod/sodium".to_owned())
+        );
+    }
+
+    #[test]
+    fn parse_id_pinned() {
+        assert_eq!(
+            parse_id("sodium@mc1.21-0.6.0".to_owned()).unwrap(),
+            ModIdentifier::PinnedModrinthProject("sodium".to_owned(), "mc1.21-0.6.0".to_owned())
+        );
+        assert_eq!(
+            parse_id(" 394468 @ 5217345 ".to_owned()).unwrap(),
+            ModIdentifier::PinnedCurseForgeProject(394468, 5217345)
+        );
+        assert_eq!(
+            parse_id("CaffeineMC/sodium@123456".to_owned()).unwrap(),
+            ModIdentifier::PinnedGitHubRepository(
+                ("CaffeineMC".to_owned(), "sodium".to_owned()),
+                123456
             )
-            .await?;
+        );
+    }
+
+    #[test]
+    fn parse_id_malformed_pins() {
+        // CurseForge and GitHub pins must be IDs, and neither the project nor the pin can be empty
+        for id in [
+            "394468@latest",
+            "CaffeineMC/sodium@latest",
+            "sodium@",
+            "sodium @ ",
+            "@mc1.21-0.6.0",
+            "",
+        ] {
+            assert!(
+                matches!(parse_id(id.to_owned()), Err(Error::InvalidIdentifier(ref s)) if s == id.trim()),
+                "{id}"
+            );
         }
-        profile.push_mod(
-            project.name.trim().to_string(),
-            ModIdentifier::CurseForgeProject(project.id),
-            override_profile,
-            filters,
+    }
+
+    #[test]
+    fn parse_id_prefixed() {
+        assert_eq!(
+            parse_id_with_mode("mr:Sodium", DispatchMode::Strict).unwrap(),
+            ModIdentifier::ModrinthProject("sodium".to_owned())
+        );
+        assert_eq!(
+            parse_id_with_mode("cf:394468", DispatchMode::Heuristic).unwrap(),
+            ModIdentifier::CurseForgeProject(394468)
+        );
+        // GitLab and direct URLs are recognised, but mods can't be added from them
+        for id in ["gl:owner/example", "url:https://example.com/example.jar"] {
+            assert!(
+                matches!(parse_id_with_mode(id, DispatchMode::Heuristic), Err(Error::InvalidIdentifier(ref s)) if s == id),
+                "{id}"
+            );
+        }
+        assert_eq!(
+            "GitLab".parse::<ProviderKind>().unwrap().icon_hint(),
+            "gitlab"
         );
+        assert_eq!(ProviderKind::Url.base_url(), None);
+    }
 
-        Ok(())
+    #[test]
+    fn identifier_list_mixed_delimiters() {
+        assert_eq!(
+            parse_identifier_list(
+                "sodium, lithium\r\n394468,,\n\n  CaffeineMC/sodium  ,iris@1.7.0\n,"
+            ),
+            [
+                "sodium",
+                "lithium",
+                "394468",
+                "CaffeineMC/sodium",
+                "iris@1.7.0"
+            ]
+        );
+    }
+
+    #[test]
+    fn identifier_list_comments() {
+        let input = "\
+# Performance mods
+sodium # Rendering
+  # lithium
+ferritecore,# krypton
+https://modrinth.com/mod/iris#description
+starlight#1
+";
+        assert_eq!(
+            parse_identifier_list(input),
+            [
+                "sodium",
+                "ferritecore",
+                "https://modrinth.com/mod/iris#description",
+                "starlight#1"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn pinned_rejects_unpinned_identifiers() {
+        let mut profile = profile();
+        let id = ModIdentifier::ModrinthProject("sodium".to_owned());
+        let result = pinned(&id, &mut profile, false, Vec::new()).await;
+        assert!(matches!(result, Err(Error::InvalidIdentifier(ref s)) if *s == id.to_string()));
+        assert!(profile.mods.is_empty());
+    }
+
+    #[tokio::test]
+    async fn pinned_respects_lock() {
+        let mut profile = profile();
+        profile.locked = true;
+        let id = ModIdentifier::PinnedModrinthProject("AANobbMI".to_owned(), "OihdIimA".to_owned());
+        let result = pinned(&id, &mut profile, false, Vec::new()).await;
+        assert!(matches!(result, Err(Error::ProfileLocked)));
+        assert!(profile.mods.is_empty());
+    }
+
+    #[tokio::test]
+    async fn duplicate_from_another_platform() {
+        let curseforge = ModIdentifier::CurseForgeProject(900001);
+        let modrinth = ModIdentifier::ModrinthProject("DupeTest".to_owned());
+        let unrelated = ModIdentifier::ModrinthProject("Unrelated".to_owned());
+        crate::metadata::cache(curseforge.clone(), "Dupe Test", "dupe-test", &["a"]);
+        crate::metadata::cache(modrinth.clone(), "Dupe Test", "dupe-test", &["a"]);
+        crate::metadata::cache(unrelated.clone(), "Unrelated", "unrelated", &["b"]);
+
+        let mut profile = profile();
+        profile.push_mod("Dupe Test".to_owned(), curseforge, false, Vec::new());
+        assert!(!check_duplicate(&mut profile, &unrelated).await.unwrap());
+        assert!(matches!(
+            check_duplicate(&mut profile, &modrinth).await,
+            Err(Error::AlreadyAdded)
+        ));
+
+        profile.duplicate_strategy = DuplicateStrategy::Fallback;
+        assert!(check_duplicate(&mut profile, &modrinth).await.unwrap());
+        assert_eq!(profile.mods[0].fallback, Some(modrinth.clone()));
+        // The mod already has a fallback
+        assert!(matches!(
+            check_duplicate(&mut profile, &modrinth).await,
+            Err(Error::AlreadyAdded)
+        ));
+    }
+
+    #[tokio::test]
+    async fn older_version_supports_game_version() {
+        let project = modrinth_project("DroppedVer", "dropped-version", "mod");
+        // The latest version dropped 1.20.1, but an older one still supports it
+        let versions = vec![
+            modrinth_version("DroppedVer", "new", &["1.21"], "2024-06-01T00:00:00Z", &[]),
+            modrinth_version(
+                "DroppedVer",
+                "old",
+                &["1.20.1"],
+                "2023-06-01T00:00:00Z",
+                &["P7dR8mSH"],
+            ),
+        ];
+
+        let mut profile = profile();
+        modrinth_with_versions(
+            &project,
+            Ok(versions),
+            &mut profile,
+            true,
+            false,
+            Vec::new(),
+        )
+        .await
+        .unwrap();
+        let mod_ = &profile.mods[0];
+        assert!(!mod_.force_added);
+        // The dependencies are those of the older version
+        assert_eq!(
+            mod_.required_dependencies,
+            [ModIdentifier::ModrinthProject("P7dR8mSH".to_owned())]
+        );
+
+        // None of the versions support 1.19.2
+        let versions = vec![modrinth_version(
+            "DroppedVer",
+            "new",
+            &["1.21"],
+            "2024-06-01T00:00:00Z",
+            &[],
+        )];
+        let mut profile = Profile::new(
+            "Profile".to_owned(),
+            "mods".into(),
+            vec!["1.19.2".to_owned()],
+            ModLoader::Fabric,
+        );
+        let result = modrinth_with_versions(
+            &project,
+            Ok(versions),
+            &mut profile,
+            true,
+            false,
+            Vec::new(),
+        )
+        .await;
+        assert!(matches!(result, Err(Error::Incompatible(_))), "{result:?}");
+    }
+
+    #[cfg(feature = "curseforge")]
+    #[test]
+    fn curseforge_non_mods() {
+        let profile = profile();
+        let check = |game_id, class_id, website_url: &str| {
+            check_curseforge_project(
+                &curseforge_project(game_id, class_id, website_url),
+                &profile,
+            )
+        };
+        let mod_url = "https://www.curseforge.com/minecraft/mc-mods/example";
+        assert!(check(432, Some(6), mod_url).is_ok());
+        // Modpacks, worlds, and Bukkit plugins
+        for (class_id, section) in [(4471, "modpacks"), (17, "worlds"), (5, "bukkit-plugins")] {
+            let website_url = format!("https://www.curseforge.com/minecraft/{section}/example");
+            assert!(
+                matches!(
+                    check(432, Some(class_id), &website_url),
+                    Err(Error::NotAMod)
+                ),
+                "{section}"
+            );
+            // Projects without a class are classified by their URL
+            assert!(
+                matches!(check(432, None, &website_url), Err(Error::NotAMod)),
+                "{section}"
+            );
+        }
+        assert!(check(432, None, mod_url).is_ok());
+        // Minecraft: Bedrock Edition
+        assert!(matches!(
+            check(78022, Some(6), mod_url),
+            Err(Error::NotAMod)
+        ));
+    }
+
+    #[tokio::test]
+    async fn modrinth_project_types() {
+        let check = |project_type: &str, profile: &Profile| {
+            check_modrinth_project(
+                &modrinth_project("TypeTest", "type-test", project_type),
+                profile,
+            )
+        };
+        let mut profile = profile();
+        assert!(check("mod", &profile).is_ok());
+        // Resource packs, shaders, and data packs are only accepted if the profile allows them
+        for project_type in ["resourcepack", "shader", "datapack", "modpack", "plugin"] {
+            assert!(
+                matches!(
+                    check(project_type, &profile),
+                    Err(Error::UnsupportedProjectType(_))
+                ),
+                "{project_type}"
+            );
+        }
+        profile.allow_resourcepacks = true;
+        profile.allow_shaderpacks = true;
+        profile.allow_datapacks = true;
+        for project_type in ["mod", "resourcepack", "shader", "datapack"] {
+            assert!(check(project_type, &profile).is_ok(), "{project_type}");
+        }
+        for project_type in ["modpack", "plugin"] {
+            assert!(
+                matches!(
+                    check(project_type, &profile),
+                    Err(Error::UnsupportedProjectType(_))
+                ),
+                "{project_type}"
+            );
+        }
+
+        // Resource packs are added with their content type, and don't need to support the profile's mod loader
+        let project = modrinth_project("ResPackTest", "res-pack-test", "resourcepack");
+        let mut version = modrinth_version(
+            "ResPackTest",
+            "pack",
+            &["1.20.1"],
+            "2024-06-01T00:00:00Z",
+            &[],
+        );
+        version.loaders = vec!["minecraft".to_owned()];
+        modrinth_with_versions(
+            &project,
+            Ok(vec![version]),
+            &mut profile,
+            true,
+            false,
+            Vec::new(),
+        )
+        .await
+        .unwrap();
+        let mod_ = profile.mods.last().unwrap();
+        assert_eq!(mod_.content_type, ContentType::ResourcePack);
+        assert!(!mod_.force_added);
+    }
+
+    #[test]
+    fn modrinth_id_and_slug() {
+        assert!(is_modrinth_id("AANobbMI"));
+        assert!(!is_modrinth_id("sodium"));
+        assert!(!is_modrinth_id("sodium-extra"));
+
+        // Both forms of the identifier resolve to the same project, without retrying either as a slug
+        let sodium = modrinth_project("AANobbMI", "sodium", "mod");
+        let ids = ["AANobbMI", "sodium"].map(ToOwned::to_owned);
+        assert!(ids.iter().all(|id| is_modrinth_project(&sodium, id)));
+        assert!(modrinth_slugs_to_retry(&ids, &[sodium]).is_empty());
+
+        // A slug that looks like a project ID isn't found as an ID, so it's retried as a slug
+        let ids = ["CoolMod1".to_owned()];
+        assert_eq!(modrinth_slugs_to_retry(&ids, &[]), ["coolmod1"]);
+        let cool_mod = modrinth_project("Ab12Cd34", "coolmod1", "mod");
+        assert!(is_modrinth_project(&cool_mod, &ids[0]));
+        assert!(modrinth_slugs_to_retry(&ids, &[cool_mod]).is_empty());
     }
 }
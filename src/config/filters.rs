@@ -1,4 +1,4 @@
-use super::structs::ModLoader;
+use super::structs::{ModLoader, ProviderKind};
 use crate::iter_ext::IterExt as _;
 use derive_more::derive::Display;
 use serde::{Deserialize, Serialize};
@@ -24,10 +24,21 @@ pub enum Filter {
     #[display("Game Version Minor ({})", _0.iter().display(", "))]
     GameVersionMinor(Vec<String>),
 
+    /// Selects files compatible with any of the versions specified, preferring the files that support the most of them
+    ///
+    /// This is for profiles that should work on several versions, e.g. both `1.20.1` and `1.20.4`.
+    /// Implementation detail: Like `ModLoaderPrefer`, this filter only works as intended if it is run on an already filtered list.
+    #[display("Game Version Set ({})", _0.iter().display(", "))]
+    GameVersionSet(Vec<String>),
+
     /// Selects files matching the channel provided or more stable channels
     #[display("Release Channel ({_0})")]
     ReleaseChannel(ReleaseChannel),
 
+    /// Like `ReleaseChannel`, but only applies to files from the given platform
+    #[display("Release Channel on {_0} ({_1})")]
+    ProviderReleaseChannel(ProviderKind, ReleaseChannel),
+
     /// Selects the files with filenames matching the provided regex
     #[display("Filename ({_0})")]
     Filename(String),
@@ -39,14 +50,69 @@ pub enum Filter {
     /// Selects files with descriptions matching the provided regex
     #[display("Description ({_0})")]
     Description(String),
+
+    /// Selects files with version numbers satisfying the semver requirement provided, e.g. `^0.5`, `~1.2.3`, or `>=1.0, <2.0`
+    ///
+    /// Version numbers that aren't semver are matched if they contain the requirement instead,
+    /// see [`Filter::is_substring_match`].
+    #[display("Version Constraint ({_0})")]
+    VersionConstraint(String),
+}
+
+/// The kinds of filters, ignoring their parameters
+#[derive(Deserialize, Serialize, Debug, Display, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum FilterKind {
+    /// `ModLoaderPrefer` and `ModLoaderAny`
+    ModLoader,
+    /// `GameVersionStrict`, `GameVersionMinor`, and `GameVersionSet`
+    GameVersion,
+    /// `ReleaseChannel` and `ProviderReleaseChannel`
+    ReleaseChannel,
+    Filename,
+    Title,
+    Description,
+    VersionConstraint,
+}
+
+impl Filter {
+    pub fn kind(&self) -> FilterKind {
+        match self {
+            Filter::ModLoaderPrefer(_) | Filter::ModLoaderAny(_) => FilterKind::ModLoader,
+            Filter::GameVersionStrict(_)
+            | Filter::GameVersionMinor(_)
+            | Filter::GameVersionSet(_) => FilterKind::GameVersion,
+            Filter::ReleaseChannel(_) | Filter::ProviderReleaseChannel(..) => {
+                FilterKind::ReleaseChannel
+            }
+            Filter::Filename(_) => FilterKind::Filename,
+            Filter::Title(_) => FilterKind::Title,
+            Filter::Description(_) => FilterKind::Description,
+            Filter::VersionConstraint(_) => FilterKind::VersionConstraint,
+        }
+    }
+
+    /// Get the filter that applies to files from `provider`
+    ///
+    /// Returns `None` if this is a `ProviderReleaseChannel` for a different platform,
+    /// and converts it to a `ReleaseChannel` if it's for `provider`.
+    pub fn for_provider(self, provider: ProviderKind) -> Option<Self> {
+        match self {
+            Filter::ProviderReleaseChannel(for_provider, channel) => {
+                (for_provider == provider).then_some(Filter::ReleaseChannel(channel))
+            }
+            filter => Some(filter),
+        }
+    }
 }
 
 pub trait ProfileParameters {
-    /// Get the game versions present, if self has `GameVersionStrict` or `GameVersionMinor`
+    /// Get the game versions present, if self has `GameVersionStrict`, `GameVersionMinor`, or `GameVersionSet`
     fn game_versions(&self) -> Option<&Vec<String>>;
     /// Get the first mod loader present, if self has `ModLoaderPrefer` or `ModLoaderAny`
     fn mod_loader(&self) -> Option<&ModLoader>;
-    /// Get the game versions present, if self has `GameVersionStrict` or `GameVersionMinor`
+    /// Get the mod loaders present in order of preference, if self has `ModLoaderPrefer` or `ModLoaderAny`
+    fn mod_loaders(&self) -> Option<&Vec<ModLoader>>;
+    /// Get the game versions present, if self has `GameVersionStrict`, `GameVersionMinor`, or `GameVersionSet`
     fn game_versions_mut(&mut self) -> Option<&mut Vec<String>>;
     /// Get the mod loaders present, if self has `ModLoaderPrefer` or `ModLoaderAny`
     fn mod_loaders_mut(&mut self) -> Option<&mut Vec<ModLoader>>;
@@ -57,6 +123,7 @@ impl ProfileParameters for Vec<Filter> {
         self.iter().find_map(|filter| match filter {
             Filter::GameVersionStrict(v) => Some(v),
             Filter::GameVersionMinor(v) => Some(v),
+            Filter::GameVersionSet(v) => Some(v),
             _ => None,
         })
     }
@@ -69,10 +136,19 @@ impl ProfileParameters for Vec<Filter> {
         })
     }
 
+    fn mod_loaders(&self) -> Option<&Vec<ModLoader>> {
+        self.iter().find_map(|filter| match filter {
+            Filter::ModLoaderPrefer(v) => Some(v),
+            Filter::ModLoaderAny(v) => Some(v),
+            _ => None,
+        })
+    }
+
     fn game_versions_mut(&mut self) -> Option<&mut Vec<String>> {
         self.iter_mut().find_map(|filter| match filter {
             Filter::GameVersionStrict(v) => Some(v),
             Filter::GameVersionMinor(v) => Some(v),
+            Filter::GameVersionSet(v) => Some(v),
             _ => None,
         })
     }
@@ -98,3 +174,21 @@ pub enum ReleaseChannel {
     Beta,
     Alpha,
 }
+
+/// How to pick between compatible files when the author has marked one as their preferred file
+#[derive(
+    Deserialize, Serialize, Debug, Display, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum,
+)]
+pub enum FileSelection {
+    /// Always select the newest compatible file
+    Latest,
+    /// Select the file the author has marked as the main file if it is compatible,
+    /// and fall back to the newest compatible file otherwise
+    ///
+    /// Currently only CurseForge has such a file, so this behaves like `Latest` on other platforms.
+    #[default]
+    Recommended,
+    /// Like `Recommended`, but also select the newest compatible version the author has featured on Modrinth,
+    /// and fall back to the newest compatible version if none of the featured ones are compatible
+    Featured,
+}
@@ -1,13 +1,18 @@
 pub mod filters;
+pub mod share_code;
 pub mod structs;
 
+use serde_json::{json, Value};
 use std::{
-    fs::{create_dir_all, File},
-    io::{BufReader, Result},
+    fs::{create_dir_all, read_to_string, rename, File},
+    io::{BufWriter, ErrorKind, Result, Write},
     path::{Path, PathBuf},
     sync::LazyLock,
 };
 
+/// The current version of the config's format
+pub const SCHEMA_VERSION: u32 = 1;
+
 pub static DEFAULT_CONFIG_PATH: LazyLock<PathBuf> = LazyLock::new(|| {
     crate::HOME
         .join(".config")
@@ -15,27 +20,135 @@ pub static DEFAULT_CONFIG_PATH: LazyLock<PathBuf> = LazyLock::new(|| {
         .join("config.json")
 });
 
-/// Open the config file at `path` and deserialise it into a config struct
-pub fn read_config(path: impl AsRef<Path>) -> Result<structs::Config> {
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("The config file {0} does not exist")]
+    Missing(PathBuf),
+    /// The config file is not valid JSON, or doesn't match the config's format
+    #[error("The config file is invalid: {0}")]
+    Parse(#[source] serde_json::Error),
+    #[error(transparent)]
+    IOError(#[from] std::io::Error),
+}
+
+impl ConfigError {
+    /// The line of the config file that could not be parsed, starting from 1
+    ///
+    /// This isn't known for some errors that only occur after the JSON was parsed, such as missing fields in older configs.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            ConfigError::Parse(err) if err.line() > 0 => Some(err.line()),
+            _ => None,
+        }
+    }
+
+    /// The column of the [line](ConfigError::line) that could not be parsed, starting from 1
+    pub fn column(&self) -> Option<usize> {
+        match self {
+            ConfigError::Parse(err) if err.line() > 0 => Some(err.column()),
+            _ => None,
+        }
+    }
+}
+
+/// Open the config file at `path` and deserialise it into a config struct,
+/// creating a default config file there if it doesn't exist
+///
+/// Older configs are migrated to the current format in memory, and will be persisted when the config is next written.
+/// Use [`read_and_persist_config`] to write the migrated config immediately.
+pub fn read_config(path: impl AsRef<Path>) -> std::result::Result<structs::Config, ConfigError> {
     if !path.as_ref().exists() {
         create_dir_all(path.as_ref().parent().expect("Invalid config directory"))?;
         write_config(&path, &structs::Config::default())?;
     }
+    read_existing_config(path)
+}
 
-    let config_file = BufReader::new(File::open(&path)?);
-    let mut config: structs::Config = serde_json::from_reader(config_file)?;
-
-    config
-        .profiles
-        .iter_mut()
-        .for_each(structs::Profile::backwards_compat);
+/// Open the config file at `path` and deserialise it into a config struct like [`read_config`],
+/// but return [`ConfigError::Missing`] if it doesn't exist
+pub fn read_existing_config(
+    path: impl AsRef<Path>,
+) -> std::result::Result<structs::Config, ConfigError> {
+    read_and_migrate(path.as_ref()).map(|(config, _)| config)
+}
 
+/// Read the existing config file at `path` like [`read_existing_config`],
+/// and if it was migrated from an older schema version, write the migrated config back to `path`
+///
+/// This way older configs are only migrated once, instead of every time they're read until they're next written.
+pub fn read_and_persist_config(
+    path: impl AsRef<Path>,
+) -> std::result::Result<structs::Config, ConfigError> {
+    let (config, migrated) = read_and_migrate(path.as_ref())?;
+    if migrated {
+        write_config(path, &config)?;
+    }
     Ok(config)
 }
 
+/// Read and [migrate](migrate) the existing config file at `path`, and return whether it needed migrating
+fn read_and_migrate(path: &Path) -> std::result::Result<(structs::Config, bool), ConfigError> {
+    let contents = match read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            return Err(ConfigError::Missing(path.to_owned()))
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    let value: Value = serde_json::from_str(&contents).map_err(ConfigError::Parse)?;
+    let is_current =
+        value.get("schema_version").and_then(Value::as_u64) == Some(SCHEMA_VERSION.into());
+    let config = migrate(value).map_err(|err| {
+        // Errors in the format don't have a position since they occur after the JSON is parsed,
+        // so parse the file directly to find it, which is only possible if it didn't need migrating
+        ConfigError::Parse(
+            is_current
+                .then(|| serde_json::from_str::<structs::Config>(&contents).err())
+                .flatten()
+                .filter(|err| err.line() > 0)
+                .unwrap_or(err),
+        )
+    })?;
+
+    Ok((config, !is_current))
+}
+
 /// Serialise `config` and write it to the config file at `path`
+///
+/// The config is first written to a temporary file next to `path`, which then replaces the config file.
+/// This way the config file is never left half-written if the program is interrupted.
 pub fn write_config(path: impl AsRef<Path>, config: &structs::Config) -> Result<()> {
-    let config_file = File::create(path)?;
-    serde_json::to_writer_pretty(config_file, config)?;
-    Ok(())
+    let temp_path = path.as_ref().with_extension("part");
+
+    let mut temp_file = BufWriter::new(File::create(&temp_path)?);
+    serde_json::to_writer_pretty(&mut temp_file, config)?;
+    temp_file.flush()?;
+    temp_file.get_ref().sync_all()?;
+
+    rename(temp_path, path)
+}
+
+/// Migrate the config `value` from its schema version to [`SCHEMA_VERSION`], and deserialise it
+///
+/// The migrations are applied in order, so a config can be migrated from any older version.
+pub fn migrate(mut value: Value) -> serde_json::Result<structs::Config> {
+    let version = value
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+
+    if let Some(config) = value.as_object_mut() {
+        config.insert("schema_version".to_owned(), json!(SCHEMA_VERSION));
+    }
+    let mut config: structs::Config = serde_json::from_value(value)?;
+
+    if version < 1 {
+        config
+            .profiles
+            .iter_mut()
+            .for_each(structs::Profile::backwards_compat);
+    }
+
+    Ok(config)
 }
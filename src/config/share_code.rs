@@ -0,0 +1,322 @@
+use super::{
+    filters::{Filter, ProfileParameters as _},
+    structs::{ModIdentifier, ModLoader, Profile},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use std::{path::PathBuf, str::FromStr, string::FromUtf8Error};
+
+/// The version of the share code format, which is the first byte of every code
+pub const SHARE_CODE_VERSION: u8 = 1;
+
+const CURSEFORGE_TAG: u8 = 0;
+const MODRINTH_TAG: u8 = 1;
+const GITHUB_TAG: u8 = 2;
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub enum Error {
+    #[error("The share code is not valid base64")]
+    InvalidBase64(#[from] base64::DecodeError),
+    #[error("The share code is version {0}, which is not supported")]
+    UnsupportedVersion(u8),
+    #[error("The share code ended unexpectedly")]
+    Truncated,
+    #[error("The share code contains invalid text")]
+    InvalidText(#[from] FromUtf8Error),
+    #[error("The share code contains an unknown platform {0}")]
+    UnknownProvider(u8),
+    #[error("The profile has {0} game versions, but at most 255 can be shared")]
+    TooManyGameVersions(usize),
+    #[error("The profile has {0} mods, but at most 65535 can be shared")]
+    TooManyMods(usize),
+    #[error("`{0}` is longer than 255 bytes, so it can't be shared")]
+    TooLong(String),
+}
+type Result<T> = std::result::Result<T, Error>;
+
+impl Profile {
+    /// Encode the profile's game versions, mod loader, and mods into a compact, URL-safe code for sharing
+    ///
+    /// Mods are encoded using their unpinned identifiers and names, so pins, filters, and other settings aren't shared.
+    /// Returns an error instead of truncating the profile if it has too many game versions or mods,
+    /// or if any of the strings are longer than 255 bytes.
+    pub fn to_share_code(&self) -> Result<String> {
+        let mut bytes = vec![SHARE_CODE_VERSION];
+        push_str(
+            &mut bytes,
+            &self
+                .filters
+                .mod_loader()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+        )?;
+
+        let game_versions = self.filters.game_versions().cloned().unwrap_or_default();
+        bytes.push(
+            u8::try_from(game_versions.len())
+                .map_err(|_| Error::TooManyGameVersions(game_versions.len()))?,
+        );
+        for version in &game_versions {
+            push_str(&mut bytes, version)?;
+        }
+
+        bytes.extend(
+            u16::try_from(self.mods.len())
+                .map_err(|_| Error::TooManyMods(self.mods.len()))?
+                .to_be_bytes(),
+        );
+        for mod_ in &self.mods {
+            match mod_.identifier.unpinned() {
+                ModIdentifier::CurseForgeProject(id) => {
+                    bytes.push(CURSEFORGE_TAG);
+                    bytes.extend(id.to_be_bytes());
+                }
+                ModIdentifier::ModrinthProject(id) => {
+                    bytes.push(MODRINTH_TAG);
+                    push_str(&mut bytes, &id)?;
+                }
+                ModIdentifier::GitHubRepository(owner, repo) => {
+                    bytes.push(GITHUB_TAG);
+                    push_str(&mut bytes, &owner)?;
+                    push_str(&mut bytes, &repo)?;
+                }
+                _ => unreachable!("The identifier was unpinned"),
+            }
+            push_str(&mut bytes, &mod_.name)?;
+        }
+
+        Ok(URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    /// Get a weak ETag for the profile's [share code](Profile::to_share_code), e.g. to serve the code over HTTP
+    ///
+    /// This is the [content hash](Profile::content_hash) of the profile that the share code decodes to,
+    /// so it only changes when the shared game versions, mod loader, or mods change.
+    /// It's weak since the names of the mods are shared but not hashed.
+    /// Returns the same errors as [`Profile::to_share_code`] if the profile can't be shared.
+    pub fn share_code_etag(&self) -> Result<String> {
+        let shared = Self::from_share_code(&self.to_share_code()?, String::new(), PathBuf::new())?;
+        Ok(format!("W/\"{}\"", shared.content_hash()))
+    }
+
+    /// Decode a code from [`Profile::to_share_code`] into a new profile with `name` and `output_dir`
+    ///
+    /// The mods are added without being checked, so they should be checked before being downloaded.
+    pub fn from_share_code(code: &str, name: String, output_dir: PathBuf) -> Result<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(code.trim())?;
+        let mut reader = ShareCodeReader {
+            bytes: &bytes,
+            position: 0,
+        };
+
+        let version = reader.u8()?;
+        if version != SHARE_CODE_VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let mod_loader = ModLoader::from_str(&reader.string()?).ok();
+        let game_versions = (0..reader.u8()?)
+            .map(|_| reader.string())
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut profile = Profile::new(
+            name,
+            output_dir,
+            game_versions.clone(),
+            mod_loader.unwrap_or(ModLoader::Fabric),
+        );
+        profile.filters.clear();
+        if let Some(mod_loader) = mod_loader {
+            profile
+                .filters
+                .push(Filter::ModLoaderPrefer(match mod_loader {
+                    ModLoader::Quilt => vec![ModLoader::Quilt, ModLoader::Fabric],
+                    _ => vec![mod_loader],
+                }));
+        }
+        if !game_versions.is_empty() {
+            profile
+                .filters
+                .push(Filter::GameVersionStrict(game_versions));
+        }
+
+        for _ in 0..reader.u16()? {
+            let identifier = match reader.u8()? {
+                CURSEFORGE_TAG => ModIdentifier::CurseForgeProject(reader.i32()?),
+                MODRINTH_TAG => ModIdentifier::ModrinthProject(reader.string()?),
+                GITHUB_TAG => ModIdentifier::GitHubRepository(reader.string()?, reader.string()?),
+                tag => return Err(Error::UnknownProvider(tag)),
+            };
+            let name = reader.string()?;
+            profile.push_mod(name, identifier, false, Vec::new());
+        }
+
+        Ok(profile)
+    }
+}
+
+/// Push `string` prefixed by its length, which must fit in a byte
+fn push_str(bytes: &mut Vec<u8>, string: &str) -> Result<()> {
+    let length = u8::try_from(string.len()).map_err(|_| Error::TooLong(string.to_owned()))?;
+    bytes.push(length);
+    bytes.extend(string.as_bytes());
+    Ok(())
+}
+
+/// A cursor over the bytes of a share code
+struct ShareCodeReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl ShareCodeReader<'_> {
+    fn take(&mut self, length: usize) -> Result<&[u8]> {
+        let bytes = self
+            .bytes
+            .get(self.position..self.position + length)
+            .ok_or(Error::Truncated)?;
+        self.position += length;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> Result<i32> {
+        Ok(i32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let length = self.u8()? as usize;
+        Ok(String::from_utf8(self.take(length)?.to_vec())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(mods: &[(&str, ModIdentifier)]) -> Profile {
+        let mut profile = Profile::new(
+            "Profile".to_owned(),
+            "mods".into(),
+            vec!["1.20.1".to_owned()],
+            ModLoader::Fabric,
+        );
+        for (name, identifier) in mods {
+            profile.push_mod((*name).to_owned(), identifier.clone(), false, Vec::new());
+        }
+        profile
+    }
+
+    #[test]
+    fn share_code_round_trip() {
+        let shared = profile(&[
+            (
+                "Sodium",
+                ModIdentifier::ModrinthProject("AANobbMI".to_owned()),
+            ),
+            (
+                "JEI",
+                ModIdentifier::PinnedCurseForgeProject(238222, 4712866),
+            ),
+            (
+                "Example",
+                ModIdentifier::GitHubRepository("owner".to_owned(), "example".to_owned()),
+            ),
+        ]);
+        let decoded = Profile::from_share_code(
+            &shared.to_share_code().unwrap(),
+            "Shared".to_owned(),
+            "out".into(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            decoded.filters.game_versions(),
+            Some(&vec!["1.20.1".to_owned()])
+        );
+        assert_eq!(decoded.filters.mod_loader(), Some(&ModLoader::Fabric));
+        assert_eq!(
+            decoded
+                .mods
+                .iter()
+                .map(|mod_| (mod_.name.as_str(), &mod_.identifier))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    "Sodium",
+                    &ModIdentifier::ModrinthProject("AANobbMI".to_owned())
+                ),
+                // Pins aren't shared
+                ("JEI", &ModIdentifier::CurseForgeProject(238222)),
+                (
+                    "Example",
+                    &ModIdentifier::GitHubRepository("owner".to_owned(), "example".to_owned())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn share_code_etag() {
+        let sodium = ModIdentifier::ModrinthProject("AANobbMI".to_owned());
+        let lithium = ModIdentifier::ModrinthProject("gvQqBUqZ".to_owned());
+        let etag = profile(&[("Sodium", sodium.clone()), ("Lithium", lithium.clone())])
+            .share_code_etag()
+            .unwrap();
+        assert!(etag.starts_with("W/\"") && etag.ends_with('"'));
+
+        // The order of the mods, their names, and settings that aren't shared don't matter
+        let mut reordered = profile(&[("Lithium", lithium.clone()), ("sodium", sodium.clone())]);
+        reordered.mods[1].identifier =
+            ModIdentifier::PinnedModrinthProject("AANobbMI".to_owned(), "OihdIimA".to_owned());
+        reordered.keep_versions = 3;
+        assert_eq!(reordered.share_code_etag().unwrap(), etag);
+
+        // Changing the shared mods or game versions does
+        let removed = profile(&[("Sodium", sodium.clone())]);
+        assert_ne!(removed.share_code_etag().unwrap(), etag);
+        let mut updated = profile(&[("Sodium", sodium), ("Lithium", lithium)]);
+        updated.filters = vec![Filter::GameVersionStrict(vec!["1.21.1".to_owned()])];
+        assert_ne!(updated.share_code_etag().unwrap(), etag);
+    }
+
+    #[test]
+    fn share_code_rejects_truncation() {
+        let sodium = ModIdentifier::ModrinthProject("AANobbMI".to_owned());
+        let long_name = "a".repeat(256);
+        let shared = profile(&[(&long_name, sodium.clone())]);
+        assert!(matches!(shared.to_share_code(), Err(Error::TooLong(name)) if name == long_name));
+        assert!(shared.share_code_etag().is_err());
+        // Names of exactly 255 bytes still fit
+        assert!(profile(&[(&long_name[1..], sodium.clone())])
+            .to_share_code()
+            .is_ok());
+
+        let mut shared = profile(&[]);
+        shared.filters = vec![Filter::GameVersionStrict(
+            (0..256).map(|i| format!("1.{i}")).collect(),
+        )];
+        assert!(matches!(
+            shared.to_share_code(),
+            Err(Error::TooManyGameVersions(256))
+        ));
+
+        let mut shared = profile(&[]);
+        shared.mods = vec![
+            shared
+                .push_mod("Sodium".to_owned(), sodium, false, Vec::new())
+                .clone();
+            65536
+        ];
+        assert!(matches!(
+            shared.to_share_code(),
+            Err(Error::TooManyMods(65536))
+        ));
+    }
+}
@@ -1,10 +1,24 @@
-use super::filters::Filter;
+use super::filters::{Filter, FilterKind};
+use crate::upgrade::MIRRORED_HOSTS;
 use derive_more::derive::Display;
 use serde::{Deserialize, Serialize};
-use std::{path::PathBuf, str::FromStr};
+use sha1::{Digest, Sha1};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{canonicalize, create_dir_all, read_dir, remove_file, File},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use url::Url;
 
-#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Config {
+    /// The version of the config's format, which is used to migrate older configs
+    ///
+    /// Configs from before this was added don't have it, so they are version 0.
+    #[serde(default)]
+    pub schema_version: u32,
+
     #[serde(skip_serializing_if = "is_zero")]
     #[serde(default)]
     pub active_profile: usize,
@@ -22,6 +36,18 @@ pub struct Config {
     pub modpacks: Vec<Modpack>,
 }
 
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            schema_version: super::SCHEMA_VERSION,
+            active_profile: 0,
+            profiles: Vec::new(),
+            active_modpack: 0,
+            modpacks: Vec::new(),
+        }
+    }
+}
+
 const fn is_zero(n: &usize) -> bool {
     *n == 0
 }
@@ -53,7 +79,91 @@ pub struct Profile {
 
     pub mods: Vec<Mod>,
 
-    // Kept for backwards compatibility reasons (i.e. migrating from a v4 config)
+    /// The number of previously installed files to keep for each mod so that upgrades can be rolled back
+    #[serde(skip_serializing_if = "is_zero")]
+    #[serde(default)]
+    pub keep_versions: usize,
+
+    /// Whether Modrinth data packs can be added to this profile
+    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default)]
+    pub allow_datapacks: bool,
+
+    /// Whether Modrinth resource packs can be added to this profile
+    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default)]
+    pub allow_resourcepacks: bool,
+
+    /// Whether Modrinth shader packs can be added to this profile
+    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default)]
+    pub allow_shaderpacks: bool,
+
+    /// The directory to download resource packs to, see [`Profile::output_dir_for`] for the default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub resourcepacks_dir: Option<PathBuf>,
+
+    /// The directory to download shader packs to, see [`Profile::output_dir_for`] for the default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub shaderpacks_dir: Option<PathBuf>,
+
+    /// The directory to download data packs to, see [`Profile::output_dir_for`] for the default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub datapacks_dir: Option<PathBuf>,
+
+    /// The directory of mods that the user manages manually, e.g. mods that can't be downloaded since distribution is denied
+    ///
+    /// See [`Profile::user_mods_path`] for the default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub user_mods_dir: Option<PathBuf>,
+
+    /// The platforms that mods can be added from, all platforms are allowed if this is empty
+    #[serde(skip_serializing_if = "HashSet::is_empty")]
+    #[serde(default)]
+    pub allowed_providers: HashSet<ProviderKind>,
+
+    /// The version of the mod loader the profile's instance uses, e.g. `47.2.0` for Forge
+    ///
+    /// This is used to detect mods that require a newer build of the loader,
+    /// see [`check_loader_version`](crate::loader_version::check_loader_version).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub loader_version: Option<String>,
+
+    /// What to do when a mod that is already in this profile is added from another platform
+    #[serde(skip_serializing_if = "DuplicateStrategy::is_reject")]
+    #[serde(default)]
+    pub duplicate_strategy: DuplicateStrategy,
+
+    /// The mirror to download files from instead of the platforms' CDNs, see [`Profile::rewrite_download_url`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub download_mirror: Option<Url>,
+
+    /// Whether adding, removing, and pinning mods is prevented, e.g. for a curated profile shared by a team
+    ///
+    /// Reading and resolving the profile is still allowed.
+    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default)]
+    pub locked: bool,
+
+    /// Whether to download the optional files of Modrinth versions along with their primary files,
+    /// see [`DownloadData::optional_files`](crate::upgrade::DownloadData::optional_files)
+    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default)]
+    pub include_optional_files: bool,
+
+    /// Whether to modify the profile even though it's [`locked`](Profile::locked)
+    ///
+    /// This is never saved, so the lock has to be explicitly overridden every time the profile is loaded.
+    #[serde(skip)]
+    pub override_lock: bool,
+
+    // Kept for backwards compatibility reasons (i.e. migrating from a config before schema version 1)
     #[serde(skip_serializing)]
     game_version: Option<String>,
     #[serde(skip_serializing)]
@@ -79,12 +189,41 @@ impl Profile {
                 Filter::GameVersionStrict(game_versions),
             ],
             mods: vec![],
+            keep_versions: 0,
+            allow_datapacks: false,
+            allow_resourcepacks: false,
+            allow_shaderpacks: false,
+            resourcepacks_dir: None,
+            shaderpacks_dir: None,
+            datapacks_dir: None,
+            user_mods_dir: None,
+            allowed_providers: HashSet::new(),
+            loader_version: None,
+            duplicate_strategy: DuplicateStrategy::Reject,
+            download_mirror: None,
+            locked: false,
+            include_optional_files: false,
+            override_lock: false,
             game_version: None,
             mod_loader: None,
         }
     }
 
-    /// Convert the v4 profile's `game_version` and `mod_loader` fields into filters
+    /// Duplicate this profile with all its mods and settings, but with a different `name` and `output_dir`
+    ///
+    /// The mods' install histories are cleared since those files are in this profile's output directory.
+    pub fn clone_as(&self, name: impl Into<String>, output_dir: PathBuf) -> Self {
+        let mut clone = self.clone();
+        clone.name = name.into();
+        clone.output_dir = output_dir;
+        for mod_ in &mut clone.mods {
+            mod_.history.clear();
+        }
+        clone
+    }
+
+    /// Convert the `game_version` and `mod_loader` fields of profiles from before schema version 1,
+    /// which were written by ferium v4, into filters
     pub(crate) fn backwards_compat(&mut self) {
         if let (Some(version), Some(loader)) = (self.game_version.take(), self.mod_loader.take()) {
             self.filters = vec![
@@ -104,22 +243,581 @@ impl Profile {
         }
     }
 
+    /// Return [`ProfileLocked`] if the profile is [`locked`](Profile::locked) and the lock isn't overridden
+    pub fn check_unlocked(&self) -> Result<(), ProfileLocked> {
+        if self.locked && !self.override_lock {
+            Err(ProfileLocked)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Add a new mod to the profile, and return it so that other fields can be set
     pub fn push_mod(
         &mut self,
         name: String,
         identifier: ModIdentifier,
         override_filters: bool,
         filters: Vec<Filter>,
-    ) {
-        self.mods.push(Mod {
-            name,
-            identifier,
-            filters,
-            override_filters,
-            check_game_version: None,
-            check_mod_loader: None,
+    ) -> &mut Mod {
+        self.mods
+            .push(Mod::new(name, identifier, filters, override_filters));
+        self.mods.last_mut().unwrap()
+    }
+
+    /// Get the directory that files of `content_type` are downloaded to
+    ///
+    /// Directories that aren't set default to a sibling of the output directory with the same name as the field,
+    /// e.g. `.minecraft/resourcepacks` if the output directory is `.minecraft/mods`.
+    pub fn output_dir_for(&self, content_type: ContentType) -> PathBuf {
+        let (dir, default) = match content_type {
+            ContentType::Mod => return self.output_dir.clone(),
+            ContentType::ResourcePack => (&self.resourcepacks_dir, "resourcepacks"),
+            ContentType::ShaderPack => (&self.shaderpacks_dir, "shaderpacks"),
+            ContentType::DataPack => (&self.datapacks_dir, "datapacks"),
+        };
+        dir.clone().unwrap_or_else(|| {
+            self.output_dir
+                .parent()
+                .unwrap_or(&self.output_dir)
+                .join(default)
+        })
+    }
+
+    /// Get the directory of mods that the user manages manually, which defaults to `user` in the output directory
+    ///
+    /// Files in this directory are never downloaded, upgraded, or removed.
+    pub fn user_mods_path(&self) -> PathBuf {
+        self.user_mods_dir
+            .clone()
+            .unwrap_or_else(|| self.output_dir.join("user"))
+    }
+
+    /// Get the JAR files in the [directory of manually managed mods](Profile::user_mods_path)
+    ///
+    /// Nothing is returned if the directory doesn't exist or can't be read.
+    pub fn user_mods(&self) -> Vec<PathBuf> {
+        let Ok(entries) = read_dir(self.user_mods_path()) else {
+            return Vec::new();
+        };
+        let mut files = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.is_file() && path.extension().is_some_and(|extension| extension == "jar")
+            })
+            .collect::<Vec<_>>();
+        files.sort_unstable();
+        files
+    }
+
+    /// Whether `path` is in the [directory of manually managed mods](Profile::user_mods_path), so it shouldn't be managed
+    pub fn is_user_mod(&self, path: impl AsRef<Path>) -> bool {
+        path.as_ref().starts_with(self.user_mods_path())
+    }
+
+    /// Whether `loader` is one of the mod loaders in the profile's filters
+    pub fn uses_loader(&self, loader: ModLoader) -> bool {
+        self.filters.iter().any(|filter| match filter {
+            Filter::ModLoaderPrefer(loaders) | Filter::ModLoaderAny(loaders) => {
+                loaders.contains(&loader)
+            }
+            _ => false,
         })
     }
+
+    /// Whether mods from `provider` can be added to this profile
+    pub fn allows_provider(&self, provider: ProviderKind) -> bool {
+        self.allowed_providers.is_empty() || self.allowed_providers.contains(&provider)
+    }
+
+    /// Get the mod matching `identifier`, using [`Mod::matches`]
+    pub fn find_mod(&self, identifier: &str) -> Option<&Mod> {
+        self.mods.iter().find(|mod_| mod_.matches(identifier))
+    }
+
+    /// Get the mod matching `identifier` mutably, using [`Mod::matches`]
+    pub fn find_mod_mut(&mut self, identifier: &str) -> Option<&mut Mod> {
+        self.mods.iter_mut().find(|mod_| mod_.matches(identifier))
+    }
+
+    /// Get the mods that were added even though they were incompatible
+    pub fn force_added_mods(&self) -> impl Iterator<Item = &Mod> {
+        self.mods.iter().filter(|mod_| mod_.force_added)
+    }
+
+    /// Enable or disable the mod matching `identifier`
+    ///
+    /// Returns false if no mod matched `identifier`.
+    pub fn set_enabled(&mut self, identifier: &str, enabled: bool) -> bool {
+        if let Some(mod_) = self.find_mod_mut(identifier) {
+            mod_.enabled = enabled;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get a hex encoded SHA-1 hash of the settings and mods that determine which files the profile resolves to
+    ///
+    /// The hash changes whenever one of these settings changes, so if it hasn't changed the profile only needs to be
+    /// resolved again to pick up new files of its unpinned mods. It doesn't depend on the order of the mods or filters,
+    /// and is stable across serialisation round-trips. It hashes the settings' JSON, so it can differ between versions
+    /// of this crate. It's also used for the [ETags of share codes](Profile::share_code_etag).
+    ///
+    /// These fields contribute to the hash:
+    /// - The profile's `filters`, `allowed_providers`, `download_mirror`, and `include_optional_files`
+    /// - The `identifier` (including its pin), `fallback`, `enabled`, `filters`, `override_filters`, `ignored_filters`,
+    ///   `allow_prereleases`, `github_contents_path`, `asset_filter`, and `content_type` of each mod
+    ///
+    /// The other fields, such as names, directories, notes, tags, and install histories, don't.
+    pub fn content_hash(&self) -> String {
+        // Serialise each of `values` and sort them so that their order doesn't matter
+        fn sorted<T: Serialize>(values: impl IntoIterator<Item = T>) -> Vec<String> {
+            let mut values = values
+                .into_iter()
+                .map(|value| serde_json::json!(value).to_string())
+                .collect::<Vec<_>>();
+            values.sort_unstable();
+            values
+        }
+
+        let mods = sorted(self.mods.iter().map(|mod_| {
+            serde_json::json!({
+                "identifier": mod_.identifier,
+                "fallback": mod_.fallback,
+                "enabled": mod_.enabled,
+                "filters": sorted(&mod_.filters),
+                "override_filters": mod_.override_filters,
+                "ignored_filters": sorted(&mod_.ignored_filters),
+                "allow_prereleases": mod_.allow_prereleases,
+                "github_contents_path": mod_.github_contents_path,
+                "asset_filter": mod_.asset_filter,
+                "content_type": mod_.content_type,
+            })
+        }));
+        let content = serde_json::json!({
+            "filters": sorted(&self.filters),
+            "allowed_providers": sorted(&self.allowed_providers),
+            "download_mirror": self.download_mirror,
+            "include_optional_files": self.include_optional_files,
+            "mods": mods,
+        });
+        format!("{:x}", Sha1::digest(content.to_string()))
+    }
+
+    /// Whether the profile doesn't have any mods
+    pub fn is_empty(&self) -> bool {
+        self.mods.is_empty()
+    }
+
+    /// Get the number of mods in the profile, including disabled ones
+    pub fn len(&self) -> usize {
+        self.mods.len()
+    }
+
+    /// Get all the mods in the profile, including disabled ones
+    ///
+    /// ```
+    /// # use libium::config::structs::{ModIdentifier, ModLoader, Mod, Profile};
+    /// let mut profile = Profile::new(
+    ///     "Profile".to_owned(),
+    ///     "mods".into(),
+    ///     vec!["1.21.1".to_owned()],
+    ///     ModLoader::Fabric,
+    /// );
+    /// assert!(profile.is_empty());
+    ///
+    /// profile.mods.push(Mod::new(
+    ///     "Sodium".to_owned(),
+    ///     ModIdentifier::ModrinthProject("AANobbMI".to_owned()),
+    ///     Vec::new(),
+    ///     false,
+    /// ));
+    /// assert_eq!(profile.len(), 1);
+    /// assert_eq!(profile.iter().map(|mod_| mod_.name.as_str()).collect::<Vec<_>>(), ["Sodium"]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = &Mod> {
+        self.mods.iter()
+    }
+
+    /// Get the mods that were added to the profile from `origin`, e.g. to remove all the mods from a modpack
+    pub fn mods_from_origin<'a>(
+        &'a self,
+        origin: &'a ModOrigin,
+    ) -> impl Iterator<Item = &'a Mod> + 'a {
+        self.mods
+            .iter()
+            .filter(move |mod_| mod_.origin.as_ref() == Some(origin))
+    }
+
+    /// Get the mods that should be downloaded and upgraded
+    pub fn enabled_mods(&self) -> impl Iterator<Item = &Mod> {
+        self.mods.iter().filter(|mod_| mod_.enabled)
+    }
+
+    /// Get the mods that are kept in the profile, but should not be downloaded or upgraded
+    pub fn disabled_mods(&self) -> impl Iterator<Item = &Mod> {
+        self.mods.iter().filter(|mod_| !mod_.enabled)
+    }
+
+    /// Remove the pins of all the mods in the profile
+    ///
+    /// Returns the number of mods that were unpinned, or [`ProfileLocked`] if the profile is locked.
+    pub fn unpin_all(&mut self) -> Result<usize, ProfileLocked> {
+        self.check_unlocked()?;
+        let mut count = 0;
+        for mod_ in &mut self.mods {
+            if mod_.identifier.is_pinned() {
+                mod_.identifier = mod_.identifier.unpinned();
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Pin the mod matching `identifier` to `file_id`, so that it always resolves to that file instead of being upgraded
+    ///
+    /// `file_id` is a CurseForge file ID, Modrinth version ID, or GitHub release asset ID, like in [`ModIdentifier::pinned_to`].
+    /// The file isn't checked against the profile's filters since pinning overrides them,
+    /// use [`pinned_file_conflicts`](crate::upgrade::mod_downloadable::pinned_file_conflicts) to warn the user about that.
+    /// Returns false if no mod matches `identifier` or `file_id` is not valid for its platform,
+    /// or [`ProfileLocked`] if the profile is locked.
+    pub fn pin_mod(&mut self, identifier: &str, file_id: &str) -> Result<bool, ProfileLocked> {
+        self.check_unlocked()?;
+        let Some(mod_) = self.mods.iter_mut().find(|mod_| mod_.matches(identifier)) else {
+            return Ok(false);
+        };
+        let Some(pinned) = mod_.identifier.pinned_to(file_id) else {
+            return Ok(false);
+        };
+        mod_.identifier = pinned;
+        Ok(true)
+    }
+
+    /// Remove the pin of the mod matching `identifier`, so that it is upgraded again
+    ///
+    /// Returns false if no mod matches `identifier` or it isn't pinned, or [`ProfileLocked`] if the profile is locked.
+    pub fn unpin_mod(&mut self, identifier: &str) -> Result<bool, ProfileLocked> {
+        self.check_unlocked()?;
+        let Some(mod_) = self
+            .mods
+            .iter_mut()
+            .find(|mod_| mod_.matches(identifier) && mod_.identifier.is_pinned())
+        else {
+            return Ok(false);
+        };
+        mod_.identifier = mod_.identifier.unpinned();
+        Ok(true)
+    }
+
+    /// Record that `file` was installed for the mod matching `identifier`
+    ///
+    /// The files that were installed before it are kept in the mod's history, up to `keep_versions` of them.
+    /// Returns false if no mod matches `identifier`.
+    pub fn record_installed(&mut self, identifier: &str, file: InstalledFile) -> bool {
+        let keep_versions = self.keep_versions;
+        let Some(mod_) = self.find_mod_mut(identifier) else {
+            return false;
+        };
+        if mod_.history.last() != Some(&file) {
+            mod_.history.push(file);
+        }
+        let excess = mod_.history.len().saturating_sub(keep_versions + 1);
+        mod_.history.drain(..excess);
+        true
+    }
+
+    /// Roll the mod matching `identifier` back to the file installed before its current one
+    ///
+    /// The mod is pinned to the previous file, and the current file is removed from its history.
+    /// Returns the previous file, or `None` if no mod matches `identifier` or there is no previous file.
+    /// Returns [`ProfileLocked`] if the profile is locked, since the mod is pinned.
+    pub fn rollback(&mut self, identifier: &str) -> Result<Option<InstalledFile>, ProfileLocked> {
+        self.check_unlocked()?;
+        let Some(mod_) = self.find_mod_mut(identifier) else {
+            return Ok(None);
+        };
+        if mod_.history.len() < 2 {
+            return Ok(None);
+        }
+        let previous = mod_.history[mod_.history.len() - 2].clone();
+        let Some(pinned) = mod_.identifier.pinned_to(&previous.file_id) else {
+            return Ok(None);
+        };
+        mod_.identifier = pinned;
+        mod_.history.pop();
+        Ok(Some(previous))
+    }
+
+    /// Point `url` to the profile's download mirror if it's on one of the [`MIRRORED_HOSTS`]
+    ///
+    /// Only the scheme, host, and port are replaced, so the mirror must serve the files at the same paths.
+    /// Other URLs, and all URLs if the profile has no mirror, are returned unchanged.
+    pub fn rewrite_download_url(&self, mut url: Url) -> Url {
+        let Some(mirror) = &self.download_mirror else {
+            return url;
+        };
+        if !url
+            .host_str()
+            .is_some_and(|host| MIRRORED_HOSTS.contains(&host))
+        {
+            return url;
+        }
+        let original = url.clone();
+        if url.set_scheme(mirror.scheme()).is_err()
+            || url.set_host(mirror.host_str()).is_err()
+            || url.set_port(mirror.port()).is_err()
+        {
+            return original;
+        }
+        url
+    }
+
+    /// Trim the history of every mod to the current file and the `keep_versions` files before it
+    ///
+    /// Histories can be longer than the limit if `keep_versions` was lowered after the files were installed.
+    /// A mod's history is stored with it, so removing a mod already removes its history.
+    /// Returns the number of entries that were removed.
+    pub fn prune_history(&mut self) -> usize {
+        let keep_versions = self.keep_versions;
+        let mut pruned = 0;
+        for mod_ in &mut self.mods {
+            let excess = mod_.history.len().saturating_sub(keep_versions + 1);
+            mod_.history.drain(..excess);
+            pruned += excess;
+        }
+        pruned
+    }
+
+    /// Set the note of the mod matching `identifier`, or remove it if `note` is `None` or empty
+    ///
+    /// Returns false if no mod matches `identifier`.
+    pub fn set_note(&mut self, identifier: &str, note: Option<String>) -> bool {
+        let Some(mod_) = self.find_mod_mut(identifier) else {
+            return false;
+        };
+        mod_.note = note.filter(|note| !note.trim().is_empty());
+        true
+    }
+
+    /// Set the kinds of the profile's filters that are ignored for the mod matching `identifier`
+    ///
+    /// Returns false if no mod matches `identifier`.
+    pub fn set_ignored_filters(&mut self, identifier: &str, ignored: Vec<FilterKind>) -> bool {
+        let Some(mod_) = self.find_mod_mut(identifier) else {
+            return false;
+        };
+        mod_.ignored_filters = ignored;
+        true
+    }
+
+    /// Set whether the GitHub pre-releases of the mod matching `identifier` are treated as releases
+    ///
+    /// Returns false if no mod matches `identifier`.
+    pub fn set_allow_prereleases(&mut self, identifier: &str, allow: bool) -> bool {
+        let Some(mod_) = self.find_mod_mut(identifier) else {
+            return false;
+        };
+        mod_.allow_prereleases = allow;
+        true
+    }
+
+    /// Get the note of the mod matching `identifier`, if it has one
+    pub fn note(&self, identifier: &str) -> Option<&str> {
+        self.find_mod(identifier)?.note.as_deref()
+    }
+
+    /// Add `tag` to every mod that `predicate` returns true for, e.g. all the pinned mods
+    ///
+    /// Returns the number of mods that didn't already have the tag.
+    pub fn tag_where(&mut self, predicate: impl Fn(&Mod) -> bool, tag: &str) -> usize {
+        let mut tagged = 0;
+        for mod_ in self.mods.iter_mut().filter(|mod_| predicate(mod_)) {
+            if !mod_.tags.iter().any(|t| t == tag) {
+                mod_.tags.push(tag.to_owned());
+                tagged += 1;
+            }
+        }
+        tagged
+    }
+
+    /// Get the mods in the profile that have `tag`
+    pub fn tagged<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a Mod> {
+        self.mods
+            .iter()
+            .filter(move |mod_| mod_.tags.iter().any(|t| t == tag))
+    }
+
+    /// Get the highest [minimum Java version](Mod::min_java) of the enabled mods in the profile
+    ///
+    /// Returns `None` if none of the mods declare a requirement.
+    pub fn max_required_java(&self) -> Option<u8> {
+        self.mods
+            .iter()
+            .filter(|mod_| mod_.enabled)
+            .filter_map(|mod_| mod_.min_java)
+            .max()
+    }
+
+    /// Get the mods in the profile that require the mod matching `identifier`
+    ///
+    /// This should be checked before removing a mod, since the mods returned would break without it.
+    /// Returns an empty list if no mod matches `identifier`.
+    pub fn dependents_of(&self, identifier: &str) -> Vec<&Mod> {
+        let Some(target) = self.find_mod(identifier) else {
+            return Vec::new();
+        };
+        let target = target.identifier.unpinned();
+        self.mods
+            .iter()
+            .filter(|mod_| {
+                mod_.required_dependencies
+                    .iter()
+                    .any(|dependency| dependency.unpinned() == target)
+            })
+            .collect()
+    }
+
+    /// Remove the mod matching `identifier`, and find the mods that were only in the profile as its dependencies
+    ///
+    /// The orphaned dependencies are not removed, so that the user can confirm removing them too.
+    /// Dependencies of the orphans that nothing else requires are also orphans.
+    /// Returns `None` if no mod matches `identifier`, or [`ProfileLocked`] if the profile is locked.
+    pub fn remove_mod_with_orphans(
+        &mut self,
+        identifier: &str,
+    ) -> Result<Option<RemovalPlan>, ProfileLocked> {
+        self.check_unlocked()?;
+        let Some(index) = self.mods.iter().position(|mod_| mod_.matches(identifier)) else {
+            return Ok(None);
+        };
+        let removed = self.mods.remove(index);
+
+        // The mods that would be removed, whose dependencies may be orphaned
+        let mut removing = vec![removed.identifier.unpinned()];
+        let mut orphans = Vec::new();
+        while let Some(id) = removing.pop() {
+            let dependencies = if id == removed.identifier.unpinned() {
+                &removed.required_dependencies
+            } else {
+                match self
+                    .mods
+                    .iter()
+                    .find(|mod_| mod_.identifier.unpinned() == id)
+                {
+                    Some(mod_) => &mod_.required_dependencies,
+                    None => continue,
+                }
+            };
+            for dependency in dependencies.iter().map(ModIdentifier::unpinned) {
+                let Some(dependency_mod) = self
+                    .mods
+                    .iter()
+                    .find(|mod_| mod_.identifier.unpinned() == dependency)
+                else {
+                    continue;
+                };
+                let still_required = self.mods.iter().any(|mod_| {
+                    !orphans.contains(&mod_.name)
+                        && mod_
+                            .required_dependencies
+                            .iter()
+                            .any(|other| other.unpinned() == dependency)
+                });
+                if !still_required && !orphans.contains(&dependency_mod.name) {
+                    orphans.push(dependency_mod.name.clone());
+                    removing.push(dependency);
+                }
+            }
+        }
+
+        Ok(Some(RemovalPlan { removed, orphans }))
+    }
+
+    /// Check the profile for problems that could cause files to be written to the wrong place
+    pub fn validate(&self) -> Vec<ProfileIssue> {
+        let mut issues = Vec::new();
+
+        // Resolve the output directory and compare it to where it would be without following any links
+        if let (Ok(resolved), Some(parent), Some(name)) = (
+            canonicalize(&self.output_dir),
+            self.output_dir.parent(),
+            self.output_dir.file_name(),
+        ) {
+            let expected = canonicalize(if parent.as_os_str().is_empty() {
+                ".".as_ref()
+            } else {
+                parent
+            })
+            .map(|parent| parent.join(name));
+            if expected.is_ok_and(|expected| expected != resolved) {
+                issues.push(ProfileIssue::OutputDirLinked { resolved });
+            }
+        }
+
+        issues
+    }
+
+    /// Check that the output directory exists and files can be written to it, creating it if it doesn't exist
+    ///
+    /// This should be called before downloading files so that setup mistakes are caught before any work is done.
+    pub fn check_output_writable(&self) -> Result<(), OutputDirError> {
+        let dir = &self.output_dir;
+        let permission = |err: std::io::Error| {
+            if err.kind() == std::io::ErrorKind::PermissionDenied {
+                OutputDirError::PermissionDenied(dir.clone())
+            } else {
+                OutputDirError::IOError(err)
+            }
+        };
+        if dir.exists() {
+            if !dir.is_dir() {
+                return Err(OutputDirError::NotADirectory(dir.clone()));
+            }
+        } else if let Err(err) = create_dir_all(dir) {
+            return Err(match err.kind() {
+                std::io::ErrorKind::PermissionDenied => permission(err),
+                _ => OutputDirError::DoesNotExist(dir.clone()),
+            });
+        }
+
+        let test_file = dir.join(".libium-write-test");
+        File::create(&test_file).map_err(permission)?;
+        remove_file(test_file).map_err(permission)
+    }
+
+    /// Get the licenses of the mods in the profile
+    ///
+    /// A mod's license is `None` if it wasn't recorded when the mod was added, e.g. since CurseForge doesn't provide licenses.
+    /// Licenses that were declared as unknown are still returned, check them using [`License::is_specified`].
+    pub fn licenses(&self) -> HashMap<ModIdentifier, Option<&License>> {
+        self.mods
+            .iter()
+            .map(|mod_| (mod_.identifier.unpinned(), mod_.license.as_ref()))
+            .collect()
+    }
+
+    /// Get the mods whose required dependencies aren't in the profile, along with those missing dependencies
+    ///
+    /// Dependencies are only found if they are from the same platform as the mod that requires them.
+    pub fn missing_dependencies(&self) -> Vec<(&Mod, Vec<&ModIdentifier>)> {
+        self.mods
+            .iter()
+            .filter_map(|mod_| {
+                let missing = mod_
+                    .required_dependencies
+                    .iter()
+                    .filter(|dependency| {
+                        !self
+                            .mods
+                            .iter()
+                            .any(|other| other.identifier.unpinned() == dependency.unpinned())
+                    })
+                    .collect::<Vec<_>>();
+                (!missing.is_empty()).then_some((mod_, missing))
+            })
+            .collect()
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -137,6 +835,103 @@ pub struct Mod {
     #[serde(default)]
     pub override_filters: bool,
 
+    /// The kinds of the profile's filters that don't apply to this mod, e.g. the mod loader for a loader-agnostic mod
+    ///
+    /// The mod's own filters still apply.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub ignored_filters: Vec<FilterKind>,
+
+    /// Whether the pre-releases of this mod's GitHub repository are treated as releases,
+    /// for repositories that only publish pre-releases
+    ///
+    /// The profile's release channel filter still applies to the other mods. A mod that only publishes pre-releases
+    /// can be added with the compatibility checks disabled, and then have this set.
+    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default)]
+    pub allow_prereleases: bool,
+
+    /// Whether this mod was added without passing the compatibility checks
+    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default)]
+    pub force_added: bool,
+
+    /// Whether this mod should be downloaded and upgraded
+    ///
+    /// Disabled mods are kept in the profile so that their configuration isn't lost.
+    #[serde(skip_serializing_if = "is_true")]
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// The mods this mod requires, as declared by its compatible file when it was added
+    ///
+    /// These are recorded even if the dependencies were not added to the profile.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub required_dependencies: Vec<ModIdentifier>,
+
+    /// The files that have been installed for this mod, with the current one last
+    ///
+    /// Only as many previous files as the profile's `keep_versions` are kept,
+    /// and the files in the history should not be deleted from the output directory when upgrading.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub history: Vec<InstalledFile>,
+
+    /// The directory in the default branch of this mod's GitHub repository to get files from
+    /// if none of its releases are compatible
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub github_contents_path: Option<String>,
+
+    /// A regex that the filenames of this mod's GitHub release assets must match,
+    /// for repositories that publish several mods in each release
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub asset_filter: Option<String>,
+
+    /// A note from the user about this mod, such as why it is pinned or was force added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub note: Option<String>,
+
+    /// Labels from the user to group mods by, e.g. `performance` or `client-only`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// The identifier to resolve this mod's file from if it is unavailable from its own platform,
+    /// e.g. if distribution is denied on CurseForge or there is no compatible file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub fallback: Option<ModIdentifier>,
+
+    /// The kind of content this is, which decides the directory it's downloaded to
+    #[serde(skip_serializing_if = "ContentType::is_mod")]
+    #[serde(default)]
+    pub content_type: ContentType,
+
+    /// The license the project declared when it was added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub license: Option<License>,
+
+    /// The project's slug when it was added, or the repository's name for GitHub repositories,
+    /// used to match the mod by name and to find its dependencies in a scan
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub slug: Option<String>,
+
+    /// The minimum Java version the mod requires, e.g. `21`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub min_java: Option<u8>,
+
+    /// How this mod was added to the profile, which is `None` for mods added before this was recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub origin: Option<ModOrigin>,
+
     // Kept for backwards compatibility reasons
     #[serde(skip_serializing)]
     check_game_version: Option<bool>,
@@ -156,34 +951,423 @@ impl Mod {
             identifier,
             filters,
             override_filters,
+            ignored_filters: Vec::new(),
+            allow_prereleases: false,
+            force_added: false,
+            enabled: true,
+            required_dependencies: Vec::new(),
+            history: Vec::new(),
+            github_contents_path: None,
+            asset_filter: None,
+            note: None,
+            tags: Vec::new(),
+            fallback: None,
+            content_type: ContentType::Mod,
+            license: None,
+            slug: None,
+            min_java: None,
+            origin: Some(ModOrigin::Manual),
             check_game_version: None,
             check_mod_loader: None,
         }
     }
+
+    /// Whether `identifier` refers to this mod
+    ///
+    /// `identifier` can be the mod's name or slug (case-insensitive), its CurseForge project ID,
+    /// its Modrinth project ID, or its GitHub repository in the form `owner/name`.
+    pub fn matches(&self, identifier: &str) -> bool {
+        let identifier = identifier.trim();
+        self.name.eq_ignore_ascii_case(identifier)
+            || self
+                .slug
+                .as_ref()
+                .is_some_and(|slug| slug.eq_ignore_ascii_case(identifier))
+            || match &self.identifier {
+                ModIdentifier::CurseForgeProject(id)
+                | ModIdentifier::PinnedCurseForgeProject(id, _) => identifier.parse() == Ok(*id),
+                ModIdentifier::ModrinthProject(id)
+                | ModIdentifier::PinnedModrinthProject(id, _) => id == identifier,
+                ModIdentifier::GitHubRepository(owner, repo)
+                | ModIdentifier::PinnedGitHubRepository((owner, repo), _) => {
+                    identifier.split_once('/').is_some_and(|(o, r)| {
+                        o.eq_ignore_ascii_case(owner) && r.eq_ignore_ascii_case(repo)
+                    })
+                }
+            }
+    }
+
+    /// Whether this mod always resolves to the same file,
+    /// because it and its fallback are pinned and tracked by immutable IDs rather than slugs
+    ///
+    /// Pinned GitHub repositories are considered reproducible because release asset IDs are immutable.
+    pub fn is_reproducible(&self) -> bool {
+        let is_reproducible = |identifier: &ModIdentifier| match identifier {
+            ModIdentifier::PinnedCurseForgeProject(..)
+            | ModIdentifier::PinnedGitHubRepository(..) => true,
+            ModIdentifier::PinnedModrinthProject(id, _) => {
+                crate::add::is_modrinth_id(id) && self.slug.as_ref() != Some(id)
+            }
+            _ => false,
+        };
+        is_reproducible(&self.identifier) && self.fallback.as_ref().is_none_or(is_reproducible)
+    }
+}
+
+/// A file that was installed for a mod
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct InstalledFile {
+    /// The ID of the file on its platform, which the mod can be pinned to
+    pub file_id: String,
+    /// The path of the file relative to the output directory
+    pub output: PathBuf,
 }
 
 const fn is_false(b: &bool) -> bool {
     !*b
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+const fn is_true(b: &bool) -> bool {
+    *b
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+/// Displayed in the same format that `add::parse_id()` accepts
+#[derive(Deserialize, Serialize, Debug, Display, Clone, PartialEq, Eq, Hash)]
 pub enum ModIdentifier {
     CurseForgeProject(i32),
     ModrinthProject(String),
+    #[display("{_0}/{_1}")]
     GitHubRepository(String, String),
 
+    #[display("{_0}@{_1}")]
     PinnedCurseForgeProject(i32, i32),
+    #[display("{_0}@{_1}")]
     PinnedModrinthProject(String, String),
+    #[display("{}/{}@{_1}", _0.0, _0.1)]
     PinnedGitHubRepository((String, String), i32),
 }
 
-#[derive(Deserialize, Serialize, Debug, Display, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+/// The platforms that mods can be from
+///
+/// Mods can't be added from GitLab or direct URLs yet,
+/// these are included so that frontends can refer to and [show](ProviderKind::icon_hint) every source consistently.
+#[derive(
+    Deserialize, Serialize, Debug, Display, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum,
+)]
+pub enum ProviderKind {
+    CurseForge,
+    Modrinth,
+    GitHub,
+    GitLab,
+    Url,
+}
+
+impl ProviderKind {
+    /// The prefix that explicitly marks an identifier as being from this platform, e.g. `mr:sodium`
+    pub fn prefix(self) -> &'static str {
+        match self {
+            ProviderKind::CurseForge => "cf",
+            ProviderKind::Modrinth => "mr",
+            ProviderKind::GitHub => "gh",
+            ProviderKind::GitLab => "gl",
+            ProviderKind::Url => "url",
+        }
+    }
+
+    /// Get the platform that `prefix` refers to
+    pub fn from_prefix(prefix: &str) -> Option<Self> {
+        [
+            ProviderKind::CurseForge,
+            ProviderKind::Modrinth,
+            ProviderKind::GitHub,
+            ProviderKind::GitLab,
+            ProviderKind::Url,
+        ]
+        .into_iter()
+        .find(|provider| provider.prefix().eq_ignore_ascii_case(prefix))
+    }
+
+    /// The URL of the platform's website, which project pages are under
+    ///
+    /// Direct URLs aren't from a platform, so they don't have one.
+    pub fn base_url(self) -> Option<&'static str> {
+        match self {
+            ProviderKind::CurseForge => Some("https://www.curseforge.com"),
+            ProviderKind::Modrinth => Some("https://modrinth.com"),
+            ProviderKind::GitHub => Some("https://github.com"),
+            ProviderKind::GitLab => Some("https://gitlab.com"),
+            ProviderKind::Url => None,
+        }
+    }
+
+    /// The name of an icon to show for this platform
+    ///
+    /// This is the platform's slug on [Simple Icons](https://simpleicons.org), or `link` for direct URLs.
+    pub fn icon_hint(self) -> &'static str {
+        match self {
+            ProviderKind::CurseForge => "curseforge",
+            ProviderKind::Modrinth => "modrinth",
+            ProviderKind::GitHub => "github",
+            ProviderKind::GitLab => "gitlab",
+            ProviderKind::Url => "link",
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("The given string is not a recognised platform")]
+pub struct ProviderKindParseError;
+
+impl FromStr for ProviderKind {
+    type Err = ProviderKindParseError;
+
+    // This implementation is case-insensitive, and accepts the platforms' prefixes as well as their names
+    fn from_str(from: &str) -> Result<Self, Self::Err> {
+        let from = from.trim().trim_end_matches(':');
+        if let Some(provider) = Self::from_prefix(from) {
+            return Ok(provider);
+        }
+        match from.to_lowercase().replace([' ', '-', '_'], "").as_str() {
+            "curseforge" => Ok(Self::CurseForge),
+            "modrinth" => Ok(Self::Modrinth),
+            "github" => Ok(Self::GitHub),
+            "gitlab" => Ok(Self::GitLab),
+            _ => Err(Self::Err {}),
+        }
+    }
+}
+
+impl ModIdentifier {
+    /// Get the platform this identifier is from
+    pub fn provider(&self) -> ProviderKind {
+        match self {
+            ModIdentifier::CurseForgeProject(_) | ModIdentifier::PinnedCurseForgeProject(..) => {
+                ProviderKind::CurseForge
+            }
+            ModIdentifier::ModrinthProject(_) | ModIdentifier::PinnedModrinthProject(..) => {
+                ProviderKind::Modrinth
+            }
+            ModIdentifier::GitHubRepository(..) | ModIdentifier::PinnedGitHubRepository(..) => {
+                ProviderKind::GitHub
+            }
+        }
+    }
+
+    pub fn is_pinned(&self) -> bool {
+        matches!(
+            self,
+            ModIdentifier::PinnedCurseForgeProject(..)
+                | ModIdentifier::PinnedModrinthProject(..)
+                | ModIdentifier::PinnedGitHubRepository(..)
+        )
+    }
+
+    /// Get this identifier pinned to `file_id`, replacing any existing pin
+    ///
+    /// `file_id` is a CurseForge file ID, Modrinth version ID, or GitHub release asset ID.
+    /// Returns `None` if `file_id` is not a number for CurseForge or GitHub.
+    pub fn pinned_to(&self, file_id: &str) -> Option<Self> {
+        Some(match self.unpinned() {
+            ModIdentifier::CurseForgeProject(id) => {
+                ModIdentifier::PinnedCurseForgeProject(id, file_id.parse().ok()?)
+            }
+            ModIdentifier::ModrinthProject(id) => {
+                ModIdentifier::PinnedModrinthProject(id, file_id.to_owned())
+            }
+            ModIdentifier::GitHubRepository(owner, repo) => {
+                ModIdentifier::PinnedGitHubRepository((owner, repo), file_id.parse().ok()?)
+            }
+            _ => unreachable!(),
+        })
+    }
+
+    /// Get the owner and name of the GitHub repository of this identifier
+    pub fn github_repo(&self) -> Option<(&str, &str)> {
+        match self {
+            ModIdentifier::GitHubRepository(owner, repo)
+            | ModIdentifier::PinnedGitHubRepository((owner, repo), _) => Some((owner, repo)),
+            _ => None,
+        }
+    }
+
+    /// Get this identifier with its GitHub repository replaced by `owner/repo`, keeping any pin
+    ///
+    /// Identifiers from other platforms are returned unchanged.
+    pub fn with_github_repo(&self, owner: String, repo: String) -> Self {
+        match self {
+            ModIdentifier::GitHubRepository(..) => ModIdentifier::GitHubRepository(owner, repo),
+            ModIdentifier::PinnedGitHubRepository(_, pin) => {
+                ModIdentifier::PinnedGitHubRepository((owner, repo), *pin)
+            }
+            id => id.clone(),
+        }
+    }
+
+    /// Get this identifier without its pin
+    pub fn unpinned(&self) -> Self {
+        match self {
+            ModIdentifier::PinnedCurseForgeProject(id, _) => ModIdentifier::CurseForgeProject(*id),
+            ModIdentifier::PinnedModrinthProject(id, _) => {
+                ModIdentifier::ModrinthProject(id.clone())
+            }
+            ModIdentifier::PinnedGitHubRepository((owner, repo), _) => {
+                ModIdentifier::GitHubRepository(owner.clone(), repo.clone())
+            }
+            id => id.clone(),
+        }
+    }
+}
+
+/// The result of removing a mod with [`Profile::remove_mod_with_orphans`]
+#[derive(Debug, Clone)]
+pub struct RemovalPlan {
+    /// The mod that was removed
+    pub removed: Mod,
+    /// The names of the mods that are no longer required by any of the remaining mods
+    pub orphans: Vec<String>,
+}
+
+/// A problem with a profile found by [`Profile::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileIssue {
+    /// The output directory is a symlink or junction to another location, possibly another instance
+    OutputDirLinked {
+        /// The canonical path the output directory resolves to
+        resolved: PathBuf,
+    },
+}
+
+/// The profile is [`locked`](Profile::locked), so it can't be modified
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("The profile is locked")]
+pub struct ProfileLocked;
+
+/// Why the output directory can't be written to, found by [`Profile::check_output_writable`]
+#[derive(thiserror::Error, Debug)]
+pub enum OutputDirError {
+    #[error("The output directory {} does not exist and could not be created", _0.display())]
+    DoesNotExist(PathBuf),
+    #[error("The output directory {} is not a directory", _0.display())]
+    NotADirectory(PathBuf),
+    #[error("Permission to write to the output directory {} was denied", _0.display())]
+    PermissionDenied(PathBuf),
+    #[error("IO: {0}")]
+    IOError(#[from] std::io::Error),
+}
+
+/// A license declared by a project
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct License {
+    /// The SPDX identifier of the license, e.g. `MIT`, or `LicenseRef-*` for custom licenses
+    pub id: String,
+    pub name: String,
+    pub url: Option<Url>,
+}
+
+impl License {
+    /// Whether the project declared an actual license, rather than an unknown one
+    pub fn is_specified(&self) -> bool {
+        !matches!(self.id.as_str(), "" | "NOASSERTION" | "LicenseRef-Unknown")
+    }
+}
+
+/// How a mod was added to a profile, used to manage the mods added together
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub enum ModOrigin {
+    /// The mod was added directly by the user
+    Manual,
+    /// The mod was imported from a modpack
+    Modpack {
+        name: String,
+        version: Option<String>,
+    },
+    /// The mod was added from the [preset](crate::presets::Preset) with this name
+    Preset(String),
+    /// The mod was added as a dependency of the mod with this name
+    Dependency { of: String },
+}
+
+/// The kinds of content that a profile can contain, which are downloaded to different directories
+#[derive(Deserialize, Serialize, Debug, Display, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContentType {
+    #[default]
+    Mod,
+    ResourcePack,
+    ShaderPack,
+    DataPack,
+}
+
+impl ContentType {
+    pub fn is_mod(&self) -> bool {
+        *self == ContentType::Mod
+    }
+
+    /// Whether this content is loaded by the game itself rather than a mod loader,
+    /// so its compatibility only depends on the game version
+    pub fn is_loader_agnostic(&self) -> bool {
+        matches!(self, ContentType::ResourcePack | ContentType::ShaderPack)
+    }
+
+    /// Remove the mod loader filters from `filters` if this content is [loader agnostic](Self::is_loader_agnostic)
+    ///
+    /// ```
+    /// # use libium::config::{filters::{Filter, FilterKind}, structs::ContentType};
+    /// let profile_filters = vec![
+    ///     Filter::ModLoaderPrefer(vec![libium::config::structs::ModLoader::Forge]),
+    ///     Filter::GameVersionStrict(vec!["1.20.1".to_owned()]),
+    /// ];
+    ///
+    /// let shader_filters = ContentType::ShaderPack.applicable_filters(profile_filters.clone());
+    /// assert!(shader_filters.iter().all(|filter| filter.kind() != FilterKind::ModLoader));
+    /// assert_eq!(shader_filters.len(), 1);
+    ///
+    /// assert_eq!(ContentType::Mod.applicable_filters(profile_filters).len(), 2);
+    /// ```
+    pub fn applicable_filters(&self, mut filters: Vec<Filter>) -> Vec<Filter> {
+        if self.is_loader_agnostic() {
+            filters.retain(|filter| filter.kind() != FilterKind::ModLoader);
+        }
+        filters
+    }
+}
+
+/// What to do when a mod is added that is the same project as a mod from another platform in the profile
+///
+/// Projects are compared using [`same_project`](crate::metadata::same_project).
+#[derive(Deserialize, Serialize, Debug, Display, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateStrategy {
+    /// Return [`AlreadyAdded`](crate::add::Error::AlreadyAdded)
+    #[default]
+    Reject,
+    /// Use the new identifier as the existing mod's [`fallback`](Mod::fallback),
+    /// or return [`AlreadyAdded`](crate::add::Error::AlreadyAdded) if it already has one
+    Fallback,
+}
+
+impl DuplicateStrategy {
+    pub fn is_reject(&self) -> bool {
+        *self == DuplicateStrategy::Reject
+    }
+}
+
+// Deserialised using `FromStr` so that the aliases of loaders are accepted
+#[derive(
+    Deserialize, Serialize, Debug, Display, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum,
+)]
+#[serde(try_from = "String")]
 pub enum ModLoader {
     Quilt,
     Fabric,
     Forge,
     #[clap(name = "neoforge")]
     NeoForge,
+    #[clap(name = "liteloader")]
+    LiteLoader,
+    Rift,
 }
 
 #[derive(thiserror::Error, Debug, PartialEq, Eq)]
@@ -193,14 +1377,24 @@ pub struct ModLoaderParseError;
 impl FromStr for ModLoader {
     type Err = ModLoaderParseError;
 
-    // This implementation is case-insensitive
+    // This implementation is case-insensitive, and accepts common aliases of the loaders
     fn from_str(from: &str) -> Result<Self, Self::Err> {
         match from.trim().to_lowercase().as_str() {
-            "quilt" => Ok(Self::Quilt),
-            "fabric" => Ok(Self::Fabric),
-            "forge" => Ok(Self::Forge),
-            "neoforge" => Ok(Self::NeoForge),
+            "quilt" | "quiltmc" | "quilt-loader" => Ok(Self::Quilt),
+            "fabric" | "fabricmc" | "fabric-loader" => Ok(Self::Fabric),
+            "forge" | "minecraftforge" | "lexforge" => Ok(Self::Forge),
+            "neoforge" | "neo-forge" | "neoforged" => Ok(Self::NeoForge),
+            "liteloader" | "lite-loader" => Ok(Self::LiteLoader),
+            "rift" => Ok(Self::Rift),
             _ => Err(Self::Err {}),
         }
     }
 }
+
+impl TryFrom<String> for ModLoader {
+    type Error = ModLoaderParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
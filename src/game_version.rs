@@ -0,0 +1,168 @@
+use crate::MODRINTH_API;
+use ferinth::structures::tag::{GameVersion, GameVersionType};
+use std::{
+    fs::File,
+    io::{self, BufReader},
+    path::Path,
+    sync::OnceLock,
+};
+
+static GAME_VERSIONS: OnceLock<Vec<GameVersion>> = OnceLock::new();
+static OVERRIDE: OnceLock<Vec<GameVersion>> = OnceLock::new();
+
+/// Versions released as April Fools jokes
+///
+/// Some of these are listed as snapshots, but they should never be picked as a game version for a profile.
+const APRIL_FOOLS_VERSIONS: &[&str] = &[
+    "2.0",
+    "15w14a",
+    "1.RV-Pre1",
+    "3D Shareware v1.34",
+    "20w14infinite",
+    "22w13oneblockatatime",
+    "23w13a_or_b",
+    "24w14potato",
+    "25w14craftmine",
+];
+
+/// The prefixes of versions from before the full release of the game,
+/// i.e. pre-classic, classic, indev, infdev, alpha, and beta
+const ANCIENT_PREFIXES: &[&str] = &["rd-", "c0.", "in-", "inf-", "a1.", "b1."];
+
+/// Use `versions` as the list of Minecraft versions instead of fetching it, e.g. for reproducible or offline runs
+///
+/// This can only be set once, and must be set before the list is first used.
+/// Returns `versions` back if an override has already been set.
+pub fn set_override(versions: Vec<GameVersion>) -> Result<(), Vec<GameVersion>> {
+    OVERRIDE.set(versions)
+}
+
+/// Read the list of Minecraft versions to use from the JSON file at `path`, in the format of Modrinth's game version tags
+///
+/// See [`set_override`] for more information.
+pub fn set_override_from_file(path: impl AsRef<Path>) -> io::Result<()> {
+    let versions = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+    set_override(versions).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "The game version list has already been overridden",
+        )
+    })
+}
+
+/// Gets the list of all Minecraft versions
+///
+/// The list is taken from the first of these that is available:
+/// 1. The override set using [`set_override`]
+/// 2. The list fetched earlier in the program
+/// 3. Modrinth's game version tags, which are then cached for the rest of the program
+pub async fn list() -> ferinth::Result<&'static [GameVersion]> {
+    if let Some(v) = OVERRIDE.get() {
+        Ok(v)
+    } else if let Some(v) = GAME_VERSIONS.get() {
+        Ok(v)
+    } else {
+        let versions = MODRINTH_API.list_game_versions().await?;
+        let _ = GAME_VERSIONS.set(versions);
+
+        Ok(GAME_VERSIONS.get().unwrap())
+    }
+}
+
+/// Whether `version` is an April Fools joke version
+pub fn is_april_fools(version: &GameVersion) -> bool {
+    APRIL_FOOLS_VERSIONS.contains(&version.version.as_str())
+}
+
+/// Whether `version` is from before the full release of the game, e.g. `b1.7.3`, `a1.2.6`, `c0.30`, or `rd-132211`
+///
+/// These versions aren't part of any [version group](crate::upgrade::check::get_version_groups),
+/// so they're only ever matched exactly.
+pub fn is_ancient(version: &str) -> bool {
+    ANCIENT_PREFIXES
+        .iter()
+        .any(|prefix| version.starts_with(prefix))
+}
+
+/// Gets the most recently released full release of the game from `versions`
+///
+/// April Fools versions are never considered stable.
+pub fn latest_stable(versions: &[GameVersion]) -> Option<&GameVersion> {
+    versions
+        .iter()
+        .filter(|v| v.version_type == GameVersionType::Release && !is_april_fools(v))
+        .max_by_key(|v| v.date)
+}
+
+/// Gets the most recently released version of the game from `versions`, including snapshots
+///
+/// April Fools versions are still excluded.
+pub fn latest_including_snapshots(versions: &[GameVersion]) -> Option<&GameVersion> {
+    versions
+        .iter()
+        .filter(|v| {
+            matches!(
+                v.version_type,
+                GameVersionType::Release | GameVersionType::Snapshot
+            ) && !is_april_fools(v)
+        })
+        .max_by_key(|v| v.date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(version: &str) -> GameVersion {
+        GameVersion {
+            version: version.to_owned(),
+            version_type: GameVersionType::Release,
+            date: "2023-06-12T13:25:51Z".parse().unwrap(),
+            major: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn override_before_cache() {
+        GAME_VERSIONS.set(vec![version("cached")]).unwrap();
+        set_override(vec![version("overridden")]).unwrap();
+        let versions = list().await.unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].version, "overridden");
+    }
+
+    #[test]
+    fn ancient_versions() {
+        for version in [
+            "rd-132211",
+            "rd-20090515",
+            "c0.0.11a",
+            "c0.30_01c",
+            "in-20091223-1459",
+            "inf-20100618",
+            "a1.0.4",
+            "a1.2.6",
+            "b1.0",
+            "b1.7.3",
+        ] {
+            assert!(is_ancient(version), "{version}");
+        }
+    }
+
+    #[test]
+    fn modern_versions() {
+        for version in [
+            "1.0",
+            "1.7.10",
+            "1.20.1",
+            "1.21-pre1",
+            "1.20.5-rc1",
+            "23w13a",
+            "20w14infinite",
+            "1.RV-Pre1",
+            "3D Shareware v1.34",
+        ] {
+            assert!(!is_ancient(version), "{version}");
+        }
+    }
+}
@@ -0,0 +1,223 @@
+#[cfg(feature = "curseforge")]
+use crate::CURSEFORGE_API;
+use crate::{GITHUB_API, MODRINTH_API};
+use futures_util::join;
+use octocrab::models::Rate;
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The ID of JEI on CurseForge, used as a project that should always exist
+#[cfg(feature = "curseforge")]
+const CURSEFORGE_TEST_PROJECT: i32 = 238222;
+
+/// The result of checking a platform with [`health_check`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HealthStatus {
+    Ok,
+    /// The platform rejected the API key or token
+    Unauthorized,
+    /// The platform could not be reached, or returned an unexpected error
+    Unreachable(String),
+    /// CurseForge support is not enabled in this build
+    Disabled,
+}
+
+impl HealthStatus {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, HealthStatus::Ok)
+    }
+}
+
+/// The status of each platform
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthReport {
+    pub modrinth: HealthStatus,
+    pub curseforge: HealthStatus,
+    pub github: HealthStatus,
+}
+
+impl HealthReport {
+    /// Whether every enabled platform is working
+    pub fn is_healthy(&self) -> bool {
+        [&self.modrinth, &self.curseforge, &self.github]
+            .iter()
+            .all(|status| status.is_ok() || status == &&HealthStatus::Disabled)
+    }
+}
+
+/// Check whether each platform is reachable and accepts the configured credentials
+///
+/// One lightweight request is sent to each platform, concurrently.
+pub async fn health_check() -> HealthReport {
+    let (modrinth, curseforge, github) = join!(modrinth(), curseforge(), github());
+    HealthReport {
+        modrinth,
+        curseforge,
+        github,
+    }
+}
+
+fn from_reqwest_error(err: &reqwest::Error) -> HealthStatus {
+    match err.status() {
+        Some(status) if status.as_u16() == 401 || status.as_u16() == 403 => {
+            HealthStatus::Unauthorized
+        }
+        _ => HealthStatus::Unreachable(err.to_string()),
+    }
+}
+
+async fn modrinth() -> HealthStatus {
+    match MODRINTH_API.list_loaders().await {
+        Ok(_) => HealthStatus::Ok,
+        Err(ferinth::Error::ReqwestError(err)) => from_reqwest_error(&err),
+        Err(err) => HealthStatus::Unreachable(err.to_string()),
+    }
+}
+
+#[cfg(feature = "curseforge")]
+async fn curseforge() -> HealthStatus {
+    match CURSEFORGE_API.get_mod(CURSEFORGE_TEST_PROJECT).await {
+        Ok(_) => HealthStatus::Ok,
+        Err(furse::Error::ReqwestError(err)) => from_reqwest_error(&err),
+        Err(err) => HealthStatus::Unreachable(err.to_string()),
+    }
+}
+
+#[cfg(not(feature = "curseforge"))]
+async fn curseforge() -> HealthStatus {
+    HealthStatus::Disabled
+}
+
+async fn github() -> HealthStatus {
+    // Checking the rate limit doesn't count against it
+    match GITHUB_API.ratelimit().get().await {
+        Ok(_) => HealthStatus::Ok,
+        Err(octocrab::Error::GitHub { source, .. })
+            if source.status_code.as_u16() == 401 || source.status_code.as_u16() == 403 =>
+        {
+            HealthStatus::Unauthorized
+        }
+        Err(err) => HealthStatus::Unreachable(err.to_string()),
+    }
+}
+
+/// The details of the GitHub token found by [`validate_github_token`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitHubTokenInfo {
+    /// Whether a token was provided using the `GITHUB_TOKEN` environment variable
+    pub provided: bool,
+    /// Whether GitHub accepted the token, which is false if no token was provided
+    pub valid: bool,
+    /// The login of the user the token belongs to
+    pub login: Option<String>,
+    /// The OAuth scopes of the token, fine-grained tokens don't have any
+    pub scopes: Vec<String>,
+    /// The REST API rate limit of the token, or of the IP address if no token was provided
+    ///
+    /// This is `None` if the token is invalid, since GitHub rejects all requests made with it.
+    pub rate_limit: Option<Rate>,
+}
+
+/// Check whether the GitHub token is valid, and get its scopes and remaining rate limit
+///
+/// Unlike [`health_check`], this explains why GitHub requests might fail, such as the rate limit being exhausted.
+pub async fn validate_github_token() -> octocrab::Result<GitHubTokenInfo> {
+    #[derive(Deserialize)]
+    struct User {
+        login: String,
+    }
+
+    let provided = std::env::var_os("GITHUB_TOKEN").is_some();
+    let mut info = GitHubTokenInfo {
+        provided,
+        valid: false,
+        login: None,
+        scopes: Vec::new(),
+        rate_limit: None,
+    };
+    if provided {
+        let response = GITHUB_API._get("/user").await?;
+        if response.status().is_success() {
+            info.valid = true;
+            info.scopes = response
+                .headers()
+                .get("x-oauth-scopes")
+                .and_then(|scopes| scopes.to_str().ok())
+                .map(|scopes| {
+                    scopes
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|scope| !scope.is_empty())
+                        .map(ToOwned::to_owned)
+                        .collect()
+                })
+                .unwrap_or_default();
+            info.login = serde_json::from_str::<User>(&GITHUB_API.body_to_string(response).await?)
+                .ok()
+                .map(|user| user.login);
+        }
+    }
+    if !provided || info.valid {
+        info.rate_limit = Some(GITHUB_API.ratelimit().get().await?.resources.core);
+    }
+    Ok(info)
+}
+
+/// The base URL of Modrinth's API, which the rate limit is read from
+const MODRINTH_API_URL: &str = "https://api.modrinth.com/v2/";
+
+/// Modrinth's rate limit for this IP address, found by [`modrinth_rate_limit`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModrinthRateLimit {
+    /// The number of requests allowed in each minute
+    pub limit: usize,
+    /// The number of requests remaining until the rate limit resets
+    pub remaining: usize,
+    /// The Unix timestamp at which the rate limit resets
+    pub reset_at: u64,
+}
+
+impl ModrinthRateLimit {
+    /// Whether no requests are remaining, so requests should be delayed until `reset_at` instead of being sent
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+/// Get Modrinth's rate limit from the `X-Ratelimit-*` headers of a lightweight request
+///
+/// Ferinth doesn't expose the headers of its responses, so this sends a separate request,
+/// which counts against the rate limit. The headers are also read if the rate limit was exceeded.
+/// Returns `None` if Modrinth didn't send the headers.
+pub async fn modrinth_rate_limit() -> reqwest::Result<Option<ModrinthRateLimit>> {
+    let mut request = crate::HTTP_CLIENT.get(format!("{MODRINTH_API_URL}tag/loader"));
+    if let Some(token) = crate::modrinth_token() {
+        request = request.header(reqwest::header::AUTHORIZATION, token);
+    }
+    let response = request.send().await?;
+
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok())
+    };
+    let (Some(limit), Some(remaining), Some(reset)) = (
+        header("x-ratelimit-limit"),
+        header("x-ratelimit-remaining"),
+        header("x-ratelimit-reset"),
+    ) else {
+        return Ok(None);
+    };
+    // Modrinth sends the number of seconds until the rate limit resets
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    Ok(Some(ModrinthRateLimit {
+        limit: limit as usize,
+        remaining: remaining as usize,
+        reset_at: now + reset,
+    }))
+}
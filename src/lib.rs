@@ -1,39 +1,181 @@
 pub mod add;
 pub mod config;
+pub mod game_version;
+pub mod health;
 pub mod iter_ext;
+pub mod loader_version;
+pub mod metadata;
+#[cfg(feature = "curseforge")]
+pub mod migrate;
+pub mod misc;
 pub mod modpack;
+pub mod presets;
+pub mod remove;
 pub mod scan;
+pub mod search;
 pub mod upgrade;
 pub mod version_ext;
 
 pub use add::add;
 pub use scan::scan;
 
-use std::{path::PathBuf, sync::LazyLock};
+use std::{
+    path::PathBuf,
+    sync::{LazyLock, OnceLock},
+};
 
-pub static GITHUB_API: LazyLock<octocrab::Octocrab> = LazyLock::new(|| {
-    let mut github = octocrab::OctocrabBuilder::new();
-    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
-        github = github.personal_token(token);
+/// The user agent used if [`set_user_agent()`] isn't called
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    "libium/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/gorilla-devs/libium)"
+);
+
+static USER_AGENT: OnceLock<String> = OnceLock::new();
+
+/// Set the user agent to send with requests to Modrinth and GitHub, and with other requests such as downloads
+///
+/// A descriptive user agent that identifies your application and how to contact you reduces the chance of being rate limited.
+/// This must be called before any requests are made since the clients are built when they're first used,
+/// and returns `user_agent` back as an error if the user agent was already set.
+///
+/// The CurseForge client doesn't support custom user agents.
+pub fn set_user_agent(user_agent: String) -> Result<(), String> {
+    USER_AGENT.set(user_agent)
+}
+
+/// Get the user agent to send with requests
+pub fn user_agent() -> &'static str {
+    USER_AGENT.get_or_init(|| DEFAULT_USER_AGENT.to_owned())
+}
+
+static MODRINTH_TOKEN: OnceLock<Option<String>> = OnceLock::new();
+
+/// Set the personal access token to authenticate requests to Modrinth with
+///
+/// Authenticated requests can access the user's private projects, so their drafts and projects under review can be added.
+/// This must be called before any requests are made since the clients are built when they're first used,
+/// and returns `token` back as an error if the token was already set.
+/// If this isn't called, the token is read from the `MODRINTH_TOKEN` environment variable.
+pub fn set_modrinth_token(token: String) -> Result<(), String> {
+    MODRINTH_TOKEN
+        .set(Some(token))
+        .map_err(|token| token.unwrap_or_default())
+}
+
+/// Get the token to authenticate requests to Modrinth with, if there is one
+pub fn modrinth_token() -> Option<&'static str> {
+    MODRINTH_TOKEN
+        .get_or_init(|| std::env::var("MODRINTH_TOKEN").ok())
+        .as_deref()
+}
+
+/// The base URL of the GitHub API used if [`set_github_api_url()`] isn't called
+pub const DEFAULT_GITHUB_API_URL: &str = "https://api.github.com";
+
+static GITHUB_API_URL: OnceLock<Option<String>> = OnceLock::new();
+
+/// Set the base URL of the GitHub API to use, e.g. `https://github.example.com/api/v3/` for a GitHub Enterprise Server instance
+///
+/// This must be called before any requests are made since the clients are built when they're first used,
+/// and returns `url` back as an error if the URL was already set or isn't a valid URL.
+/// If this isn't called, the URL is read from the `GITHUB_API_URL` environment variable,
+/// which GitHub Actions sets to the API of the instance the workflow runs on.
+/// An invalid URL in the environment variable is ignored.
+///
+/// The `GITHUB_TOKEN` must be a token issued by the same instance, since tokens from `github.com` aren't accepted by enterprise instances.
+/// Enterprise instances may also require authentication for all requests, even to public repositories.
+pub fn set_github_api_url(url: String) -> Result<(), String> {
+    if url.parse::<http::Uri>().is_err() {
+        return Err(url);
     }
-    github.build().expect("Could not build GitHub client")
+    GITHUB_API_URL
+        .set(Some(url))
+        .map_err(|url| url.unwrap_or_default())
+}
+
+/// Get the base URL of the GitHub API to use, if it isn't the [default](DEFAULT_GITHUB_API_URL)
+pub fn github_api_url() -> Option<&'static str> {
+    GITHUB_API_URL
+        .get_or_init(|| {
+            std::env::var("GITHUB_API_URL")
+                .ok()
+                .filter(|url| url.parse::<http::Uri>().is_ok())
+        })
+        .as_deref()
+}
+
+pub static GITHUB_API: LazyLock<octocrab::Octocrab> = LazyLock::new(|| {
+    use octocrab::service::middleware::{
+        auth_header::AuthHeaderLayer, base_uri::BaseUriLayer, extra_headers::ExtraHeadersLayer,
+    };
+    use std::sync::Arc;
+
+    // The client is built from octocrab's parts since its default client sends its own `octocrab` user agent,
+    // and doesn't retry failed requests since they're retried with a backoff by `add::RetryPolicy` instead,
+    // as octocrab retries immediately and drops the body of the retried request
+    let connector = hyper_rustls::HttpsConnectorBuilder::new()
+        .with_native_roots()
+        .expect("Could not build GitHub client, the root certificates could not be loaded")
+        .https_or_http()
+        .enable_http1()
+        .build();
+    let client = hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+        .build(connector);
+    let base_uri = github_api_url()
+        .and_then(|url| url.parse().ok())
+        .unwrap_or_else(|| http::Uri::from_static(DEFAULT_GITHUB_API_URL));
+    let auth_header = std::env::var("GITHUB_TOKEN")
+        .ok()
+        .and_then(|token| format!("Bearer {token}").parse().ok());
+    let user_agent = user_agent()
+        .parse()
+        .unwrap_or_else(|_| http::HeaderValue::from_static(DEFAULT_USER_AGENT));
+
+    octocrab::OctocrabBuilder::new_empty()
+        .with_service(client)
+        .with_layer(&tower_http::follow_redirect::FollowRedirectLayer::new())
+        .with_layer(&ExtraHeadersLayer::new(Arc::new(vec![(
+            http::header::USER_AGENT,
+            user_agent,
+        )])))
+        .with_layer(&BaseUriLayer::new(base_uri.clone()))
+        .with_layer(&AuthHeaderLayer::new(
+            auth_header,
+            base_uri,
+            http::Uri::from_static("https://uploads.github.com"),
+        ))
+        .with_auth(octocrab::AuthState::None)
+        .build()
+        .expect("Could not build GitHub client")
 });
 
-pub static CURSEFORGE_API: LazyLock<furse::Furse> = LazyLock::new(|| {
-    furse::Furse::new(&std::env::var("CURSEFORGE_API_KEY").unwrap_or(String::from(
+/// Get the key to authenticate requests to CurseForge with
+///
+/// This is read from the `CURSEFORGE_API_KEY` environment variable, and defaults to libium's own key.
+#[cfg(feature = "curseforge")]
+pub fn curseforge_api_key() -> String {
+    std::env::var("CURSEFORGE_API_KEY").unwrap_or(String::from(
         "$2a$10$sI.yRk4h4R49XYF94IIijOrO4i3W3dAFZ4ssOlNE10GYrDhc2j8K.",
-    )))
-});
+    ))
+}
+
+#[cfg(feature = "curseforge")]
+pub static CURSEFORGE_API: LazyLock<furse::Furse> =
+    LazyLock::new(|| furse::Furse::new(&curseforge_api_key()));
 
 pub static MODRINTH_API: LazyLock<ferinth::Ferinth> = LazyLock::new(|| {
-    ferinth::Ferinth::new(
-        "ferium",
-        // TODO: option_env!("CARGO_PKG_VERSION"),
-        None,
-        Some("Discord: therookiecoder"),
-        None,
-    )
-    .expect("Could not build Modrinth client") // This should never fail since no `authorisation` token was provided
+    ferinth::Ferinth::new(user_agent(), None, None, modrinth_token())
+        .expect("Could not build Modrinth client, the Modrinth token may be invalid")
+});
+
+/// The client for requests that aren't made through a platform's client, such as downloads,
+/// which sends the [user agent](user_agent)
+pub static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .user_agent(user_agent())
+        .build()
+        .expect("Could not build HTTP client")
 });
 
 pub static HOME: LazyLock<PathBuf> =
@@ -64,3 +206,14 @@ pub fn read_wrapper(mut source: impl std::io::Read) -> std::io::Result<String> {
     source.read_to_string(&mut buffer)?;
     Ok(buffer)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_github_api_url() {
+        let url = "https://github example.com/api/v3/".to_owned();
+        assert_eq!(set_github_api_url(url.clone()), Err(url));
+    }
+}
@@ -0,0 +1,296 @@
+use crate::config::structs::{ModLoader, Profile};
+use derive_more::derive::Display;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+    sync::LazyLock,
+};
+use zip::ZipArchive;
+
+const FORGE_PROMOTIONS_URL: &str =
+    "https://files.minecraftforge.net/net/minecraftforge/forge/promotions_slim.json";
+const NEOFORGE_VERSIONS_URL: &str =
+    "https://maven.neoforged.net/api/maven/versions/releases/net/neoforged/neoforge";
+
+/// Matches each range in a Maven version range, e.g. `[47.1,48)`
+static RANGE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[\[(][^\])]*[\])]").unwrap());
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub enum Error {
+    ReqwestError(#[from] reqwest::Error),
+    JSONError(#[from] serde_json::Error),
+    IOError(#[from] std::io::Error),
+    ZipError(#[from] zip::result::ZipError),
+    TOMLError(#[from] toml::de::Error),
+}
+type Result<T> = std::result::Result<T, Error>;
+
+/// Which build of a mod loader to pick for a game version
+#[derive(
+    Deserialize, Serialize, Debug, Display, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum,
+)]
+pub enum LoaderChannel {
+    /// The build that the loader's developers recommend, or the latest stable build for NeoForge
+    #[default]
+    Recommended,
+    /// The newest build, including betas
+    Latest,
+}
+
+/// The version of the mod loader a mod requires, read from its `mods.toml`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoaderRequirement {
+    pub loader: ModLoader,
+    /// The Maven version range of the builds the mod supports, e.g. `[47.1,)`
+    pub range: String,
+}
+
+impl LoaderRequirement {
+    /// Whether the loader build `version` is in the required range
+    ///
+    /// Bounds that aren't version numbers, such as unreplaced `${forge_version}` placeholders, are ignored.
+    pub fn is_satisfied_by(&self, version: &str) -> bool {
+        let version = version_numbers(version);
+        let ranges = RANGE.find_iter(&self.range).collect::<Vec<_>>();
+        // A bare version is a minimum
+        if ranges.is_empty() {
+            let minimum = version_numbers(&self.range);
+            return minimum.is_empty() || version >= minimum;
+        }
+
+        ranges.iter().any(|range| {
+            let range = range.as_str();
+            let (low_inclusive, high_inclusive) = (range.starts_with('['), range.ends_with(']'));
+            let inner = &range[1..range.len() - 1];
+            match inner.split_once(',') {
+                Some((low, high)) => {
+                    let (low, high) = (version_numbers(low), version_numbers(high));
+                    (low.is_empty() || version > low || (low_inclusive && version == low))
+                        && (high.is_empty()
+                            || version < high
+                            || (high_inclusive && version == high))
+                }
+                None => {
+                    let exact = version_numbers(inner);
+                    exact.is_empty() || version == exact
+                }
+            }
+        })
+    }
+}
+
+/// A mod that requires a different build of the mod loader than the profile's
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("The mod requires {loader} {required}, but the profile uses {available}")]
+pub struct LoaderVersionMismatch {
+    pub loader: ModLoader,
+    /// The Maven version range of the builds the mod supports
+    pub required: String,
+    /// The profile's loader version
+    pub available: String,
+}
+
+/// Get the version of the `loader` build for `game_version` in `channel`
+///
+/// Forge's recommended build falls back to its latest build since many game versions don't have one.
+/// Returns `None` if there are no builds for the game version,
+/// or if `loader` isn't Forge or NeoForge since their versions aren't tracked.
+pub async fn resolve_loader_version(
+    loader: ModLoader,
+    game_version: &str,
+    channel: LoaderChannel,
+) -> Result<Option<String>> {
+    match loader {
+        ModLoader::Forge => {
+            #[derive(Deserialize)]
+            struct Promotions {
+                promos: HashMap<String, String>,
+            }
+            let promotions: Promotions = serde_json::from_slice(
+                &crate::HTTP_CLIENT
+                    .get(FORGE_PROMOTIONS_URL)
+                    .send()
+                    .await?
+                    .bytes()
+                    .await?,
+            )?;
+            let latest = promotions.promos.get(&format!("{game_version}-latest"));
+            Ok(match channel {
+                LoaderChannel::Recommended => promotions
+                    .promos
+                    .get(&format!("{game_version}-recommended"))
+                    .or(latest),
+                LoaderChannel::Latest => latest,
+            }
+            .cloned())
+        }
+        ModLoader::NeoForge => {
+            #[derive(Deserialize)]
+            struct Versions {
+                versions: Vec<String>,
+            }
+            // NeoForge versions start with the game version without the leading `1.`, e.g. `21.1.77` is for 1.21.1
+            let Some(minor) = game_version.strip_prefix("1.") else {
+                return Ok(None);
+            };
+            let prefix = if minor.contains('.') {
+                format!("{minor}.")
+            } else {
+                format!("{minor}.0.")
+            };
+            let versions: Versions = serde_json::from_slice(
+                &crate::HTTP_CLIENT
+                    .get(NEOFORGE_VERSIONS_URL)
+                    .send()
+                    .await?
+                    .bytes()
+                    .await?,
+            )?;
+            Ok(versions
+                .versions
+                .into_iter()
+                .filter(|version| version.starts_with(&prefix))
+                .filter(|version| channel == LoaderChannel::Latest || !version.contains("beta"))
+                .max_by_key(|version| version_numbers(version)))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Read the Forge or NeoForge build that the mod in the JAR file at `path` requires, from its `mods.toml`
+///
+/// Returns `None` if the file doesn't have a `mods.toml` or doesn't declare a loader dependency.
+pub fn required_loader_version(path: impl AsRef<Path>) -> Result<Option<LoaderRequirement>> {
+    let mut jar = ZipArchive::new(BufReader::new(File::open(path)?))?;
+    for (name, loader, mod_id) in [
+        (
+            "META-INF/neoforge.mods.toml",
+            ModLoader::NeoForge,
+            "neoforge",
+        ),
+        ("META-INF/mods.toml", ModLoader::Forge, "forge"),
+        ("META-INF/mods.toml", ModLoader::NeoForge, "neoforge"),
+    ] {
+        let Ok(mut entry) = jar.by_name(name) else {
+            continue;
+        };
+        let mut contents = String::new();
+        entry.read_to_string(&mut contents)?;
+        let manifest: toml::Table = toml::from_str(&contents)?;
+
+        let range = manifest
+            .get("dependencies")
+            .and_then(toml::Value::as_table)
+            .into_iter()
+            .flat_map(|dependencies| dependencies.values())
+            .filter_map(toml::Value::as_array)
+            .flatten()
+            .find(|dependency| {
+                dependency.get("modId").and_then(toml::Value::as_str) == Some(mod_id)
+            })
+            .and_then(|dependency| dependency.get("versionRange"))
+            .and_then(toml::Value::as_str);
+        if let Some(range) = range {
+            return Ok(Some(LoaderRequirement {
+                loader,
+                range: range.to_owned(),
+            }));
+        }
+    }
+    Ok(None)
+}
+
+/// Check that the mod in the JAR file at `path` supports the profile's [`loader_version`](Profile::loader_version)
+///
+/// Nothing is checked if the profile doesn't have a loader version,
+/// or if the mod doesn't require a specific build of the profile's mod loader.
+pub fn check_loader_version(
+    path: impl AsRef<Path>,
+    profile: &Profile,
+) -> Result<std::result::Result<(), LoaderVersionMismatch>> {
+    let (Some(available), Some(requirement)) =
+        (&profile.loader_version, required_loader_version(path)?)
+    else {
+        return Ok(Ok(()));
+    };
+    Ok(
+        if requirement.is_satisfied_by(available) || !profile.uses_loader(requirement.loader) {
+            Ok(())
+        } else {
+            Err(LoaderVersionMismatch {
+                loader: requirement.loader,
+                required: requirement.range,
+                available: available.clone(),
+            })
+        },
+    )
+}
+
+/// Get the numbers in `version` without trailing zeros, so that `47.1` and `47.1.0` are equal
+fn version_numbers(version: &str) -> Vec<u64> {
+    let mut numbers = version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|n| n.parse().ok())
+        .collect::<Vec<u64>>();
+    while numbers.last() == Some(&0) {
+        numbers.pop();
+    }
+    numbers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forge(range: &str) -> LoaderRequirement {
+        LoaderRequirement {
+            loader: ModLoader::Forge,
+            range: range.to_owned(),
+        }
+    }
+
+    #[test]
+    fn open_ended_range() {
+        let requirement = forge("[47.1,)");
+        assert!(requirement.is_satisfied_by("47.1.0"));
+        assert!(requirement.is_satisfied_by("47.2.0"));
+        assert!(requirement.is_satisfied_by("48.0.1"));
+        assert!(!requirement.is_satisfied_by("47.0.35"));
+    }
+
+    #[test]
+    fn half_open_range() {
+        let requirement = forge("[47,48)");
+        assert!(requirement.is_satisfied_by("47"));
+        assert!(requirement.is_satisfied_by("47.3.0"));
+        assert!(!requirement.is_satisfied_by("48.0.0"));
+        assert!(!requirement.is_satisfied_by("46.0.14"));
+    }
+
+    #[test]
+    fn upper_bound_only() {
+        let requirement = forge("(,48]");
+        assert!(requirement.is_satisfied_by("40.2.0"));
+        assert!(requirement.is_satisfied_by("48"));
+        assert!(!requirement.is_satisfied_by("48.0.1"));
+    }
+
+    #[test]
+    fn bare_version_is_minimum() {
+        let requirement = forge("47.1");
+        assert!(requirement.is_satisfied_by("47.1.0"));
+        assert!(requirement.is_satisfied_by("49.0.3"));
+        assert!(!requirement.is_satisfied_by("47.0.35"));
+    }
+
+    #[test]
+    fn placeholder_is_ignored() {
+        assert!(forge("${forge_version}").is_satisfied_by("47.1.0"));
+        assert!(forge("[${forge_version},)").is_satisfied_by("36.2.39"));
+    }
+}
@@ -0,0 +1,480 @@
+use crate::{
+    config::structs::{ModIdentifier, ModLoader, Profile, ProviderKind},
+    GITHUB_API, MODRINTH_API,
+};
+#[cfg(feature = "curseforge")]
+use crate::{iter_ext::IterExt as _, CURSEFORGE_API};
+use ferinth::structures::project::Project;
+#[cfg(feature = "curseforge")]
+use furse::structures::mod_structs::Mod as CFMod;
+use futures_util::{future::try_join_all, try_join};
+use octocrab::models::Repository;
+use reqwest::Url;
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    sync::{LazyLock, Mutex},
+};
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub enum Error {
+    #[error("Modrinth: {0}")]
+    ModrinthError(#[from] ferinth::Error),
+    #[cfg(feature = "curseforge")]
+    #[error("CurseForge: {0}")]
+    CurseForgeError(#[from] furse::Error),
+    #[error("CurseForge support is not enabled in this build")]
+    CurseForgeDisabled,
+    #[error("GitHub: {0:#?}")]
+    GitHubError(Box<octocrab::Error>),
+}
+type Result<T> = std::result::Result<T, Error>;
+
+// Boxed since octocrab's error is much larger than the others
+impl From<octocrab::Error> for Error {
+    fn from(err: octocrab::Error) -> Self {
+        Self::GitHubError(Box::new(err))
+    }
+}
+
+/// The metadata of a project, normalised across platforms
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectMeta {
+    /// The identifier of the project, without any pin
+    pub identifier: ModIdentifier,
+    pub name: String,
+    /// The project's slug, or `owner/name` for GitHub repositories
+    pub slug: String,
+    pub description: String,
+    pub icon_url: Option<Url>,
+    /// The project's page on its platform
+    pub page_url: Option<Url>,
+    /// The total downloads of the project, GitHub doesn't provide this
+    pub downloads: Option<usize>,
+    /// The game versions the project supports, GitHub doesn't provide these
+    pub game_versions: Vec<String>,
+    /// The mod loaders the project supports, GitHub doesn't provide these
+    pub loaders: Vec<ModLoader>,
+    /// The images in the project's gallery, GitHub doesn't provide these
+    pub gallery: Vec<GalleryImage>,
+    /// The people who made the project, or the owner of a GitHub repository
+    pub authors: Vec<Author>,
+}
+
+/// A person or organisation credited for a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Author {
+    pub name: String,
+    /// The author's role in the project's team on Modrinth, or `Owner` for GitHub repositories
+    ///
+    /// CurseForge doesn't provide roles.
+    pub role: Option<String>,
+    /// The author's profile page on the project's platform
+    pub url: Option<Url>,
+}
+
+/// An image in the gallery of a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GalleryImage {
+    pub url: Url,
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
+
+static CACHE: LazyLock<Mutex<HashMap<ModIdentifier, ProjectMeta>>> =
+    LazyLock::new(Default::default);
+#[cfg(feature = "curseforge")]
+static CURSEFORGE_PROJECTS: LazyLock<Mutex<HashMap<i32, CFMod>>> = LazyLock::new(Default::default);
+/// Keyed by both the project ID and slug
+static MODRINTH_PROJECTS: LazyLock<Mutex<HashMap<String, Project>>> =
+    LazyLock::new(Default::default);
+/// Keyed by the lowercase owner and name, since they are case insensitive
+static GITHUB_REPOS: LazyLock<Mutex<HashMap<(String, String), Repository>>> =
+    LazyLock::new(Default::default);
+
+/// Get the CurseForge project of `id`
+///
+/// Like the metadata, each project is only fetched once, and is cached for the rest of the program.
+#[cfg(feature = "curseforge")]
+pub async fn curseforge_project(id: i32) -> Result<CFMod> {
+    if let Some(project) = CURSEFORGE_PROJECTS.lock().unwrap().get(&id) {
+        return Ok(project.clone());
+    }
+    let project = CURSEFORGE_API.get_mod(id).await?;
+    cache_curseforge_projects([&project]);
+    Ok(project)
+}
+
+/// Get the Modrinth project of `id`, which can be its ID or slug, and cache it like [`curseforge_project`]
+pub async fn modrinth_project(id: &str) -> Result<Project> {
+    if let Some(project) = MODRINTH_PROJECTS.lock().unwrap().get(id) {
+        return Ok(project.clone());
+    }
+    let project = MODRINTH_API.get_project(id).await?;
+    cache_modrinth_projects([&project]);
+    Ok(project)
+}
+
+/// Get the GitHub repository `owner/repo`, and cache it like [`curseforge_project`]
+///
+/// Requests for repositories that were renamed or transferred are redirected, so this returns their new location.
+pub async fn github_repository(owner: &str, repo: &str) -> Result<Repository> {
+    let key = (owner.to_lowercase(), repo.to_lowercase());
+    if let Some(repository) = GITHUB_REPOS.lock().unwrap().get(&key) {
+        return Ok(repository.clone());
+    }
+    let repository = GITHUB_API.repos(owner, repo).get().await?;
+    GITHUB_REPOS.lock().unwrap().insert(key, repository.clone());
+    Ok(repository)
+}
+
+/// Cache the CurseForge `projects` that were fetched in bulk, so that [`curseforge_project`] doesn't fetch them again
+#[cfg(feature = "curseforge")]
+pub(crate) fn cache_curseforge_projects<'a>(projects: impl IntoIterator<Item = &'a CFMod>) {
+    let mut cache = CURSEFORGE_PROJECTS.lock().unwrap();
+    for project in projects {
+        cache.insert(project.id, project.clone());
+    }
+}
+
+/// Cache the Modrinth `projects` that were fetched in bulk, so that [`modrinth_project`] doesn't fetch them again
+pub(crate) fn cache_modrinth_projects<'a>(projects: impl IntoIterator<Item = &'a Project>) {
+    let mut cache = MODRINTH_PROJECTS.lock().unwrap();
+    for project in projects {
+        cache.insert(project.id.clone(), project.clone());
+        cache.insert(project.slug.clone(), project.clone());
+    }
+}
+
+/// Get the metadata of the project of `id`
+///
+/// The metadata of each project is only fetched once, and is cached for the rest of the program.
+pub async fn project_metadata(id: &ModIdentifier) -> Result<ProjectMeta> {
+    let id = id.unpinned();
+    if let Some(meta) = CACHE.lock().unwrap().get(&id) {
+        return Ok(meta.clone());
+    }
+
+    let meta = match &id {
+        #[cfg(feature = "curseforge")]
+        ModIdentifier::CurseForgeProject(project_id) => {
+            let project = curseforge_project(*project_id).await?;
+            ProjectMeta {
+                identifier: id.clone(),
+                name: project.name,
+                slug: project.slug,
+                description: project.summary,
+                icon_url: project.logo.map(|logo| logo.url),
+                page_url: Some(project.links.website_url),
+                downloads: Some(project.download_count),
+                game_versions: project
+                    .latest_files_indexes
+                    .iter()
+                    .map(|index| index.game_version.clone())
+                    .filter(|version| !version.is_empty())
+                    .collect_hashset()
+                    .into_iter()
+                    .collect_vec(),
+                loaders: project
+                    .latest_files_indexes
+                    .iter()
+                    .filter_map(|index| index.mod_loader.as_ref())
+                    .filter_map(|loader| ModLoader::from_str(&format!("{loader:?}")).ok())
+                    .collect_hashset()
+                    .into_iter()
+                    .collect_vec(),
+                gallery: project
+                    .screenshots
+                    .into_iter()
+                    .map(|screenshot| GalleryImage {
+                        url: screenshot.url,
+                        title: Some(screenshot.title).filter(|title| !title.is_empty()),
+                        description: Some(screenshot.description)
+                            .filter(|description| !description.is_empty()),
+                    })
+                    .collect_vec(),
+                authors: project
+                    .authors
+                    .into_iter()
+                    .map(|author| Author {
+                        name: author.name,
+                        role: None,
+                        url: Some(author.url),
+                    })
+                    .collect_vec(),
+            }
+        }
+        #[cfg(not(feature = "curseforge"))]
+        ModIdentifier::CurseForgeProject(_) => return Err(Error::CurseForgeDisabled),
+        ModIdentifier::ModrinthProject(project_id) => {
+            let (mut project, members) = try_join!(modrinth_project(project_id), async {
+                Ok(MODRINTH_API.list_project_team_members(project_id).await?)
+            })?;
+            project.gallery.sort_by_key(|image| image.ordering);
+            ProjectMeta {
+                identifier: id.clone(),
+                page_url: ProviderKind::Modrinth
+                    .base_url()
+                    .and_then(|base_url| format!("{base_url}/mod/{}", project.slug).parse().ok()),
+                name: project.title,
+                slug: project.slug,
+                description: project.description,
+                icon_url: project.icon_url,
+                downloads: Some(project.downloads),
+                game_versions: project.game_versions,
+                loaders: project
+                    .loaders
+                    .iter()
+                    .filter_map(|loader| ModLoader::from_str(loader).ok())
+                    .collect(),
+                gallery: project
+                    .gallery
+                    .into_iter()
+                    .map(|image| GalleryImage {
+                        url: image.url,
+                        title: image.title,
+                        description: image.description,
+                    })
+                    .collect(),
+                authors: members
+                    .into_iter()
+                    .map(|member| Author {
+                        url: ProviderKind::Modrinth.base_url().and_then(|base_url| {
+                            format!("{base_url}/user/{}", member.user.username)
+                                .parse()
+                                .ok()
+                        }),
+                        name: member.user.username,
+                        role: Some(member.role),
+                    })
+                    .collect(),
+            }
+        }
+        ModIdentifier::GitHubRepository(owner, repo) => {
+            let repository = github_repository(owner, repo).await?;
+            ProjectMeta {
+                identifier: id.clone(),
+                slug: format!("{owner}/{repo}"),
+                name: repository.name,
+                description: repository.description.unwrap_or_default(),
+                icon_url: repository
+                    .owner
+                    .as_ref()
+                    .map(|owner| owner.avatar_url.clone()),
+                page_url: repository.html_url,
+                downloads: None,
+                game_versions: Vec::new(),
+                loaders: Vec::new(),
+                gallery: Vec::new(),
+                authors: repository
+                    .owner
+                    .into_iter()
+                    .map(|owner| Author {
+                        name: owner.login,
+                        role: Some("Owner".to_owned()),
+                        url: Some(owner.html_url),
+                    })
+                    .collect(),
+            }
+        }
+        _ => unreachable!("The identifier was unpinned"),
+    };
+
+    CACHE.lock().unwrap().insert(id, meta.clone());
+    Ok(meta)
+}
+
+/// Fetch the metadata of every mod in `profile`, and their fallbacks, concurrently
+/// so that later calls to [`project_metadata`] and the functions using it are served from the cache
+///
+/// This should be called before operations that get the metadata of many mods one at a time.
+/// Returns the first error encountered, the metadata fetched before it is still cached.
+pub async fn prefetch(profile: &Profile) -> Result<()> {
+    try_join_all(
+        profile
+            .mods
+            .iter()
+            .flat_map(|mod_| [Some(&mod_.identifier), mod_.fallback.as_ref()])
+            .flatten()
+            .map(ModIdentifier::unpinned)
+            .collect::<HashSet<_>>()
+            .iter()
+            .map(project_metadata),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Get the images in the gallery of the project of `id`, in the order the project displays them
+///
+/// Only the URLs of the images are returned, they aren't downloaded.
+/// GitHub repositories don't have galleries, so they always return an empty list.
+pub async fn gallery(id: &ModIdentifier) -> Result<Vec<GalleryImage>> {
+    Ok(project_metadata(id).await?.gallery)
+}
+
+/// Get the authors of the project of `id`
+///
+/// Modrinth projects return their team members with their roles, CurseForge projects return their authors,
+/// and GitHub repositories return their owner since contributors aren't credited as authors.
+pub async fn authors(id: &ModIdentifier) -> Result<Vec<Author>> {
+    Ok(project_metadata(id).await?.authors)
+}
+
+/// How two identifiers were matched by [`project_match`], from the least to most confident
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchConfidence {
+    /// The projects' names are the same, ignoring case
+    Name,
+    /// The projects' slugs are the same, ignoring case
+    Slug,
+    /// Both the names and slugs are the same
+    NameAndSlug,
+    /// The projects have a Modrinth or CurseForge file with the same SHA-1 hash
+    FileHash,
+    /// The identifiers are from the same platform and have the same slug, so they are the same project
+    Identical,
+}
+
+/// Check whether `a` and `b` are likely to be the same project, possibly on different platforms,
+/// and how confident the match is
+///
+/// Identifiers from the same platform are compared using their project's slug, which is reliable.
+/// Platforms don't link to each other though, so across platforms this is a heuristic;
+/// the projects are considered the same if their names or slugs match ignoring case,
+/// or if any of their Modrinth and CurseForge files have the same SHA-1 hash.
+/// The hashes are only compared if the names and slugs don't match, since they need every file of both projects.
+/// Projects that were renamed and publish different builds on each platform won't be recognised,
+/// and unrelated projects that happen to share a name will be reported as the same.
+///
+/// Returns `None` if the projects don't match.
+pub async fn project_match(
+    a: &ModIdentifier,
+    b: &ModIdentifier,
+) -> Result<Option<MatchConfidence>> {
+    let (meta_a, meta_b) = try_join!(project_metadata(a), project_metadata(b))?;
+    let same_slug = meta_a.slug.eq_ignore_ascii_case(&meta_b.slug);
+    if a.provider() == b.provider() {
+        return Ok(same_slug.then_some(MatchConfidence::Identical));
+    }
+    match (
+        meta_a.name.trim().eq_ignore_ascii_case(meta_b.name.trim()),
+        same_slug,
+    ) {
+        (true, true) => return Ok(Some(MatchConfidence::NameAndSlug)),
+        (false, true) => return Ok(Some(MatchConfidence::Slug)),
+        (true, false) => return Ok(Some(MatchConfidence::Name)),
+        (false, false) => (),
+    }
+    let (hashes_a, hashes_b) = try_join!(
+        file_hashes(&meta_a.identifier),
+        file_hashes(&meta_b.identifier)
+    )?;
+    Ok((!hashes_a.is_disjoint(&hashes_b)).then_some(MatchConfidence::FileHash))
+}
+
+/// Check whether `a` and `b` are likely to be the same project, possibly on different platforms
+///
+/// This is the heuristic used wherever projects from different platforms are compared,
+/// such as when adding a mod that is already in the profile from another platform, see [`project_match`].
+pub async fn same_project(a: &ModIdentifier, b: &ModIdentifier) -> Result<bool> {
+    Ok(project_match(a, b).await?.is_some())
+}
+
+static HASHES: LazyLock<Mutex<HashMap<ModIdentifier, HashSet<String>>>> =
+    LazyLock::new(Default::default);
+
+/// Get the SHA-1 hashes of all the files of the project of the unpinned `id`
+///
+/// GitHub doesn't provide hashes, so GitHub repositories have none.
+///
+/// Like the metadata, the hashes of each project are only fetched once.
+async fn file_hashes(id: &ModIdentifier) -> Result<HashSet<String>> {
+    if let Some(hashes) = HASHES.lock().unwrap().get(id) {
+        return Ok(hashes.clone());
+    }
+    let hashes: HashSet<String> = match id {
+        #[cfg(feature = "curseforge")]
+        ModIdentifier::CurseForgeProject(project_id) => CURSEFORGE_API
+            .get_mod_files(*project_id)
+            .await?
+            .into_iter()
+            .flat_map(|file| file.hashes)
+            .filter(|hash| hash.algo == furse::structures::file_structs::HashAlgo::Sha1)
+            .map(|hash| hash.value)
+            .collect(),
+        #[cfg(not(feature = "curseforge"))]
+        ModIdentifier::CurseForgeProject(_) => return Err(Error::CurseForgeDisabled),
+        ModIdentifier::ModrinthProject(project_id) => MODRINTH_API
+            .list_versions(project_id)
+            .await?
+            .into_iter()
+            .flat_map(|version| version.files)
+            .map(|file| file.hashes.sha1)
+            .collect(),
+        _ => HashSet::new(),
+    };
+    HASHES.lock().unwrap().insert(id.clone(), hashes.clone());
+    Ok(hashes)
+}
+
+/// Cache the metadata and file hashes of the project of `identifier`, so that tests don't fetch them
+#[cfg(test)]
+pub(crate) fn cache(identifier: ModIdentifier, name: &str, slug: &str, hashes: &[&str]) {
+    HASHES.lock().unwrap().insert(
+        identifier.clone(),
+        hashes.iter().map(|&hash| hash.to_owned()).collect(),
+    );
+    CACHE.lock().unwrap().insert(
+        identifier.clone(),
+        ProjectMeta {
+            identifier,
+            name: name.to_owned(),
+            slug: slug.to_owned(),
+            description: String::new(),
+            icon_url: None,
+            page_url: None,
+            downloads: None,
+            game_versions: Vec::new(),
+            loaders: Vec::new(),
+            gallery: Vec::new(),
+            authors: Vec::new(),
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn project_matches() {
+        let sodium_mr = ModIdentifier::ModrinthProject("AANobbMI".to_owned());
+        let sodium_cf = ModIdentifier::CurseForgeProject(394468);
+        let renamed_cf = ModIdentifier::CurseForgeProject(1);
+        let other_cf = ModIdentifier::CurseForgeProject(2);
+        cache(sodium_mr.clone(), "Sodium", "sodium", &["a", "b"]);
+        cache(sodium_cf.clone(), "sodium ", "sodium", &[]);
+        cache(
+            renamed_cf.clone(),
+            "Sodium Renewed",
+            "sodium-renewed",
+            &["b"],
+        );
+        cache(other_cf.clone(), "Lithium", "lithium", &["c"]);
+
+        assert_eq!(
+            project_match(&sodium_mr, &sodium_cf).await.unwrap(),
+            Some(MatchConfidence::NameAndSlug)
+        );
+        assert_eq!(
+            project_match(&sodium_mr, &renamed_cf).await.unwrap(),
+            Some(MatchConfidence::FileHash)
+        );
+        assert_eq!(project_match(&sodium_mr, &other_cf).await.unwrap(), None);
+        // Projects on the same platform are only the same if their slugs are
+        assert!(!same_project(&sodium_cf, &renamed_cf).await.unwrap());
+        assert!(same_project(&sodium_cf, &sodium_cf).await.unwrap());
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{
+    config::structs::{ModIdentifier, Profile},
+    iter_ext::IterExt as _,
+    metadata::project_match,
+    CURSEFORGE_API, MODRINTH_API,
+};
+use ferinth::structures::{
+    project::ProjectType,
+    search::{Facet, Sort},
+};
+use furse::structures::file_structs::HashAlgo;
+use futures_util::future::try_join_all;
+
+pub use crate::metadata::MatchConfidence;
+
+#[derive(thiserror::Error, Debug)]
+#[error(transparent)]
+pub enum Error {
+    ModrinthError(#[from] ferinth::Error),
+    CurseForgeError(#[from] furse::Error),
+    MetadataError(#[from] crate::metadata::Error),
+}
+type Result<T> = std::result::Result<T, Error>;
+
+/// The number of search results compared with each CurseForge project that doesn't share a file with Modrinth
+const SEARCH_CANDIDATES: usize = 5;
+
+/// A Modrinth project that a CurseForge mod in a profile can be migrated to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationSuggestion {
+    /// The name of the mod in the profile
+    pub name: String,
+    pub curseforge_id: i32,
+    pub modrinth_id: String,
+    pub confidence: MatchConfidence,
+}
+
+/// Find the Modrinth projects that the CurseForge mods in `profile` are also available as
+///
+/// The hashes of the CurseForge projects' latest files are looked up on Modrinth first,
+/// and the rest of the mods are searched for by name. The candidates are compared with the CurseForge project
+/// using [`project_match`], and the most confident match is suggested.
+/// Mods without a matching candidate are not included.
+pub async fn suggest_modrinth_migrations(profile: &Profile) -> Result<Vec<MigrationSuggestion>> {
+    let cf_mods = profile
+        .mods
+        .iter()
+        .filter_map(|mod_| match mod_.identifier {
+            ModIdentifier::CurseForgeProject(id)
+            | ModIdentifier::PinnedCurseForgeProject(id, _) => Some((mod_.name.clone(), id)),
+            _ => None,
+        })
+        .collect_vec();
+    if cf_mods.is_empty() {
+        return Ok(Vec::new());
+    }
+    let cf_projects = CURSEFORGE_API
+        .get_mods(cf_mods.iter().map(|(_, id)| *id).collect_vec())
+        .await?;
+    crate::metadata::cache_curseforge_projects(&cf_projects);
+
+    // Look up the SHA-1 hashes of the latest files
+    let hashes = cf_projects
+        .iter()
+        .flat_map(|project| &project.latest_files)
+        .flat_map(|file| &file.hashes)
+        .filter(|hash| hash.algo == HashAlgo::Sha1)
+        .map(|hash| hash.value.clone())
+        .collect_vec();
+    let versions = if hashes.is_empty() {
+        Default::default()
+    } else {
+        MODRINTH_API.get_versions_from_hashes(hashes).await?
+    };
+
+    let suggestions = try_join_all(cf_projects.into_iter().filter_map(|project| {
+        let (name, _) = cf_mods.iter().find(|(_, id)| *id == project.id)?;
+        let versions = &versions;
+        Some(async move {
+            // The project sharing a file is the most likely candidate, otherwise search for it by name
+            let hash_match = project
+                .latest_files
+                .iter()
+                .flat_map(|file| &file.hashes)
+                .find_map(|hash| versions.get(&hash.value));
+            let candidates = match hash_match {
+                Some(version) => vec![version.project_id.clone()],
+                None => MODRINTH_API
+                    .search_paged(
+                        &project.name,
+                        &Sort::Relevance,
+                        SEARCH_CANDIDATES,
+                        0,
+                        vec![vec![Facet::ProjectType(ProjectType::Mod)]],
+                    )
+                    .await?
+                    .hits
+                    .into_iter()
+                    .map(|hit| hit.project_id)
+                    .collect_vec(),
+            };
+
+            let curseforge = ModIdentifier::CurseForgeProject(project.id);
+            let mut best = None::<(MatchConfidence, String)>;
+            for modrinth_id in candidates {
+                let modrinth = ModIdentifier::ModrinthProject(modrinth_id.clone());
+                if let Some(confidence) = project_match(&curseforge, &modrinth).await? {
+                    if best.as_ref().is_none_or(|(best, _)| confidence > *best) {
+                        best = Some((confidence, modrinth_id));
+                    }
+                }
+            }
+            Ok::<_, Error>(best.map(|(confidence, modrinth_id)| MigrationSuggestion {
+                name: name.clone(),
+                curseforge_id: project.id,
+                modrinth_id,
+                confidence,
+            }))
+        })
+    }))
+    .await?;
+
+    Ok(suggestions.into_iter().flatten().collect_vec())
+}
@@ -0,0 +1,103 @@
+use crate::{
+    config::structs::ModLoader, get_minecraft_dir, modpack::prism::prism_instance_minecraft_dir,
+    HOME,
+};
+use std::{path::PathBuf, str::FromStr};
+
+/// Gets the default Minecraft instance directory if it exists
+pub fn find_minecraft_dir() -> Option<PathBuf> {
+    Some(get_minecraft_dir()).filter(|dir| dir.is_dir())
+}
+
+/// The names of the data directories of launchers that store instances in an `instances` folder
+const INSTANCE_LAUNCHERS: [&str; 3] = ["PrismLauncher", "PolyMC", "multimc"];
+
+/// Gets the Minecraft directories of the instances of MultiMC-like launchers (Prism Launcher, PolyMC, and MultiMC)
+///
+/// Only launchers installed to their default data directory are found, so portable installations are not detected.
+pub fn find_launcher_instance_dirs() -> Vec<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let data_dir = HOME.join("AppData").join("Roaming");
+    #[cfg(target_os = "macos")]
+    let data_dir = HOME.join("Library").join("Application Support");
+    #[cfg(target_os = "linux")]
+    let data_dir = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| HOME.join(".local").join("share"));
+
+    INSTANCE_LAUNCHERS
+        .iter()
+        .filter_map(|launcher| std::fs::read_dir(data_dir.join(launcher).join("instances")).ok())
+        .flatten()
+        .filter_map(|entry| prism_instance_minecraft_dir(&entry.ok()?.path()))
+        .collect()
+}
+
+/// Guess the version number of a mod from its `filename`, as a last resort when its platform doesn't provide one
+///
+/// Mod loader names and game versions like `mc1.20.1` are skipped, as is build metadata after a `+`.
+/// If there are several candidates, the first one that doesn't look like a game version is picked,
+/// e.g. `0.5.3` from `sodium-fabric-mc1.20.1-0.5.3.jar` or `15.2.0.27` from `jei-1.20.1-forge-15.2.0.27.jar`.
+pub fn parse_version_from_filename(filename: &str) -> Option<String> {
+    let is_game_version = |part: &str| {
+        let mut numbers = part.split('.');
+        numbers.next() == Some("1")
+            && numbers.clone().count() <= 2
+            && numbers.all(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+    };
+    let stem = filename
+        .strip_suffix(".jar")
+        .or_else(|| filename.strip_suffix(".zip"))
+        .unwrap_or(filename);
+    let candidates = stem
+        .split(['-', '_', ' '])
+        .skip(1) // The mod's name
+        .filter(|part| !part.starts_with("mc") && ModLoader::from_str(part).is_err())
+        .filter_map(|part| {
+            let part = part.split('+').next()?.trim_start_matches(['v', 'V']);
+            (part.contains('.') && part.starts_with(|c: char| c.is_ascii_digit())).then_some(part)
+        })
+        .collect::<Vec<_>>();
+    candidates
+        .iter()
+        .find(|part| !is_game_version(part))
+        .or(candidates.last())
+        .map(|part| part.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_from_filename() {
+        for (filename, version) in [
+            ("sodium-fabric-mc1.20.1-0.5.3.jar", "0.5.3"),
+            ("jei-1.20.1-forge-15.2.0.27.jar", "15.2.0.27"),
+            ("fabric-api-0.92.0+1.20.1.jar", "0.92.0"),
+            ("sodium-extra-0.5.1+mc1.20.1-build.131.jar", "0.5.1"),
+            ("journeymap-1.20.1-5.9.18-fabric.jar", "5.9.18"),
+            ("Xaeros_Minimap_23.9.7_Fabric_1.20.4.jar", "23.9.7"),
+            ("create-1.20.1-0.5.1.f.jar", "0.5.1.f"),
+            ("ferritecore-6.0.1-fabric.jar", "6.0.1"),
+            ("modmenu-7.2.2.jar", "7.2.2"),
+            ("Terralith_1.20_v2.4.11.jar", "2.4.11"),
+            // Versions that look like game versions are used if there's nothing else
+            ("iris-mc1.20.1-1.6.4.jar", "1.6.4"),
+            ("FreshAnimations_v1.9.1.zip", "1.9.1"),
+        ] {
+            assert_eq!(
+                parse_version_from_filename(filename).as_deref(),
+                Some(version),
+                "{filename}"
+            );
+        }
+    }
+
+    #[test]
+    fn no_version_in_filename() {
+        for filename in ["mymod.jar", "OptiFine_HD_U_I6.jar", "voicechat-fabric.jar"] {
+            assert_eq!(parse_version_from_filename(filename), None, "{filename}");
+        }
+    }
+}
@@ -1,8 +1,9 @@
 use crate::{
     config::structs::{Config, ModpackIdentifier},
-    CURSEFORGE_API, MODRINTH_API,
+    metadata,
 };
 use ferinth::structures::project::{Project, ProjectType};
+#[cfg(feature = "curseforge")]
 use furse::structures::mod_structs::Mod;
 use reqwest::StatusCode;
 
@@ -17,10 +18,12 @@ pub enum Error {
     NotAModpack,
     #[error("Modrinth: {0}")]
     ModrinthError(ferinth::Error),
+    #[cfg(feature = "curseforge")]
     #[error("CurseForge: {0}")]
     CurseForgeError(furse::Error),
 }
 
+#[cfg(feature = "curseforge")]
 impl From<furse::Error> for Error {
     fn from(err: furse::Error) -> Self {
         if let furse::Error::ReqwestError(source) = &err {
@@ -49,11 +52,26 @@ impl From<ferinth::Error> for Error {
     }
 }
 
+impl From<metadata::Error> for Error {
+    fn from(err: metadata::Error) -> Self {
+        match err {
+            metadata::Error::ModrinthError(err) => err.into(),
+            #[cfg(feature = "curseforge")]
+            metadata::Error::CurseForgeError(err) => err.into(),
+            // Only Modrinth and CurseForge projects are fetched
+            metadata::Error::CurseForgeDisabled | metadata::Error::GitHubError(_) => {
+                unreachable!()
+            }
+        }
+    }
+}
+
 /// Check if the project of `project_id` exists and is a modpack
 ///
 /// Returns the project struct
+#[cfg(feature = "curseforge")]
 pub async fn curseforge(config: &Config, project_id: i32) -> Result<Mod> {
-    let project = CURSEFORGE_API.get_mod(project_id).await?;
+    let project = metadata::curseforge_project(project_id).await?;
 
     // Check if project has already been added
     if config.modpacks.iter().any(|modpack| {
@@ -74,7 +92,7 @@ pub async fn curseforge(config: &Config, project_id: i32) -> Result<Mod> {
 ///
 /// Returns the project struct
 pub async fn modrinth(config: &Config, project_id: &str) -> Result<Project> {
-    let project = MODRINTH_API.get_project(project_id).await?;
+    let project = metadata::modrinth_project(project_id).await?;
 
     // Check if project has already been added
     if config.modpacks.iter().any(|modpack| {
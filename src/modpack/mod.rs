@@ -1,6 +1,8 @@
 pub mod add;
 pub mod curseforge;
 pub mod modrinth;
+pub mod packwiz;
+pub mod prism;
 
 pub use zip_extensions::{zip_create_from_directory, zip_extract};
 
@@ -0,0 +1,301 @@
+pub mod structs;
+
+use crate::{
+    config::{
+        filters::{FileSelection, ProfileParameters as _},
+        structs::{ContentType, Mod, ModIdentifier, ModLoader, ModOrigin, Profile, ProfileLocked},
+    },
+    upgrade::mod_downloadable::{self, resolve_all, ResolutionPlan},
+};
+use sha1::{Digest, Sha1};
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, read_to_string, write},
+    path::{Path, PathBuf},
+};
+use structs::{
+    CurseForgeUpdate, Download, Index, IndexFile, IndexReference, ModFile, ModrinthUpdate, Pack,
+    Update,
+};
+use url::Url;
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub enum Error {
+    IOError(#[from] std::io::Error),
+    TomlError(#[from] toml::de::Error),
+    TomlSerError(#[from] toml::ser::Error),
+    ProfileLocked(#[from] ProfileLocked),
+}
+type Result<T> = std::result::Result<T, Error>;
+
+impl Update {
+    /// Get the identifier of the project this file is from, pinned to the file
+    ///
+    /// Returns `None` if packwiz doesn't track the file on Modrinth or CurseForge.
+    pub fn identifier(&self) -> Option<ModIdentifier> {
+        if let Some(modrinth) = &self.modrinth {
+            Some(ModIdentifier::PinnedModrinthProject(
+                modrinth.mod_id.clone(),
+                modrinth.version.clone(),
+            ))
+        } else {
+            self.curseforge.as_ref().map(|curseforge| {
+                ModIdentifier::PinnedCurseForgeProject(curseforge.project_id, curseforge.file_id)
+            })
+        }
+    }
+}
+
+/// The mods found when importing a packwiz pack with [`import_packwiz`]
+#[derive(Debug, Clone, Default)]
+pub struct ImportSummary {
+    /// The names of the mods added to the profile
+    pub imported: Vec<String>,
+    /// The names of the mods that were already in the profile
+    pub already_added: Vec<String>,
+    /// The names of the mods that were skipped because they matched an exclusion
+    pub excluded: Vec<String>,
+    /// The names and download URLs of the mods that packwiz tracks by URL, which can't be mapped to a platform
+    pub unmapped: Vec<(String, Option<Url>)>,
+}
+
+/// Import the mods of the packwiz pack in `dir` into `profile`
+///
+/// The mods are pinned to the exact files that packwiz tracks, use [`Profile::unpin_all`] to let them upgrade.
+/// Their [origin](crate::config::structs::Mod::origin) is the pack, so they can be found using [`Profile::mods_from_origin`].
+/// Files in directories other than `mods` are imported as resource packs, shader packs, or data packs if their
+/// directory has the same name as the default output directory for that content type.
+///
+/// Mods that match any identifier in `exclude`, in the same way as [`Mod::matches`], are skipped.
+/// Returns [`Error::ProfileLocked`] without importing anything if the profile is locked.
+pub fn import_packwiz(
+    dir: &Path,
+    profile: &mut Profile,
+    exclude: &[&str],
+) -> Result<ImportSummary> {
+    profile.check_unlocked()?;
+    let pack: Pack = toml::from_str(&read_to_string(dir.join("pack.toml"))?)?;
+    let index_path = dir.join(&pack.index.file);
+    let index: Index = toml::from_str(&read_to_string(&index_path)?)?;
+    let index_dir = index_path.parent().unwrap_or(dir);
+
+    let mut summary = ImportSummary::default();
+    for file in index.files.into_iter().filter(|file| file.metafile) {
+        let mod_file: ModFile = toml::from_str(&read_to_string(index_dir.join(&file.file))?)?;
+
+        let Some(identifier) = mod_file.update.identifier() else {
+            summary
+                .unmapped
+                .push((mod_file.name, mod_file.download.url));
+            continue;
+        };
+
+        let candidate = Mod::new(mod_file.name.clone(), identifier.clone(), Vec::new(), false);
+        if exclude.iter().any(|excluded| candidate.matches(excluded)) {
+            summary.excluded.push(mod_file.name);
+            continue;
+        }
+
+        if profile
+            .mods
+            .iter()
+            .any(|mod_| mod_.identifier.unpinned() == identifier.unpinned())
+        {
+            summary.already_added.push(mod_file.name);
+            continue;
+        }
+
+        let content_type = match file
+            .file
+            .parent()
+            .and_then(Path::file_name)
+            .and_then(|dir| dir.to_str())
+        {
+            Some("resourcepacks") => ContentType::ResourcePack,
+            Some("shaderpacks") => ContentType::ShaderPack,
+            Some("datapacks") => ContentType::DataPack,
+            _ => ContentType::Mod,
+        };
+        let mod_ = profile.push_mod(mod_file.name.clone(), identifier, false, Vec::new());
+        mod_.content_type = content_type;
+        mod_.origin = Some(ModOrigin::Modpack {
+            name: pack.name.clone(),
+            version: pack.version.clone(),
+        });
+        summary.imported.push(mod_file.name);
+    }
+    Ok(summary)
+}
+
+/// The mods written when exporting a profile with [`export_packwiz`]
+#[derive(Debug, Default)]
+pub struct ExportSummary {
+    /// The names of the mods written to the pack
+    pub exported: Vec<String>,
+    /// The names of the mods whose file doesn't have a SHA-1 hash, which packwiz requires (e.g. GitHub releases)
+    pub without_hash: Vec<String>,
+    /// The mods that could not be resolved, with their errors (e.g. CurseForge mods that deny distribution)
+    pub errors: Vec<(String, mod_downloadable::Error)>,
+}
+
+/// Export the enabled mods of `profile` as a packwiz pack in `out_dir`
+///
+/// The files are resolved using `file_selection`, and the pack tracks those exact files.
+/// The Minecraft version is written to the `pack.toml`, along with the mod loader's version
+/// if the profile has a [`loader_version`](Profile::loader_version).
+pub async fn export_packwiz(
+    profile: &Profile,
+    out_dir: &Path,
+    file_selection: FileSelection,
+) -> Result<ExportSummary> {
+    export_plan_packwiz(profile, resolve_all(profile, file_selection).await, out_dir)
+}
+
+/// Export the files resolved for `profile` in `plan` as a packwiz pack in `out_dir`, like [`export_packwiz`]
+pub fn export_plan_packwiz(
+    profile: &Profile,
+    plan: ResolutionPlan,
+    out_dir: &Path,
+) -> Result<ExportSummary> {
+    let mut summary = ExportSummary {
+        errors: plan.errors,
+        ..Default::default()
+    };
+
+    let mut index = Index {
+        hash_format: Some("sha1".to_owned()),
+        files: Vec::new(),
+    };
+    for resolved in plan.mods {
+        let download_data = resolved.download_data;
+        let Some(hash) = download_data.sha1.clone() else {
+            summary.without_hash.push(resolved.name);
+            continue;
+        };
+
+        let mut download = Download {
+            url: Some(download_data.download_url.clone()),
+            hash_format: Some("sha1".to_owned()),
+            hash: Some(hash),
+            mode: None,
+        };
+        let mut update = Update::default();
+        match (&resolved.source.unpinned(), &download_data.file_id) {
+            (ModIdentifier::ModrinthProject(mod_id), Some(version)) => {
+                update.modrinth = Some(ModrinthUpdate {
+                    mod_id: mod_id.clone(),
+                    version: version.clone(),
+                })
+            }
+            (ModIdentifier::CurseForgeProject(project_id), Some(file_id)) => {
+                if let Ok(file_id) = file_id.parse() {
+                    download.url = None;
+                    download.mode = Some("metadata:curseforge".to_owned());
+                    update.curseforge = Some(CurseForgeUpdate {
+                        project_id: *project_id,
+                        file_id,
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        let content_type = profile
+            .mods
+            .iter()
+            .find(|mod_| mod_.name == resolved.name)
+            .map_or(ContentType::Mod, |mod_| mod_.content_type);
+        let path = PathBuf::from(match content_type {
+            ContentType::Mod => "mods",
+            ContentType::ResourcePack => "resourcepacks",
+            ContentType::ShaderPack => "shaderpacks",
+            ContentType::DataPack => "datapacks",
+        })
+        .join(format!("{}.pw.toml", slugify(&resolved.name)));
+
+        let contents = toml::to_string(&ModFile {
+            name: resolved.name.clone(),
+            filename: download_data.filename(),
+            side: Some("both".to_owned()),
+            download,
+            update,
+        })?;
+        create_dir_all(out_dir.join(path.parent().unwrap_or(Path::new(""))))?;
+        write(out_dir.join(&path), &contents)?;
+        index.files.push(IndexFile {
+            file: path,
+            hash: Some(format!("{:x}", Sha1::digest(&contents))),
+            metafile: true,
+        });
+        summary.exported.push(resolved.name);
+    }
+
+    let index_contents = toml::to_string(&index)?;
+    write(out_dir.join("index.toml"), &index_contents)?;
+
+    let mut versions = BTreeMap::new();
+    if let Some(version) = profile.filters.game_versions().and_then(|v| v.first()) {
+        versions.insert("minecraft".to_owned(), version.clone());
+    }
+    if let (Some(loader), Some(loader_version)) = (
+        profile.filters.mod_loader().and_then(packwiz_loader),
+        &profile.loader_version,
+    ) {
+        versions.insert(loader.to_owned(), loader_version.clone());
+    }
+    let pack = Pack {
+        name: profile.name.clone(),
+        version: None,
+        pack_format: Some("packwiz:1.1.0".to_owned()),
+        index: IndexReference {
+            file: "index.toml".into(),
+            hash_format: Some("sha1".to_owned()),
+            hash: Some(format!("{:x}", Sha1::digest(&index_contents))),
+        },
+        versions,
+    };
+    write(out_dir.join("pack.toml"), toml::to_string(&pack)?)?;
+
+    Ok(summary)
+}
+
+/// The key of `loader`'s version in the `versions` of a `pack.toml`, if packwiz supports it
+fn packwiz_loader(loader: &ModLoader) -> Option<&'static str> {
+    match loader {
+        ModLoader::Quilt => Some("quilt"),
+        ModLoader::Fabric => Some("fabric"),
+        ModLoader::Forge => Some("forge"),
+        ModLoader::NeoForge => Some("neoforge"),
+        ModLoader::LiteLoader => Some("liteloader"),
+        ModLoader::Rift => None,
+    }
+}
+
+/// Convert `name` into a filename like packwiz does, e.g. `Fabric API` into `fabric-api`
+fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import_respects_lock() {
+        let mut profile = Profile::new(
+            "Profile".to_owned(),
+            "mods".into(),
+            vec!["1.20.1".to_owned()],
+            ModLoader::Fabric,
+        );
+        profile.locked = true;
+        let result = import_packwiz(Path::new("pack"), &mut profile, &[]);
+        assert!(matches!(result, Err(Error::ProfileLocked(_))));
+        assert!(profile.mods.is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::PathBuf};
+use url::Url;
+
+/// The `pack.toml` at the root of a packwiz pack
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct Pack {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pack_format: Option<String>,
+    pub index: IndexReference,
+    /// The versions of Minecraft and the mod loader, e.g. `minecraft = "1.21.1"`
+    #[serde(default)]
+    pub versions: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct IndexReference {
+    /// The path of the index file, relative to the `pack.toml`
+    pub file: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+/// The index of all the files in a packwiz pack
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct Index {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash_format: Option<String>,
+    #[serde(default)]
+    pub files: Vec<IndexFile>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct IndexFile {
+    /// The path of the file, relative to the index file
+    pub file: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// Whether this file is the metadata of a mod, rather than a file to copy into the instance
+    #[serde(default)]
+    pub metafile: bool,
+}
+
+/// The metadata of a mod in a packwiz pack, stored in a `.pw.toml` file
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ModFile {
+    pub name: String,
+    pub filename: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub side: Option<String>,
+    pub download: Download,
+    #[serde(default)]
+    pub update: Update,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct Download {
+    /// The URL of the file, which isn't present for CurseForge files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<Url>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// How the file is downloaded, e.g. `metadata:curseforge` for CurseForge files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct Update {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modrinth: Option<ModrinthUpdate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub curseforge: Option<CurseForgeUpdate>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct ModrinthUpdate {
+    pub mod_id: String,
+    /// The ID of the version installed
+    pub version: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct CurseForgeUpdate {
+    pub project_id: i32,
+    /// The ID of the file installed
+    pub file_id: i32,
+}
@@ -0,0 +1,236 @@
+use super::packwiz::structs::ModFile;
+use crate::config::structs::{ModIdentifier, ModLoader, Profile};
+use serde::Deserialize;
+use std::{
+    collections::HashSet,
+    fs::{read_dir, read_to_string, File},
+    io::{BufReader, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub enum Error {
+    IOError(#[from] std::io::Error),
+    TomlError(#[from] toml::de::Error),
+}
+type Result<T> = std::result::Result<T, Error>;
+
+/// The `mmc-pack.json` of a MultiMC or Prism Launcher instance, which lists the components it is built from
+#[derive(Deserialize, Debug, Clone)]
+struct MmcPack {
+    #[serde(default)]
+    components: Vec<Component>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Component {
+    uid: String,
+    version: Option<String>,
+}
+
+/// Gets the Minecraft directory of the MultiMC-like launcher instance in `instance_dir`, if it exists
+pub(crate) fn prism_instance_minecraft_dir(instance_dir: &Path) -> Option<PathBuf> {
+    // Newer instances use `minecraft`, while older ones use `.minecraft`
+    ["minecraft", ".minecraft"]
+        .iter()
+        .map(|dir| instance_dir.join(dir))
+        .find(|dir| dir.is_dir())
+}
+
+/// Gets the mods directory of the MultiMC-like launcher instance in `instance_dir`
+///
+/// Defaults to `.minecraft/mods` if the instance's Minecraft directory doesn't exist yet.
+pub fn prism_instance_mods_dir(instance_dir: impl AsRef<Path>) -> PathBuf {
+    prism_instance_minecraft_dir(instance_dir.as_ref())
+        .unwrap_or_else(|| instance_dir.as_ref().join(".minecraft"))
+        .join("mods")
+}
+
+/// The details of a MultiMC-like launcher instance read by [`read_prism_instance()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrismInstance {
+    /// The name of the instance shown in the launcher
+    pub name: Option<String>,
+    pub game_version: Option<String>,
+    pub mod_loader: Option<ModLoader>,
+    pub mods_dir: PathBuf,
+}
+
+impl PrismInstance {
+    /// Create a profile that downloads to this instance's mods directory, using the instance's name if `name` is `None`
+    ///
+    /// Returns `None` if the instance's game version or mod loader is unknown.
+    pub fn to_profile(&self, name: Option<String>) -> Option<Profile> {
+        Some(Profile::new(
+            name.or_else(|| self.name.clone())?,
+            self.mods_dir.clone(),
+            vec![self.game_version.clone()?],
+            self.mod_loader?,
+        ))
+    }
+}
+
+/// Read the name, game version, and mod loader of the MultiMC-like launcher instance in `instance_dir`
+///
+/// The game version and mod loader are read from `mmc-pack.json`, and the name from `instance.cfg`.
+/// Fails if `mmc-pack.json` can't be read, while the name is `None` if `instance.cfg` can't be read.
+pub fn read_prism_instance(instance_dir: impl AsRef<Path>) -> std::io::Result<PrismInstance> {
+    let instance_dir = instance_dir.as_ref();
+    let pack: MmcPack = serde_json::from_reader(BufReader::new(File::open(
+        instance_dir.join("mmc-pack.json"),
+    )?))?;
+
+    let name = read_to_string(instance_dir.join("instance.cfg"))
+        .ok()
+        .and_then(|cfg| {
+            cfg.lines()
+                .find_map(|line| line.strip_prefix("name="))
+                .map(|name| name.trim().to_owned())
+        });
+    let game_version = pack
+        .components
+        .iter()
+        .find(|component| component.uid == "net.minecraft")
+        .and_then(|component| component.version.clone());
+    let mod_loader = pack
+        .components
+        .iter()
+        .find_map(|component| match component.uid.as_str() {
+            "net.fabricmc.fabric-loader" => Some(ModLoader::Fabric),
+            "org.quiltmc.quilt-loader" => Some(ModLoader::Quilt),
+            "net.minecraftforge" => Some(ModLoader::Forge),
+            "net.neoforged" => Some(ModLoader::NeoForge),
+            "com.mumfrey.liteloader" => Some(ModLoader::LiteLoader),
+            _ => None,
+        });
+
+    Ok(PrismInstance {
+        name,
+        game_version,
+        mod_loader,
+        mods_dir: prism_instance_mods_dir(instance_dir),
+    })
+}
+
+/// The mods found in a MultiMC or Prism Launcher instance by [`import_prism_instance`]
+#[derive(Debug, Clone, Default)]
+pub struct InstanceImport {
+    /// The version of Minecraft the instance uses
+    pub game_version: Option<String>,
+    /// The mod loader the instance uses
+    pub mod_loader: Option<ModLoader>,
+    /// The names of the mods that the launcher installed from Modrinth or CurseForge,
+    /// with their identifiers pinned to the installed files
+    pub identifiers: Vec<(String, ModIdentifier)>,
+    /// The JAR files in the mods directory that can't be mapped to a platform, e.g. ones placed there manually
+    ///
+    /// These can be identified using [`scan`](crate::scan()),
+    /// or moved to the profile's [`user` folder](crate::config::structs::Profile::user_mods_path).
+    pub unmatched: Vec<PathBuf>,
+}
+
+/// Read the game version, mod loader, and mods of the MultiMC or Prism Launcher instance in `instance_dir`
+///
+/// Prism Launcher records the mods it installs in the `mods/.index` directory in the same format as packwiz,
+/// which is used to find their identifiers. MultiMC doesn't record this, so all its mods are unmatched.
+/// The identifiers are pinned to the installed files, use [`ModIdentifier::unpinned`] to let them upgrade.
+pub fn import_prism_instance(instance_dir: &Path) -> Result<InstanceImport> {
+    let mut import = InstanceImport::default();
+
+    // The mods can still be imported if the launcher didn't write `mmc-pack.json`
+    match read_prism_instance(instance_dir) {
+        Ok(instance) => {
+            import.game_version = instance.game_version;
+            import.mod_loader = instance.mod_loader;
+        }
+        Err(err) if err.kind() == ErrorKind::NotFound => {}
+        Err(err) => return Err(err.into()),
+    }
+
+    let mods_dir = prism_instance_mods_dir(instance_dir);
+    if !mods_dir.is_dir() {
+        return Ok(import);
+    }
+
+    let mut indexed = HashSet::new();
+    let index_dir = mods_dir.join(".index");
+    if index_dir.is_dir() {
+        for entry in read_dir(index_dir)? {
+            let path = entry?.path();
+            if !path.to_string_lossy().ends_with(".pw.toml") {
+                continue;
+            }
+            let mod_file: ModFile = toml::from_str(&read_to_string(path)?)?;
+            if let Some(identifier) = mod_file.update.identifier() {
+                indexed.insert(mod_file.filename);
+                import.identifiers.push((mod_file.name, identifier));
+            }
+        }
+    }
+
+    for entry in read_dir(&mods_dir)? {
+        let path = entry?.path();
+        if path.is_file()
+            && path.extension().is_some_and(|ext| ext == "jar")
+            && path
+                .file_name()
+                .is_some_and(|name| !indexed.contains(&*name.to_string_lossy()))
+        {
+            import.unmatched.push(path);
+        }
+    }
+    import.identifiers.sort_by(|a, b| a.0.cmp(&b.0));
+    import.unmatched.sort();
+    Ok(import)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, remove_dir_all, write};
+
+    #[test]
+    fn import_instance() {
+        let instance_dir =
+            std::env::temp_dir().join(format!("libium-prism-{}", std::process::id()));
+        let mods_dir = instance_dir.join("minecraft").join("mods");
+        create_dir_all(mods_dir.join(".index")).unwrap();
+        write(
+            instance_dir.join("mmc-pack.json"),
+            r#"{"components": [
+                {"uid": "net.minecraft", "version": "1.20.1"},
+                {"uid": "net.fabricmc.fabric-loader", "version": "0.15.0"}
+            ]}"#,
+        )
+        .unwrap();
+        write(
+            mods_dir.join(".index").join("sodium.pw.toml"),
+            r#"
+                name = "Sodium"
+                filename = "sodium-fabric-0.5.3.jar"
+                [download]
+                [update.modrinth]
+                mod-id = "AANobbMI"
+                version = "OihdIimA"
+            "#,
+        )
+        .unwrap();
+        write(mods_dir.join("sodium-fabric-0.5.3.jar"), "").unwrap();
+        write(mods_dir.join("manual.jar"), "").unwrap();
+
+        let import = import_prism_instance(&instance_dir).unwrap();
+        remove_dir_all(&instance_dir).unwrap();
+
+        assert_eq!(import.game_version.as_deref(), Some("1.20.1"));
+        assert_eq!(import.mod_loader, Some(ModLoader::Fabric));
+        assert_eq!(
+            import.identifiers,
+            [(
+                "Sodium".to_owned(),
+                ModIdentifier::PinnedModrinthProject("AANobbMI".to_owned(), "OihdIimA".to_owned())
+            )]
+        );
+        assert_eq!(import.unmatched, [mods_dir.join("manual.jar")]);
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{
+    add::{add_with_warnings, parse_id, AddWarning, Error},
+    config::structs::{ModIdentifier, ModOrigin, Profile},
+};
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// The curated presets, embedded from `presets.json` so that they can be updated without changing any code
+pub static PRESETS: LazyLock<Vec<Preset>> = LazyLock::new(|| {
+    serde_json::from_str(include_str!("presets.json")).expect("Embedded presets are invalid")
+});
+
+/// A curated list of mods to start a profile with
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Preset {
+    pub name: String,
+    pub description: String,
+    /// Identifiers in the format accepted by `add::parse_id()`
+    pub mods: Vec<String>,
+}
+
+impl Preset {
+    /// Parse the preset's identifiers, returning the first invalid one as [`Error::InvalidIdentifier`]
+    pub fn identifiers(&self) -> Result<Vec<ModIdentifier>, Error> {
+        self.mods.iter().cloned().map(parse_id).collect()
+    }
+}
+
+/// Get the preset called `name`
+pub fn get(name: &str) -> Option<&'static Preset> {
+    PRESETS
+        .iter()
+        .find(|preset| preset.name.eq_ignore_ascii_case(name))
+}
+
+/// Add the mods in `preset` to `profile`, with the usual compatibility checks
+///
+/// Returns the same successful and unsuccessful mods as `add()`,
+/// so mods incompatible with the profile's game version or mod loader are returned with `Error::Incompatible`.
+/// The [origin](crate::config::structs::Mod::origin) of the added mods is the preset.
+pub async fn apply_preset(
+    profile: &mut Profile,
+    preset: &Preset,
+) -> Result<(Vec<String>, Vec<(String, Error)>), Error> {
+    let (added, errors) =
+        add_with_warnings(profile, preset.identifiers()?, true, false, vec![]).await?;
+    for added in &added {
+        // Projects merged into an existing mod as its fallback weren't added from the preset
+        if added
+            .warnings
+            .iter()
+            .any(|warning| matches!(warning, AddWarning::MergedAsFallback(_)))
+        {
+            continue;
+        }
+        if let Some(mod_) = profile.mods.iter_mut().find(|mod_| mod_.name == added.name) {
+            mod_.origin = Some(ModOrigin::Preset(preset.name.clone()));
+        }
+    }
+    Ok((added.into_iter().map(|added| added.name).collect(), errors))
+}
@@ -0,0 +1,69 @@
+use crate::config::structs::{Profile, ProfileLocked};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("No mod in the profile matches this identifier")]
+    DoesNotExist,
+    #[error("The identifier matches more than one mod: {}", _0.join(", "))]
+    /// The user should use a more specific identifier, such as the mod's project ID.
+    Ambiguous(Vec<String>),
+    #[error("The profile is locked")]
+    /// The lock can be overridden using [`Profile::override_lock`].
+    ProfileLocked,
+}
+type Result<T> = std::result::Result<T, Error>;
+
+impl From<ProfileLocked> for Error {
+    fn from(_: ProfileLocked) -> Self {
+        Error::ProfileLocked
+    }
+}
+
+/// Remove the mod matching `identifier` from `profile`, and return its name
+///
+/// `identifier` is matched using [`Mod::matches`](crate::config::structs::Mod::matches),
+/// so it can be the mod's name or slug, or its project ID or GitHub repository.
+/// Returns [`Error::DoesNotExist`] if no mod matches, and [`Error::Ambiguous`] without removing anything
+/// if more than one mod does, e.g. when two mods from different platforms have the same name.
+pub fn remove_single(profile: &mut Profile, identifier: &str) -> Result<String> {
+    profile.check_unlocked()?;
+    let matching = profile
+        .mods
+        .iter()
+        .enumerate()
+        .filter(|(_, mod_)| mod_.matches(identifier))
+        .collect::<Vec<_>>();
+    match matching.as_slice() {
+        [] => Err(Error::DoesNotExist),
+        [(index, _)] => {
+            let index = *index;
+            Ok(profile.mods.remove(index).name)
+        }
+        _ => Err(Error::Ambiguous(
+            matching
+                .into_iter()
+                .map(|(_, mod_)| mod_.name.clone())
+                .collect(),
+        )),
+    }
+}
+
+/// Remove the mods matching `identifiers` from `profile` like [`remove_single()`]
+///
+/// Returns the names of the mods that were removed, and the identifiers that couldn't be removed with their errors,
+/// both in the same order as `identifiers`.
+/// If the profile is locked, nothing is removed and every identifier is returned with [`Error::ProfileLocked`].
+pub fn remove_multiple(
+    profile: &mut Profile,
+    identifiers: &[&str],
+) -> (Vec<String>, Vec<(String, Error)>) {
+    let mut removed = Vec::new();
+    let mut errors = Vec::new();
+    for identifier in identifiers {
+        match remove_single(profile, identifier) {
+            Ok(name) => removed.push(name),
+            Err(err) => errors.push(((*identifier).to_owned(), err)),
+        }
+    }
+    (removed, errors)
+}
@@ -0,0 +1,213 @@
+use super::Result;
+use crate::config::structs::{Mod, ModIdentifier, Profile};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+use zip::ZipArchive;
+
+/// Mod IDs that refer to the game, the loader, or Java rather than other mods
+const PLATFORM_IDS: [&str; 7] = [
+    "minecraft",
+    "java",
+    "fabricloader",
+    "quilt_loader",
+    "forge",
+    "neoforge",
+    "mcp",
+];
+
+/// The dependencies declared in the metadata embedded in a mod's JAR file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EmbeddedDependencies {
+    /// The mod's own ID
+    pub mod_id: Option<String>,
+    /// The IDs of the mods that this mod requires
+    pub required: Vec<String>,
+    /// The IDs of the mods that this mod is incompatible with
+    pub breaks: Vec<String>,
+}
+
+/// A difference between the dependencies a mod's platform declares and the ones embedded in its JAR file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyDiscrepancy {
+    /// The JAR requires the mod with this ID, but the platform doesn't declare it
+    MissingFromProvider(String),
+    /// The platform declares this dependency, but the JAR doesn't require it
+    MissingFromJar(ModIdentifier),
+    /// The JAR is incompatible with this mod in the profile
+    BreaksInstalled(String),
+}
+
+#[derive(Deserialize)]
+struct FabricModJson {
+    id: Option<String>,
+    #[serde(default)]
+    depends: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    breaks: HashMap<String, serde_json::Value>,
+}
+
+/// Read the dependencies declared in the `fabric.mod.json` or `mods.toml` of the JAR file at `path`
+///
+/// The game, mod loaders, and Java are not included.
+/// Returns `None` if the JAR has neither file, or if they can't be parsed.
+pub fn embedded_dependencies(path: impl AsRef<Path>) -> Result<Option<EmbeddedDependencies>> {
+    let mut jar = ZipArchive::new(BufReader::new(File::open(path)?))?;
+    let mut read_entry = |name: &str| -> Option<String> {
+        let mut entry = jar.by_name(name).ok()?;
+        let mut contents = String::new();
+        entry.read_to_string(&mut contents).ok()?;
+        Some(contents)
+    };
+    let is_mod = |id: &String| !PLATFORM_IDS.contains(&id.as_str());
+
+    if let Some(contents) = read_entry("fabric.mod.json") {
+        let Ok(manifest) = serde_json::from_str::<FabricModJson>(&contents) else {
+            return Ok(None);
+        };
+        return Ok(Some(EmbeddedDependencies {
+            mod_id: manifest.id,
+            required: manifest.depends.into_keys().filter(is_mod).collect(),
+            breaks: manifest.breaks.into_keys().filter(is_mod).collect(),
+        }));
+    }
+
+    let Some(contents) =
+        read_entry("META-INF/neoforge.mods.toml").or_else(|| read_entry("META-INF/mods.toml"))
+    else {
+        return Ok(None);
+    };
+    let Ok(manifest) = toml::from_str::<toml::Table>(&contents) else {
+        return Ok(None);
+    };
+    let mut dependencies = EmbeddedDependencies {
+        mod_id: manifest
+            .get("mods")
+            .and_then(toml::Value::as_array)
+            .and_then(|mods| mods.first())
+            .and_then(|mod_| mod_.get("modId"))
+            .and_then(toml::Value::as_str)
+            .map(ToOwned::to_owned),
+        ..Default::default()
+    };
+    for dependency in manifest
+        .get("dependencies")
+        .and_then(toml::Value::as_table)
+        .into_iter()
+        .flat_map(|dependencies| dependencies.values())
+        .filter_map(toml::Value::as_array)
+        .flatten()
+    {
+        let Some(id) = dependency
+            .get("modId")
+            .and_then(toml::Value::as_str)
+            .map(ToOwned::to_owned)
+            .filter(is_mod)
+        else {
+            continue;
+        };
+        // Forge uses `mandatory`, while NeoForge uses `type`
+        match dependency.get("type").and_then(toml::Value::as_str) {
+            Some("required") => dependencies.required.push(id),
+            Some("incompatible") => dependencies.breaks.push(id),
+            Some(_) => {}
+            None => {
+                if dependency.get("mandatory").and_then(toml::Value::as_bool) == Some(true) {
+                    dependencies.required.push(id);
+                }
+            }
+        }
+    }
+    Ok(Some(dependencies))
+}
+
+/// Compare the [required dependencies](Mod::required_dependencies) that each mod's platform declared
+/// with the ones embedded in its most recently downloaded file
+///
+/// Mod IDs and the platforms' identifiers are different, so dependencies are matched using the slugs or names
+/// of the mods in the profile, ignoring case and treating `-` and `_` as the same.
+/// Declared dependencies that aren't in the profile can't be matched, so they are never reported as missing from the JAR.
+/// Mods that haven't been downloaded, or whose file no longer exists or doesn't have any metadata, are skipped.
+/// Returns the names of the mods with discrepancies, along with them.
+pub fn reconcile_dependencies(
+    profile: &Profile,
+) -> Result<Vec<(String, Vec<DependencyDiscrepancy>)>> {
+    let normalise = |id: &str| id.to_lowercase().replace('_', "-");
+    let mod_ids = |mod_: &Mod| {
+        [mod_.slug.as_deref(), Some(mod_.name.as_str())]
+            .into_iter()
+            .flatten()
+            .map(normalise)
+            .collect::<Vec<_>>()
+    };
+
+    let mut discrepancies = Vec::new();
+    for mod_ in &profile.mods {
+        let Some(path) = mod_
+            .history
+            .last()
+            .map(|file| profile.output_dir_for(mod_.content_type).join(&file.output))
+            .filter(|path| path.is_file())
+        else {
+            continue;
+        };
+        let Some(embedded) = embedded_dependencies(path)? else {
+            continue;
+        };
+        let required = embedded
+            .required
+            .iter()
+            .map(|id| normalise(id))
+            .collect::<Vec<_>>();
+
+        // The mods in the profile that the platform declares as dependencies, with their possible mod IDs
+        let declared = mod_
+            .required_dependencies
+            .iter()
+            .map(|dependency| {
+                let ids = profile
+                    .mods
+                    .iter()
+                    .find(|other| other.identifier.unpinned() == dependency.unpinned())
+                    .map(mod_ids);
+                (dependency, ids)
+            })
+            .collect::<Vec<_>>();
+
+        let mut found = Vec::new();
+        for (id, normalised) in embedded.required.iter().zip(&required) {
+            if !declared
+                .iter()
+                .any(|(_, ids)| ids.as_ref().is_some_and(|ids| ids.contains(normalised)))
+            {
+                found.push(DependencyDiscrepancy::MissingFromProvider(id.clone()));
+            }
+        }
+        for (dependency, ids) in &declared {
+            if ids
+                .as_ref()
+                .is_some_and(|ids| !ids.iter().any(|id| required.contains(id)))
+            {
+                found.push(DependencyDiscrepancy::MissingFromJar(dependency.unpinned()));
+            }
+        }
+        for id in &embedded.breaks {
+            if profile
+                .mods
+                .iter()
+                .any(|other| other.enabled && mod_ids(other).contains(&normalise(id)))
+            {
+                found.push(DependencyDiscrepancy::BreaksInstalled(id.clone()));
+            }
+        }
+
+        if !found.is_empty() {
+            discrepancies.push((mod_.name.clone(), found));
+        }
+    }
+    Ok(discrepancies)
+}
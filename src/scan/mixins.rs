@@ -0,0 +1,401 @@
+use super::{is_ignored, read_ignore_patterns, Result};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{read_dir, File},
+    io::{BufReader, Read, Seek},
+    path::Path,
+};
+use zip::ZipArchive;
+
+const MIXIN_ANNOTATION: &str = "Lorg/spongepowered/asm/mixin/Mixin;";
+
+/// How a mod modifies a class
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modification {
+    /// The class is the target of a mixin
+    Mixin,
+    /// The class is made accessible by an access widener or access transformer
+    AccessWidener,
+}
+
+/// Two mods that modify the same class, at least one of them using a mixin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MixinConflict {
+    /// The fully qualified name of the class, e.g. `net.minecraft.client.MinecraftClient`
+    pub class: String,
+    /// The filename of the first mod and how it modifies the class
+    pub first: (String, Modification),
+    /// The filename of the second mod and how it modifies the class
+    pub second: (String, Modification),
+}
+
+#[derive(Deserialize)]
+struct MixinConfig {
+    package: String,
+    #[serde(default)]
+    mixins: Vec<String>,
+    #[serde(default)]
+    client: Vec<String>,
+    #[serde(default)]
+    server: Vec<String>,
+}
+
+/// Find the pairs of mods in `dir_path` that modify the same class, as a soft warning that they may conflict
+///
+/// This is a best-effort heuristic. Mixin classes are found using the mixin configs at the root of each JAR file,
+/// and their targets are read from the `@Mixin` annotations in their class files.
+/// Classes in access wideners and access transformers are only reported if another mod uses a mixin on them,
+/// since widening the same class twice is harmless.
+///
+/// Files ignored by the [`IGNORE_FILE`](super::IGNORE_FILE) in `dir_path` are skipped,
+/// as are files that can't be read as JAR files.
+pub fn find_mixin_conflicts(dir_path: impl AsRef<Path>) -> Result<Vec<MixinConflict>> {
+    let ignore_patterns = read_ignore_patterns(&dir_path)?;
+    // The mods that modify each class, sorted so that the conflicts are reported in a consistent order
+    let mut classes = BTreeMap::<String, Vec<(String, Modification)>>::new();
+
+    for entry in read_dir(dir_path)? {
+        let path = entry?.path();
+        let Some(filename) = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+        else {
+            continue;
+        };
+        if !path.is_file()
+            || !path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("jar"))
+            || is_ignored(&ignore_patterns, &filename)
+        {
+            continue;
+        }
+        let Ok(mut jar) = ZipArchive::new(BufReader::new(File::open(&path)?)) else {
+            continue;
+        };
+        for (class, modification) in modified_classes(&mut jar) {
+            let mods = classes.entry(class).or_default();
+            if !mods.iter().any(|(name, _)| name == &filename) {
+                mods.push((filename.clone(), modification));
+            }
+        }
+    }
+
+    let mut conflicts = Vec::new();
+    for (class, mods) in classes {
+        for (i, first) in mods.iter().enumerate() {
+            for second in &mods[i + 1..] {
+                if first.1 == Modification::Mixin || second.1 == Modification::Mixin {
+                    conflicts.push(MixinConflict {
+                        class: class.clone(),
+                        first: first.clone(),
+                        second: second.clone(),
+                    });
+                }
+            }
+        }
+    }
+    Ok(conflicts)
+}
+
+/// Get the classes modified by the mod in `jar`, preferring mixins if a class is modified in both ways
+fn modified_classes(jar: &mut ZipArchive<impl Read + Seek>) -> HashMap<String, Modification> {
+    let mut classes = HashMap::new();
+    let root_files = jar
+        .file_names()
+        .filter(|name| !name.contains('/') || name.starts_with("META-INF/"))
+        .map(ToOwned::to_owned)
+        .collect::<Vec<_>>();
+
+    for name in root_files {
+        let Some(contents) = read_entry(jar, &name) else {
+            continue;
+        };
+        if name.ends_with(".accesswidener") || name.ends_with(".classtweaker") {
+            for class in access_widener_classes(&String::from_utf8_lossy(&contents)) {
+                classes.entry(class).or_insert(Modification::AccessWidener);
+            }
+        } else if name.eq_ignore_ascii_case("META-INF/accesstransformer.cfg") {
+            for class in access_transformer_classes(&String::from_utf8_lossy(&contents)) {
+                classes.entry(class).or_insert(Modification::AccessWidener);
+            }
+        } else if name.ends_with(".json") && !name.contains('/') {
+            let Ok(config) = serde_json::from_slice::<MixinConfig>(&contents) else {
+                continue;
+            };
+            let package = config.package.replace('.', "/");
+            for mixin in config
+                .mixins
+                .iter()
+                .chain(&config.client)
+                .chain(&config.server)
+            {
+                let class_path = format!("{package}/{}.class", mixin.replace('.', "/"));
+                let Some(class_file) = read_entry(jar, &class_path) else {
+                    continue;
+                };
+                for target in mixin_targets(&class_file).unwrap_or_default() {
+                    classes.insert(target, Modification::Mixin);
+                }
+            }
+        }
+    }
+    classes
+}
+
+fn read_entry(jar: &mut ZipArchive<impl Read + Seek>, name: &str) -> Option<Vec<u8>> {
+    let mut entry = jar.by_name(name).ok()?;
+    let mut contents = Vec::new();
+    entry.read_to_end(&mut contents).ok()?;
+    Some(contents)
+}
+
+/// Get the classes in a Fabric access widener, e.g. `accessible class net/minecraft/Foo`
+fn access_widener_classes(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .skip(1) // The header
+        .filter_map(|line| {
+            let line = line.split('#').next()?;
+            let class = line.split_whitespace().nth(2)?;
+            Some(class.replace('/', "."))
+        })
+        .collect()
+}
+
+/// Get the classes in a Forge access transformer, e.g. `public net.minecraft.Foo field`
+fn access_transformer_classes(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let line = line.split('#').next()?;
+            let class = line.split_whitespace().nth(1)?;
+            Some(class.replace('/', "."))
+        })
+        .collect()
+}
+
+/// A cursor over the bytes of a class file
+struct ClassReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl ClassReader<'_> {
+    fn take(&mut self, length: usize) -> Option<&[u8]> {
+        let bytes = self.bytes.get(self.position..self.position + length)?;
+        self.position += length;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_be_bytes(self.take(2)?.try_into().ok()?))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    /// Skip the fields or methods of the class
+    fn skip_members(&mut self) -> Option<()> {
+        for _ in 0..self.u16()? {
+            self.take(6)?;
+            self.skip_attributes()?;
+        }
+        Some(())
+    }
+
+    fn skip_attributes(&mut self) -> Option<()> {
+        for _ in 0..self.u16()? {
+            self.take(2)?;
+            let length = self.u32()? as usize;
+            self.take(length)?;
+        }
+        Some(())
+    }
+}
+
+/// Read the targets of the `@Mixin` annotation in `class_file`
+///
+/// Returns `None` if the class file couldn't be parsed.
+fn mixin_targets(class_file: &[u8]) -> Option<Vec<String>> {
+    let mut reader = ClassReader {
+        bytes: class_file,
+        position: 8, // The magic number and version
+    };
+
+    // Only the UTF-8 entries of the constant pool are needed
+    let pool_count = reader.u16()? as usize;
+    let mut utf8 = vec![None; pool_count];
+    let mut index = 1;
+    while index < pool_count {
+        match reader.u8()? {
+            1 => {
+                let length = reader.u16()? as usize;
+                utf8[index] = Some(String::from_utf8_lossy(reader.take(length)?).into_owned());
+            }
+            7 | 8 | 16 | 19 | 20 => {
+                reader.take(2)?;
+            }
+            15 => {
+                reader.take(3)?;
+            }
+            3 | 4 | 9 | 10 | 11 | 12 | 17 | 18 => {
+                reader.take(4)?;
+            }
+            // Longs and doubles take up two entries
+            5 | 6 => {
+                reader.take(8)?;
+                index += 1;
+            }
+            _ => return None,
+        }
+        index += 1;
+    }
+    let utf8_at = |index: u16| utf8.get(index as usize)?.as_deref();
+
+    // Skip the access flags, this class, and super class
+    reader.take(6)?;
+    let interfaces = reader.u16()? as usize;
+    reader.take(interfaces * 2)?;
+    reader.skip_members()?; // Fields
+    reader.skip_members()?; // Methods
+
+    let mut targets = Vec::new();
+    for _ in 0..reader.u16()? {
+        let name = utf8_at(reader.u16()?)?;
+        let length = reader.u32()? as usize;
+        let attribute = reader.take(length)?;
+        if name != "RuntimeInvisibleAnnotations" && name != "RuntimeVisibleAnnotations" {
+            continue;
+        }
+        let mut annotations = ClassReader {
+            bytes: attribute,
+            position: 0,
+        };
+        for _ in 0..annotations.u16()? {
+            let is_mixin = utf8_at(annotations.u16()?)? == MIXIN_ANNOTATION;
+            for _ in 0..annotations.u16()? {
+                let element = utf8_at(annotations.u16()?)?;
+                let values = read_element_value(&mut annotations, &utf8_at)?;
+                if is_mixin && (element == "value" || element == "targets") {
+                    targets.extend(values);
+                }
+            }
+        }
+    }
+    Some(targets)
+}
+
+/// Read an annotation element value, and return the class names in it
+fn read_element_value<'a>(
+    reader: &mut ClassReader,
+    utf8_at: &impl Fn(u16) -> Option<&'a str>,
+) -> Option<Vec<String>> {
+    Some(match reader.u8()? {
+        // Class literals, e.g. `Lnet/minecraft/Foo;`
+        b'c' => {
+            let descriptor = utf8_at(reader.u16()?)?;
+            let class = descriptor.strip_prefix('L')?.strip_suffix(';')?;
+            vec![class.replace('/', ".")]
+        }
+        // Strings, which are class names in `targets`
+        b's' => vec![utf8_at(reader.u16()?)?.replace('/', ".")],
+        b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z' => {
+            reader.take(2)?;
+            Vec::new()
+        }
+        b'e' => {
+            reader.take(4)?;
+            Vec::new()
+        }
+        b'@' => {
+            reader.take(2)?;
+            for _ in 0..reader.u16()? {
+                reader.take(2)?;
+                read_element_value(reader, utf8_at)?;
+            }
+            Vec::new()
+        }
+        b'[' => {
+            let mut classes = Vec::new();
+            for _ in 0..reader.u16()? {
+                classes.extend(read_element_value(reader, utf8_at)?);
+            }
+            classes
+        }
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf8(string: &str) -> Vec<u8> {
+        let mut entry = vec![1];
+        entry.extend((string.len() as u16).to_be_bytes());
+        entry.extend(string.as_bytes());
+        entry
+    }
+
+    /// Build a class file with a `@Mixin(Foo.class)` annotation,
+    /// after the `prefix` constant pool entries which take up `prefix_slots` entries
+    fn mixin_class(prefix: &[Vec<u8>], prefix_slots: u16) -> Vec<u8> {
+        let index = |i: u16| (prefix_slots + i).to_be_bytes();
+        let mut class = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 61];
+        class.extend((prefix_slots + 5).to_be_bytes());
+        for entry in prefix {
+            class.extend(entry);
+        }
+        class.extend(utf8("RuntimeInvisibleAnnotations"));
+        class.extend(utf8(MIXIN_ANNOTATION));
+        class.extend(utf8("value"));
+        class.extend(utf8("Lnet/minecraft/Foo;"));
+        // Access flags, this class, super class, and no interfaces, fields, or methods
+        class.extend([0; 12]);
+
+        let mut annotation = vec![0, 1];
+        annotation.extend(index(2));
+        annotation.extend([0, 1]);
+        annotation.extend(index(3));
+        annotation.push(b'c');
+        annotation.extend(index(4));
+        class.extend([0, 1]);
+        class.extend(index(1));
+        class.extend((annotation.len() as u32).to_be_bytes());
+        class.extend(annotation);
+        class
+    }
+
+    #[test]
+    fn valid_mixin() {
+        assert_eq!(
+            mixin_targets(&mixin_class(&[], 0)),
+            Some(vec!["net.minecraft.Foo".to_owned()])
+        );
+    }
+
+    #[test]
+    fn truncated_constant_pool() {
+        let class = mixin_class(&[], 0);
+        assert_eq!(mixin_targets(&class[..20]), None);
+    }
+
+    #[test]
+    fn long_and_double_take_two_entries() {
+        let mut long = vec![5];
+        long.extend(42_i64.to_be_bytes());
+        let mut double = vec![6];
+        double.extend(1.5_f64.to_be_bytes());
+        assert_eq!(
+            mixin_targets(&mixin_class(&[long, double], 4)),
+            Some(vec!["net.minecraft.Foo".to_owned()])
+        );
+    }
+}
@@ -0,0 +1,215 @@
+pub mod dependencies;
+pub mod mixins;
+
+#[cfg(feature = "curseforge")]
+use crate::CURSEFORGE_API;
+use crate::{config::structs::Profile, MODRINTH_API};
+#[cfg(feature = "curseforge")]
+use futures_util::{try_join, TryFutureExt};
+use glob::Pattern;
+use sha1::{Digest, Sha1};
+use std::{
+    collections::HashMap,
+    fs::{read, read_dir, read_to_string, File},
+    io::{BufReader, Read},
+    path::Path,
+};
+use zip::ZipArchive;
+
+/// The name of the file in a scanned directory that lists the files to ignore
+///
+/// Each line is a glob pattern (e.g. `*-custom.jar` or `mymod-[0-9]*.jar`) matched against the filename.
+/// Empty lines and lines starting with `#` are skipped.
+/// A pattern starting with `!` un-ignores the files it matches.
+/// Later lines take precedence over earlier ones, so `!` patterns should come after the patterns they override.
+pub const IGNORE_FILE: &str = ".ferignore";
+
+#[derive(thiserror::Error, Debug)]
+#[error(transparent)]
+pub enum Error {
+    IOError(#[from] std::io::Error),
+    ModrinthError(#[from] ferinth::Error),
+    #[cfg(feature = "curseforge")]
+    CurseForgeError(#[from] furse::Error),
+    PatternError(#[from] glob::PatternError),
+    ZipError(#[from] zip::result::ZipError),
+}
+type Result<T> = std::result::Result<T, Error>;
+
+/// Read the ignore patterns from the [`IGNORE_FILE`] in `dir_path`, if it exists
+///
+/// Returns the patterns along with whether they ignore (`true`) or un-ignore (`false`) files.
+pub fn read_ignore_patterns(dir_path: impl AsRef<Path>) -> Result<Vec<(bool, Pattern)>> {
+    let path = dir_path.as_ref().join(IGNORE_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            Ok(match line.strip_prefix('!') {
+                Some(pattern) => (false, Pattern::new(pattern)?),
+                None => (true, Pattern::new(line)?),
+            })
+        })
+        .collect()
+}
+
+/// Whether `filename` is ignored by `patterns`, with the last matching pattern taking precedence
+pub fn is_ignored(patterns: &[(bool, Pattern)], filename: &str) -> bool {
+    patterns
+        .iter()
+        .rev()
+        .find(|(_, pattern)| pattern.matches(filename))
+        .is_some_and(|(ignore, _)| *ignore)
+}
+
+/// Read the minimum Java version that the JAR file at `path` requires
+///
+/// The `Build-Jdk-Spec` in a JAR's manifest only says what it was built with, not what it requires,
+/// so the highest class file version of the classes in the JAR is used instead.
+/// Returns `None` if the JAR doesn't contain any classes.
+pub fn min_java_version(path: impl AsRef<Path>) -> Result<Option<u8>> {
+    let mut jar = ZipArchive::new(BufReader::new(File::open(path)?))?;
+    let mut max_major = None;
+    for i in 0..jar.len() {
+        let mut entry = jar.by_index(i)?;
+        if !entry.name().ends_with(".class") {
+            continue;
+        }
+        // The magic number, followed by the minor and major versions
+        let mut header = [0; 8];
+        if entry.read_exact(&mut header).is_err() || header[..4] != [0xCA, 0xFE, 0xBA, 0xBE] {
+            continue;
+        }
+        let major = u16::from_be_bytes([header[6], header[7]]);
+        max_major = max_major.max(Some(major));
+    }
+    // Java 1.1 is class file version 45, and each release since has incremented it
+    Ok(max_major
+        .filter(|major| *major > 44)
+        .map(|major| (major - 44).min(u8::MAX as u16) as u8))
+}
+
+/// Fill in the [minimum Java version](crate::config::structs::Mod::min_java) of the mods in `profile`
+/// that don't have one, by reading their most recently downloaded file using [`min_java_version`]
+///
+/// Mods that haven't been downloaded, or whose file no longer exists, are skipped.
+/// Returns the number of mods that were updated.
+pub fn detect_min_java(profile: &mut Profile) -> Result<usize> {
+    let mut detected = 0;
+    let paths = profile
+        .mods
+        .iter()
+        .map(|mod_| {
+            mod_.history
+                .last()
+                .map(|file| profile.output_dir_for(mod_.content_type).join(&file.output))
+        })
+        .collect::<Vec<_>>();
+    for (mod_, path) in profile.mods.iter_mut().zip(paths) {
+        if mod_.min_java.is_some() {
+            continue;
+        }
+        if let Some(path) = path.filter(|path| path.is_file()) {
+            mod_.min_java = min_java_version(path)?;
+            if mod_.min_java.is_some() {
+                detected += 1;
+            }
+        }
+    }
+    Ok(detected)
+}
+
+/// Scans `dir_path` and return the filename, Modrinth project ID, and CurseForge mod ID for each JAR file
+///
+/// The CurseForge mod IDs are always `None` if the `curseforge` feature is disabled.
+/// Files ignored by the [`IGNORE_FILE`] in `dir_path` are skipped.
+/// Calls `hashing_complete` after reading and hashing files is done.
+pub async fn scan(
+    dir_path: impl AsRef<Path>,
+    hashing_complete: impl Fn(),
+) -> Result<Vec<(String, Option<String>, Option<i32>)>> {
+    let mut filenames = HashMap::new();
+    let mut mr_hashes = vec![];
+    // The CurseForge fingerprint of each file, keyed by its SHA-1 hash
+    #[cfg(feature = "curseforge")]
+    let mut cf_hashes = HashMap::new();
+    let ignore_patterns = read_ignore_patterns(&dir_path)?;
+
+    for entry in read_dir(dir_path)? {
+        let path = entry?.path();
+        if path.is_file()
+            && path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("jar"))
+            && !path
+                .file_name()
+                .is_some_and(|name| is_ignored(&ignore_patterns, &name.to_string_lossy()))
+        {
+            let bytes = read(&path)?;
+
+            let mr_hash = format!("{:x}", Sha1::digest(&bytes));
+
+            if let Some(filename) = path.file_name() {
+                // Only add the hashes if this file wasn't already hashed
+                if filenames
+                    .insert(mr_hash.clone(), filename.to_owned())
+                    .is_none()
+                {
+                    #[cfg(feature = "curseforge")]
+                    cf_hashes.insert(mr_hash.clone(), furse::cf_fingerprint(&bytes));
+                    mr_hashes.push(mr_hash);
+                }
+            }
+        }
+    }
+
+    hashing_complete();
+
+    #[cfg(feature = "curseforge")]
+    let (mr_results, cf_results) = try_join!(
+        MODRINTH_API
+            .get_versions_from_hashes(mr_hashes.clone())
+            .map_err(Error::from),
+        CURSEFORGE_API
+            .get_fingerprint_matches(cf_hashes.values().copied().collect())
+            .map_err(Error::from),
+    )?;
+    #[cfg(not(feature = "curseforge"))]
+    let mr_results = MODRINTH_API
+        .get_versions_from_hashes(mr_hashes.clone())
+        .await?;
+
+    // Elide explicit type parameters when https://github.com/rust-lang/rust/issues/90879 is resolved.
+    let mut mr_results =
+        HashMap::<_, _>::from_iter(mr_results.into_iter().map(|(k, v)| (k, v.project_id)));
+    #[cfg(feature = "curseforge")]
+    let mut cf_results = HashMap::<_, _>::from_iter(
+        cf_results
+            .exact_fingerprints
+            .into_iter()
+            .zip(cf_results.exact_matches.into_iter().map(|m| m.id)),
+    );
+
+    Ok(mr_hashes
+        .iter()
+        .map(|mr| {
+            (
+                filenames
+                    .remove(mr)
+                    .expect("Missing filename in hashmap")
+                    .to_string_lossy()
+                    .into_owned(),
+                mr_results.remove(mr),
+                #[cfg(feature = "curseforge")]
+                cf_hashes.get(mr).and_then(|cf| cf_results.remove(cf)),
+                #[cfg(not(feature = "curseforge"))]
+                None,
+            )
+        })
+        .collect())
+}
@@ -0,0 +1,862 @@
+#[cfg(feature = "curseforge")]
+use crate::add::{CURSEFORGE_MINECRAFT_GAME_ID, CURSEFORGE_MODS_CLASS_ID};
+use crate::{
+    config::{
+        filters::FileSelection,
+        structs::{Mod, ModIdentifier, ModLoader, Profile, ProviderKind},
+    },
+    metadata::{self, same_project},
+    upgrade::{mod_downloadable, DownloadData},
+    GITHUB_API, MODRINTH_API,
+};
+use ferinth::structures::{
+    project::ProjectType,
+    search::{Facet, SearchHit, Sort},
+};
+#[cfg(feature = "curseforge")]
+use furse::structures::{
+    common_structs::{Category, Pagination},
+    mod_structs::Mod as CFMod,
+};
+#[cfg(feature = "curseforge")]
+use futures_util::StreamExt as _;
+use futures_util::{future::try_join_all, join, stream, Stream};
+#[cfg(feature = "curseforge")]
+use serde::Deserialize;
+#[cfg(feature = "curseforge")]
+use std::sync::OnceLock;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    future::Future,
+};
+
+/// The number of results fetched with each request
+pub const PAGE_SIZE: usize = 20;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Modrinth: {0}")]
+    ModrinthError(#[from] ferinth::Error),
+    #[cfg(feature = "curseforge")]
+    #[error("CurseForge: {0}")]
+    CurseForgeError(#[from] reqwest::Error),
+    #[error("{0} is not a known game version")]
+    UnknownGameVersion(String),
+    #[error("{0} is not a CurseForge category")]
+    UnknownCategory(String),
+    #[error("CurseForge support is not enabled in this build")]
+    CurseForgeDisabled,
+    #[error("GitHub: {0:#?}")]
+    GitHubError(Box<octocrab::Error>),
+}
+
+// Boxed since octocrab's error is much larger than the others
+impl From<octocrab::Error> for Error {
+    fn from(err: octocrab::Error) -> Self {
+        Self::GitHubError(Box::new(err))
+    }
+}
+
+/// What to narrow the results of [`search_stream_filtered`] down to,
+/// which are translated into Modrinth's facets and CurseForge's search parameters
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchFilters {
+    /// Categories that the projects must be in, e.g. `technology` or `magic`
+    ///
+    /// Modrinth projects must be in all of them, while CurseForge projects only need to be in one of them,
+    /// since CurseForge's search doesn't support requiring several categories.
+    pub categories: Vec<String>,
+    /// Game versions that the projects must support any of
+    pub game_versions: Vec<String>,
+    /// Mod loaders that the projects must support any of
+    pub loaders: Vec<ModLoader>,
+    /// The type of projects to search for, which is mods if it isn't set
+    pub project_type: Option<ProjectType>,
+}
+
+impl SearchFilters {
+    /// Translate the filters into Modrinth's facets, in which the inner lists are ORed together and the outer list is ANDed
+    ///
+    /// Returns [`Error::UnknownGameVersion`] if any of the game versions aren't in the [list of game versions](crate::game_version::list).
+    pub async fn facets(&self) -> Result<Vec<Vec<Facet>>, Error> {
+        let known_versions = crate::game_version::list().await?;
+        if let Some(unknown) = self.game_versions.iter().find(|version| {
+            !known_versions
+                .iter()
+                .any(|known| &known.version == *version)
+        }) {
+            return Err(Error::UnknownGameVersion(unknown.clone()));
+        }
+
+        let mut facets = vec![vec![Facet::ProjectType(
+            self.project_type.clone().unwrap_or(ProjectType::Mod),
+        )]];
+        facets.extend(
+            self.categories
+                .iter()
+                .map(|category| vec![Facet::Categories(category.to_lowercase())]),
+        );
+        if !self.game_versions.is_empty() {
+            facets.push(
+                self.game_versions
+                    .iter()
+                    .cloned()
+                    .map(Facet::Versions)
+                    .collect(),
+            );
+        }
+        if !self.loaders.is_empty() {
+            // Modrinth lists loaders as categories
+            facets.push(
+                self.loaders
+                    .iter()
+                    .map(|loader| Facet::Categories(loader.to_string().to_lowercase()))
+                    .collect(),
+            );
+        }
+        Ok(facets)
+    }
+}
+
+#[cfg(feature = "curseforge")]
+impl SearchFilters {
+    /// Translate the filters into the parameters of CurseForge's search endpoint
+    ///
+    /// The names of the categories are mapped to their IDs using CurseForge's list of categories,
+    /// which is fetched once and then cached for the rest of the program.
+    /// Returns [`Error::UnknownCategory`] if any of the categories aren't in the list,
+    /// or [`Error::UnknownGameVersion`] like [`SearchFilters::facets`].
+    pub async fn curseforge_query(&self) -> Result<Vec<(&'static str, String)>, Error> {
+        if !self.game_versions.is_empty() {
+            // Validate the game versions
+            self.facets().await?;
+        }
+        let class_id = curseforge_class_id(self.project_type.as_ref().unwrap_or(&ProjectType::Mod));
+        let mut query = vec![
+            ("gameId", CURSEFORGE_MINECRAFT_GAME_ID.to_string()),
+            ("classId", class_id.to_string()),
+        ];
+        if !self.categories.is_empty() {
+            let category_ids = curseforge_category_ids(
+                curseforge_categories().await?,
+                class_id,
+                &self.categories,
+            )?;
+            query.push(("categoryIds", serde_json::json!(category_ids).to_string()));
+        }
+        if !self.game_versions.is_empty() {
+            query.push((
+                "gameVersions",
+                serde_json::json!(self.game_versions).to_string(),
+            ));
+        }
+        let loaders = self
+            .loaders
+            .iter()
+            .filter_map(curseforge_loader_type)
+            .collect::<Vec<_>>();
+        if !loaders.is_empty() {
+            query.push(("modLoaderTypes", serde_json::json!(loaders).to_string()));
+        }
+        Ok(query)
+    }
+}
+
+/// Get the ID of CurseForge's class for projects of `project_type`
+#[cfg(feature = "curseforge")]
+fn curseforge_class_id(project_type: &ProjectType) -> usize {
+    match project_type {
+        ProjectType::ResourcePack => 12,
+        ProjectType::Shader => 6552,
+        ProjectType::Datapack => 6945,
+        ProjectType::Modpack => 4471,
+        _ => CURSEFORGE_MODS_CLASS_ID,
+    }
+}
+
+/// Get CurseForge's number for `loader`, Rift isn't on CurseForge
+#[cfg(feature = "curseforge")]
+fn curseforge_loader_type(loader: &ModLoader) -> Option<u8> {
+    match loader {
+        ModLoader::Forge => Some(1),
+        ModLoader::LiteLoader => Some(3),
+        ModLoader::Fabric => Some(4),
+        ModLoader::Quilt => Some(5),
+        ModLoader::NeoForge => Some(6),
+        ModLoader::Rift => None,
+    }
+}
+
+#[cfg(feature = "curseforge")]
+static CURSEFORGE_CATEGORIES: OnceLock<Vec<Category>> = OnceLock::new();
+
+/// Get CurseForge's categories for Minecraft, which are fetched once and then cached for the rest of the program
+#[cfg(feature = "curseforge")]
+async fn curseforge_categories() -> Result<&'static [Category], Error> {
+    #[derive(Deserialize)]
+    struct CategoriesResponse {
+        data: Vec<Category>,
+    }
+
+    if let Some(categories) = CURSEFORGE_CATEGORIES.get() {
+        return Ok(categories);
+    }
+    let response: CategoriesResponse = crate::HTTP_CLIENT
+        .get("https://api.curseforge.com/v1/categories")
+        .header("x-api-key", crate::curseforge_api_key())
+        .query(&[("gameId", CURSEFORGE_MINECRAFT_GAME_ID)])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let _ = CURSEFORGE_CATEGORIES.set(response.data);
+    Ok(CURSEFORGE_CATEGORIES.get().unwrap())
+}
+
+/// Map the `names` of categories to the IDs of the `categories` in the class of `class_id`
+///
+/// Categories are matched by their name or slug, ignoring case.
+/// Returns [`Error::UnknownCategory`] with the first name that doesn't match any category.
+#[cfg(feature = "curseforge")]
+fn curseforge_category_ids(
+    categories: &[Category],
+    class_id: usize,
+    names: &[String],
+) -> Result<Vec<i32>, Error> {
+    names
+        .iter()
+        .map(|name| {
+            categories
+                .iter()
+                .filter(|category| category.class_id == Some(class_id as i32))
+                .find(|category| {
+                    category.name.eq_ignore_ascii_case(name.trim())
+                        || category.slug.eq_ignore_ascii_case(name.trim())
+                })
+                .map(|category| category.id)
+                .ok_or_else(|| Error::UnknownCategory(name.clone()))
+        })
+        .collect()
+}
+
+/// Search CurseForge for projects matching `query` and `filters`, and return the first page of results
+///
+/// Results are in CurseForge's default order, which takes the relevance and popularity of the projects into account.
+#[cfg(feature = "curseforge")]
+pub async fn search_curseforge(
+    query: &str,
+    filters: &SearchFilters,
+) -> Result<Vec<SearchResult>, Error> {
+    let parameters = filters.curseforge_query().await?;
+    Ok(curseforge_page(query, &parameters, 0, PAGE_SIZE).await?.0)
+}
+
+/// Get up to `page_size` results of CurseForge's search for `query` with the `parameters`, starting at `offset`,
+/// along with the total number of results
+#[cfg(feature = "curseforge")]
+async fn curseforge_page(
+    query: &str,
+    parameters: &[(&'static str, String)],
+    offset: usize,
+    page_size: usize,
+) -> Result<(Vec<SearchResult>, usize), Error> {
+    #[derive(Deserialize)]
+    struct SearchResponse {
+        data: Vec<CFMod>,
+        pagination: Pagination,
+    }
+
+    let response: SearchResponse = crate::HTTP_CLIENT
+        .get("https://api.curseforge.com/v1/mods/search")
+        .header("x-api-key", crate::curseforge_api_key())
+        .query(parameters)
+        .query(&[
+            ("searchFilter", query.to_owned()),
+            ("index", offset.to_string()),
+            ("pageSize", page_size.to_string()),
+        ])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    metadata::cache_curseforge_projects(&response.data);
+    Ok((
+        response.data.into_iter().map(Into::into).collect(),
+        response.pagination.total_count,
+    ))
+}
+
+/// A mod found by [`search_stream`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    pub identifier: ModIdentifier,
+    pub name: String,
+    pub description: String,
+    pub downloads: usize,
+    pub slug: String,
+    /// The name of the project's author, or its first author if it has several
+    pub author: String,
+    /// The identifiers of the same project on other platforms, found by [`merge_duplicates`]
+    pub alternates: Vec<ModIdentifier>,
+}
+
+#[cfg(feature = "curseforge")]
+impl From<CFMod> for SearchResult {
+    fn from(project: CFMod) -> Self {
+        Self {
+            identifier: ModIdentifier::CurseForgeProject(project.id),
+            name: project.name,
+            description: project.summary,
+            downloads: project.download_count,
+            slug: project.slug,
+            author: project
+                .authors
+                .into_iter()
+                .next()
+                .map(|author| author.name)
+                .unwrap_or_default(),
+            alternates: Vec::new(),
+        }
+    }
+}
+
+impl From<SearchHit> for SearchResult {
+    fn from(hit: SearchHit) -> Self {
+        Self {
+            identifier: ModIdentifier::ModrinthProject(hit.project_id),
+            name: hit.title,
+            description: hit.description,
+            downloads: hit.downloads,
+            slug: hit.slug,
+            author: hit.author,
+            alternates: Vec::new(),
+        }
+    }
+}
+
+struct SearchState<F> {
+    fetch_page: F,
+    offset: usize,
+    buffer: VecDeque<SearchResult>,
+    seen: HashSet<ModIdentifier>,
+    done: bool,
+}
+
+/// Search Modrinth and CurseForge for mods matching `query`, as a stream of results in order of relevance
+///
+/// Pages of [`PAGE_SIZE`] results are only fetched from a platform once its previous page has been consumed,
+/// and results that appear in more than one page are only returned once.
+/// The results of the platforms are interleaved, starting with Modrinth,
+/// and once one platform runs out of results the rest of the other's are returned.
+/// The results of a platform end after its first error.
+/// Platforms that `profile` doesn't [allow](Profile::allows_provider) aren't searched.
+pub fn search_stream(
+    profile: &Profile,
+    query: impl Into<String>,
+) -> impl Stream<Item = Result<SearchResult, Error>> {
+    let query = query.into();
+    let results = modrinth_stream(
+        query.clone(),
+        vec![vec![Facet::ProjectType(ProjectType::Mod)]],
+        profile.allows_provider(ProviderKind::Modrinth),
+    );
+    #[cfg(feature = "curseforge")]
+    let results = round_robin(
+        results,
+        curseforge_stream(
+            query,
+            vec![
+                ("gameId", CURSEFORGE_MINECRAFT_GAME_ID.to_string()),
+                ("classId", CURSEFORGE_MODS_CLASS_ID.to_string()),
+            ],
+            profile.allows_provider(ProviderKind::CurseForge),
+        ),
+    );
+    results
+}
+
+/// Search Modrinth and CurseForge for projects matching `query` and `filters`, as a stream of results like [`search_stream`]
+///
+/// The filters are validated before anything is searched,
+/// except for those of platforms that `profile` doesn't allow.
+pub async fn search_stream_filtered(
+    profile: &Profile,
+    query: impl Into<String>,
+    filters: &SearchFilters,
+) -> Result<impl Stream<Item = Result<SearchResult, Error>>, Error> {
+    let query = query.into();
+    let allows_modrinth = profile.allows_provider(ProviderKind::Modrinth);
+    let facets = if allows_modrinth {
+        filters.facets().await?
+    } else {
+        Vec::new()
+    };
+    let results = modrinth_stream(query.clone(), facets, allows_modrinth);
+    #[cfg(feature = "curseforge")]
+    let results = {
+        let allows_curseforge = profile.allows_provider(ProviderKind::CurseForge);
+        let parameters = if allows_curseforge {
+            filters.curseforge_query().await?
+        } else {
+            Vec::new()
+        };
+        round_robin(
+            results,
+            curseforge_stream(query, parameters, allows_curseforge),
+        )
+    };
+    Ok(results)
+}
+
+/// Return the items of `first` and `second` alternately, starting with `first`,
+/// and the rest of the other's items once one of them ends
+#[cfg(feature = "curseforge")]
+fn round_robin<T>(
+    first: impl Stream<Item = T>,
+    second: impl Stream<Item = T>,
+) -> impl Stream<Item = T> {
+    async fn next_or_end<S: Stream + Unpin>(stream: &mut Option<S>) -> Option<S::Item> {
+        let item = stream.as_mut()?.next().await;
+        if item.is_none() {
+            *stream = None;
+        }
+        item
+    }
+
+    let state = (Some(Box::pin(first)), Some(Box::pin(second)), true);
+    stream::unfold(state, |(mut first, mut second, first_next)| async move {
+        for first_turn in [first_next, !first_next] {
+            let item = if first_turn {
+                next_or_end(&mut first).await
+            } else {
+                next_or_end(&mut second).await
+            };
+            if let Some(item) = item {
+                return Some((item, (first, second, !first_turn)));
+            }
+        }
+        None
+    })
+}
+
+fn modrinth_stream(
+    query: String,
+    facets: Vec<Vec<Facet>>,
+    allowed: bool,
+) -> impl Stream<Item = Result<SearchResult, Error>> {
+    paged_stream(allowed, move |offset| {
+        let (query, facets) = (query.clone(), facets.clone());
+        async move {
+            let response = MODRINTH_API
+                .search_paged(&query, &Sort::Relevance, PAGE_SIZE, offset, facets)
+                .await?;
+            Ok((
+                response.hits.into_iter().map(Into::into).collect(),
+                response.total_hits,
+            ))
+        }
+    })
+}
+
+#[cfg(feature = "curseforge")]
+fn curseforge_stream(
+    query: String,
+    parameters: Vec<(&'static str, String)>,
+    allowed: bool,
+) -> impl Stream<Item = Result<SearchResult, Error>> {
+    paged_stream(allowed, move |offset| {
+        let (query, parameters) = (query.clone(), parameters.clone());
+        async move { curseforge_page(&query, &parameters, offset, PAGE_SIZE).await }
+    })
+}
+
+/// Stream the results of the pages returned by `fetch_page`, which gets the page of results at an offset
+/// along with the total number of results
+///
+/// Nothing is fetched if the platform isn't `allowed`, in which case the stream is empty.
+fn paged_stream<F, Fut>(
+    allowed: bool,
+    fetch_page: F,
+) -> impl Stream<Item = Result<SearchResult, Error>>
+where
+    F: Fn(usize) -> Fut,
+    Fut: Future<Output = Result<(Vec<SearchResult>, usize), Error>>,
+{
+    let state = SearchState {
+        fetch_page,
+        offset: 0,
+        buffer: VecDeque::new(),
+        seen: HashSet::new(),
+        done: !allowed,
+    };
+    stream::unfold(state, |mut state| async move {
+        while state.buffer.is_empty() {
+            if state.done {
+                return None;
+            }
+            let (results, total) = match (state.fetch_page)(state.offset).await {
+                Ok(page) => page,
+                Err(err) => {
+                    state.done = true;
+                    return Some((Err(err), state));
+                }
+            };
+            state.offset += results.len();
+            state.done = results.len() < PAGE_SIZE || state.offset >= total;
+            for result in results {
+                if state.seen.insert(result.identifier.clone()) {
+                    state.buffer.push_back(result);
+                }
+            }
+        }
+        let result = state.buffer.pop_front()?;
+        Some((Ok(result), state))
+    })
+}
+
+/// Search Modrinth for mods matching any of `queries`, and return up to `limit` results ranked across all of them
+///
+/// The queries are searched concurrently, with up to `limit` results fetched for each.
+/// Modrinth doesn't return relevance scores, so each result scores `1 / rank` for every query it appears in,
+/// and results with the same total keep the order of the first query they appeared in.
+pub async fn search_multi(queries: &[&str], limit: usize) -> ferinth::Result<Vec<SearchResult>> {
+    let responses = try_join_all(queries.iter().map(|query| {
+        MODRINTH_API.search_paged(
+            query,
+            &Sort::Relevance,
+            limit,
+            0,
+            vec![vec![Facet::ProjectType(ProjectType::Mod)]],
+        )
+    }))
+    .await?;
+
+    let mut results = Vec::<(f64, SearchResult)>::new();
+    let mut indices = HashMap::<String, usize>::new();
+    for response in responses {
+        for (rank, hit) in response.hits.into_iter().enumerate() {
+            let score = 1.0 / (rank + 1) as f64;
+            match indices.get(&hit.project_id) {
+                Some(&i) => results[i].0 += score,
+                None => {
+                    indices.insert(hit.project_id.clone(), results.len());
+                    results.push((score, hit.into()));
+                }
+            }
+        }
+    }
+
+    // The sort is stable, so ties keep the order they were found in
+    results.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+    results.truncate(limit);
+    Ok(results.into_iter().map(|(_, result)| result).collect())
+}
+
+/// Merge the results in `results` that are the same project on different platforms into one result,
+/// e.g. when combining the results of searching Modrinth and CurseForge
+///
+/// Results are compared using [`same_project`], so the metadata of each result is fetched,
+/// as are the file hashes of results whose names and slugs don't match.
+/// This can merge different projects that happen to share a name or slug,
+/// so the user should be able to see the alternate identifiers and choose which platform to track the mod on.
+///
+/// The merged result keeps the position, details, and identifier of the Modrinth result if there is one,
+/// or of the first result otherwise. The identifiers of the other results are added to its
+/// [`alternates`](SearchResult::alternates), and their downloads are added to its downloads.
+/// Results from the same platform are never merged.
+pub async fn merge_duplicates(
+    results: Vec<SearchResult>,
+) -> Result<Vec<SearchResult>, metadata::Error> {
+    let mut merged = Vec::<SearchResult>::new();
+    for result in results {
+        let provider = result.identifier.provider();
+        let mut index = None;
+        for (i, other) in merged.iter().enumerate() {
+            if std::iter::once(&other.identifier)
+                .chain(&other.alternates)
+                .all(|identifier| identifier.provider() != provider)
+                && same_project(&other.identifier, &result.identifier).await?
+            {
+                index = Some(i);
+                break;
+            }
+        }
+        let Some(index) = index else {
+            merged.push(result);
+            continue;
+        };
+        let existing = &mut merged[index];
+        existing.downloads += result.downloads;
+        if provider == ProviderKind::Modrinth {
+            let mut alternates = std::mem::take(&mut existing.alternates);
+            alternates.push(existing.identifier.clone());
+            let downloads = existing.downloads;
+            *existing = result;
+            existing.downloads = downloads;
+            existing.alternates.extend(alternates);
+        } else {
+            existing.alternates.push(result.identifier);
+        }
+    }
+    Ok(merged)
+}
+
+/// The project found on a platform by [`cross_provider_report`]
+#[derive(Debug)]
+pub struct ProviderReport {
+    pub identifier: ModIdentifier,
+    pub name: String,
+    /// The project's total downloads, which GitHub doesn't provide for repositories
+    pub downloads: Option<usize>,
+    /// The newest file compatible with the profile
+    pub latest: Result<DownloadData, mod_downloadable::Error>,
+}
+
+/// The best match for a mod's name on each platform, see [`cross_provider_report`]
+///
+/// Each platform is `None` if nothing was found on it, or if the profile doesn't allow mods from it.
+#[derive(Debug)]
+pub struct CrossProviderReport {
+    /// The most relevant Modrinth project
+    pub modrinth: Result<Option<ProviderReport>, Error>,
+    /// The most relevant CurseForge project
+    pub curseforge: Result<Option<ProviderReport>, Error>,
+    /// The most relevant GitHub repository
+    pub github: Result<Option<ProviderReport>, Error>,
+}
+
+/// Search Modrinth, CurseForge, and GitHub for the mod called `name`, and resolve the newest file of the best match
+/// on each that is compatible with `profile`, so that the user can choose which platform to track the mod on
+///
+/// The platforms are searched and resolved concurrently, and the most relevant result of each is used,
+/// which may be a different project with a similar name.
+/// Platforms that the profile doesn't [allow](Profile::allows_provider) aren't searched.
+pub async fn cross_provider_report(profile: &Profile, name: &str) -> CrossProviderReport {
+    let resolve = |identifier: ModIdentifier, name: String, downloads: Option<usize>| async move {
+        let latest = Mod::new(name.clone(), identifier.clone(), Vec::new(), false)
+            .fetch_download_file(profile.filters.clone(), FileSelection::default())
+            .await;
+        ProviderReport {
+            identifier,
+            name,
+            downloads,
+            latest,
+        }
+    };
+    let allows = |provider| profile.allows_provider(provider);
+
+    let modrinth = async {
+        if !allows(ProviderKind::Modrinth) {
+            return Ok(None);
+        }
+        let hit = MODRINTH_API
+            .search_paged(
+                name,
+                &Sort::Relevance,
+                1,
+                0,
+                vec![vec![Facet::ProjectType(ProjectType::Mod)]],
+            )
+            .await?
+            .hits
+            .into_iter()
+            .next();
+        Ok(match hit {
+            Some(hit) => Some(
+                resolve(
+                    ModIdentifier::ModrinthProject(hit.project_id),
+                    hit.title,
+                    Some(hit.downloads),
+                )
+                .await,
+            ),
+            None => None,
+        })
+    };
+    let curseforge = async {
+        if !allows(ProviderKind::CurseForge) {
+            return Ok(None);
+        }
+        #[cfg(feature = "curseforge")]
+        {
+            let query = SearchFilters::default().curseforge_query().await?;
+            let result = curseforge_page(name, &query, 0, 1)
+                .await?
+                .0
+                .into_iter()
+                .next();
+            Ok(match result {
+                Some(result) => {
+                    Some(resolve(result.identifier, result.name, Some(result.downloads)).await)
+                }
+                None => None,
+            })
+        }
+        #[cfg(not(feature = "curseforge"))]
+        Err(Error::CurseForgeDisabled)
+    };
+    let github = async {
+        if !allows(ProviderKind::GitHub) {
+            return Ok(None);
+        }
+        let repo = GITHUB_API
+            .search()
+            .repositories(&format!("{name} in:name"))
+            .per_page(1)
+            .send()
+            .await?
+            .items
+            .into_iter()
+            .find_map(|repo| Some((repo.owner?.login, repo.name)));
+        Ok(match repo {
+            Some((owner, repo)) => Some(
+                resolve(
+                    ModIdentifier::GitHubRepository(owner, repo.clone()),
+                    repo,
+                    None,
+                )
+                .await,
+            ),
+            None => None,
+        })
+    };
+
+    let (modrinth, curseforge, github) = join!(modrinth, curseforge, github);
+    CrossProviderReport {
+        modrinth,
+        curseforge,
+        github,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(identifier: ModIdentifier, name: &str, downloads: usize) -> SearchResult {
+        SearchResult {
+            identifier,
+            name: name.to_owned(),
+            description: String::new(),
+            downloads,
+            slug: name.to_lowercase(),
+            author: String::new(),
+            alternates: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn merge_same_project() {
+        let curseforge = ModIdentifier::CurseForgeProject(900101);
+        let modrinth = ModIdentifier::ModrinthProject("MergeTest".to_owned());
+        let other = ModIdentifier::ModrinthProject("MergeOther".to_owned());
+        metadata::cache(curseforge.clone(), "Merge Test", "merge-test", &[]);
+        metadata::cache(modrinth.clone(), "Merge Test", "merge-test", &[]);
+        metadata::cache(other.clone(), "Other", "other", &[]);
+
+        let merged = merge_duplicates(vec![
+            result(curseforge.clone(), "Merge Test", 10),
+            result(other.clone(), "Other", 5),
+            result(modrinth.clone(), "Merge Test", 20),
+        ])
+        .await
+        .unwrap();
+        assert_eq!(merged.len(), 2);
+        // The Modrinth result is kept, in the position of the first result
+        assert_eq!(merged[0].identifier, modrinth);
+        assert_eq!(merged[0].alternates, [curseforge]);
+        assert_eq!(merged[0].downloads, 30);
+        assert_eq!(merged[1].identifier, other);
+    }
+
+    #[cfg(feature = "curseforge")]
+    #[test]
+    fn curseforge_category_mapping() {
+        let category = |id: i32, class_id: i32, name: &str, slug: &str| {
+            serde_json::from_value::<Category>(serde_json::json!({
+                "id": id,
+                "gameId": 432,
+                "name": name,
+                "slug": slug,
+                "url": format!("https://www.curseforge.com/minecraft/{slug}"),
+                "iconUrl": "https://media.forgecdn.net/avatars/icon.png",
+                "dateModified": "2024-01-01T00:00:00Z",
+                "isClass": false,
+                "classId": class_id,
+                "parentCategoryId": class_id,
+            }))
+            .unwrap()
+        };
+        let categories = [
+            category(412, 6, "Technology", "technology"),
+            category(419, 6, "Magic", "magic"),
+            category(5191, 12, "Magic", "magic-texture-packs"),
+        ];
+
+        assert_eq!(
+            curseforge_category_ids(
+                &categories,
+                6,
+                &["magic".to_owned(), "Technology".to_owned()]
+            )
+            .unwrap(),
+            [419, 412]
+        );
+        // Categories are only looked up in the class that's searched
+        assert_eq!(
+            curseforge_category_ids(&categories, 12, &["Magic".to_owned()]).unwrap(),
+            [5191]
+        );
+        assert!(matches!(
+            curseforge_category_ids(&categories, 6, &["Cooking".to_owned()]),
+            Err(Error::UnknownCategory(name)) if name == "Cooking"
+        ));
+    }
+
+    #[cfg(feature = "curseforge")]
+    #[tokio::test]
+    async fn interleaved_streams() {
+        let modrinth =
+            ["A", "B", "C"].map(|id| result(ModIdentifier::ModrinthProject(id.to_owned()), id, 0));
+        let curseforge = [1].map(|id| result(ModIdentifier::CurseForgeProject(id), "1", 0));
+
+        let results = round_robin(stream::iter(modrinth), stream::iter(curseforge))
+            .map(|result| result.identifier)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            results,
+            [
+                ModIdentifier::ModrinthProject("A".to_owned()),
+                ModIdentifier::CurseForgeProject(1),
+                ModIdentifier::ModrinthProject("B".to_owned()),
+                ModIdentifier::ModrinthProject("C".to_owned()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn disallowed_platforms_not_searched() {
+        let mut profile = Profile::new(
+            "Profile".to_owned(),
+            "mods".into(),
+            vec!["1.20.1".to_owned()],
+            ModLoader::Fabric,
+        );
+        profile.allowed_providers = HashSet::from([ProviderKind::GitHub]);
+
+        let results = search_stream(&profile, "sodium");
+        assert_eq!(futures_util::StreamExt::count(results).await, 0);
+        // The filters aren't validated either, which would fetch the list of game versions
+        let filters = SearchFilters {
+            game_versions: vec!["1.20.1".to_owned()],
+            ..Default::default()
+        };
+        let results = search_stream_filtered(&profile, "sodium", &filters)
+            .await
+            .unwrap();
+        assert_eq!(futures_util::StreamExt::count(results).await, 0);
+    }
+}
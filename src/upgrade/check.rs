@@ -2,10 +2,10 @@ use super::Metadata;
 use crate::{
     config::filters::{Filter, ReleaseChannel},
     iter_ext::{IterExt, IterExtPositions},
-    MODRINTH_API,
 };
 use ferinth::structures::tag::GameVersionType;
 use regex::Regex;
+use semver::{Version, VersionReq};
 use std::{collections::HashSet, sync::OnceLock};
 
 #[derive(thiserror::Error, Debug)]
@@ -20,6 +20,13 @@ pub enum Error {
 }
 pub type Result<T> = std::result::Result<T, Error>;
 
+impl Error {
+    /// Whether this error was caused by no files being compatible, rather than a failure to run the filters
+    pub fn is_incompatibility(&self) -> bool {
+        matches!(self, Error::FilterEmpty(_) | Error::IntersectFailure)
+    }
+}
+
 static VERSION_GROUPS: OnceLock<Vec<Vec<String>>> = OnceLock::new();
 
 /// Gets groups of versions that are considered minor updates in terms of mod compatibility
@@ -29,12 +36,14 @@ pub async fn get_version_groups() -> Result<&'static Vec<Vec<String>>> {
     if let Some(v) = VERSION_GROUPS.get() {
         Ok(v)
     } else {
-        let versions = MODRINTH_API.list_game_versions().await?;
+        let versions = crate::game_version::list().await?;
         let mut v = vec![vec![]];
         for version in versions {
-            if version.version_type == GameVersionType::Release {
+            if version.version_type == GameVersionType::Release
+                && !crate::game_version::is_ancient(&version.version)
+            {
                 // Push the version to the latest group
-                v.last_mut().unwrap().push(version.version);
+                v.last_mut().unwrap().push(version.version.clone());
                 // Create a new group if a new major versions is present
                 if version.major {
                     v.push(vec![]);
@@ -75,8 +84,26 @@ impl Filter {
                 .positions(|f| versions.iter().any(|vc| f.game_versions.contains(vc)))
                 .collect_hashset(),
 
+            Filter::GameVersionSet(versions) => {
+                let coverage = |f: &Metadata| {
+                    versions
+                        .iter()
+                        .filter(|v| f.game_versions.contains(v))
+                        .count()
+                };
+                let most = download_files
+                    .clone()
+                    .map(|(_, f)| coverage(f))
+                    .max()
+                    .unwrap_or_default();
+                download_files
+                    .positions(|f| most > 0 && coverage(f) == most)
+                    .collect_hashset()
+            }
+
             Filter::GameVersionMinor(versions) => {
-                let mut final_versions = vec![];
+                // Versions that aren't in any group, such as alpha and beta versions, still match themselves
+                let mut final_versions = versions.clone();
                 for group in get_version_groups().await? {
                     if group.iter().any(|v| versions.contains(v)) {
                         final_versions.extend(group.clone());
@@ -98,6 +125,9 @@ impl Filter {
                 })
                 .collect_hashset(),
 
+            // Should have been converted with `Filter::for_provider()`, since the platform of the files isn't known here
+            Filter::ProviderReleaseChannel(..) => download_files.map(|(i, _)| i).collect_hashset(),
+
             Filter::Filename(regex) => {
                 let regex = Regex::new(regex)?;
                 download_files
@@ -118,8 +148,67 @@ impl Filter {
                     .positions(|f| regex.is_match(&f.description))
                     .collect_hashset()
             }
+
+            Filter::VersionConstraint(constraint) => {
+                let requirement = VersionReq::parse(constraint).ok();
+                download_files
+                    .positions(|f| {
+                        let version = f.version.as_deref().unwrap_or(&f.filename);
+                        match (&requirement, semver_version(version, &f.game_versions)) {
+                            (Some(requirement), Some(version)) => requirement.matches(&version),
+                            _ => version.contains(constraint.as_str()),
+                        }
+                    })
+                    .collect_hashset()
+            }
         })
     }
+
+    /// Whether `self` is a [`Filter::VersionConstraint`] that is matched as a substring of `file`'s version number,
+    /// since either the constraint isn't a semver requirement or the version number isn't semver
+    pub fn is_substring_match(&self, file: &Metadata) -> bool {
+        let Filter::VersionConstraint(constraint) = self else {
+            return false;
+        };
+        VersionReq::parse(constraint).is_err()
+            || semver_version(
+                file.version.as_deref().unwrap_or(&file.filename),
+                &file.game_versions,
+            )
+            .is_none()
+    }
+}
+
+/// Find the semver version in `version`, e.g. `0.5.3` in `v0.5.3`, `0.5.3+1.20.1`, or `mymod-1.20.1-0.5.3`
+///
+/// Versions that are one of the file's `game_versions` are skipped, so that `1.20.1-0.5.3` isn't read as `1.20.1`.
+/// Versions with only a major and minor number are treated as having a patch number of 0.
+fn semver_version(version: &str, game_versions: &[String]) -> Option<Version> {
+    let is_game_version = |v: &Version| {
+        game_versions.iter().any(|game_version| {
+            *game_version == format!("{}.{}.{}", v.major, v.minor, v.patch)
+                || (v.patch == 0 && *game_version == format!("{}.{}", v.major, v.minor))
+        })
+    };
+    let parse = |part: &str| {
+        let part = part.trim_start_matches(['v', 'V']);
+        Version::parse(part)
+            .ok()
+            .or_else(|| {
+                if part.matches('.').count() == 1 {
+                    Version::parse(&format!("{part}.0")).ok()
+                } else {
+                    None
+                }
+            })
+            .filter(|v| !is_game_version(v))
+    };
+    parse(version).or_else(|| {
+        version
+            .split(['-', '_', '+', ' '])
+            .map(|part| part.trim_start_matches("mc"))
+            .find_map(parse)
+    })
 }
 
 /// Assumes that the provided `download_files` are sorted in the order of preference (e.g. chronological)
@@ -131,8 +220,11 @@ pub async fn select_latest(
     let mut run_last = vec![];
 
     for filter in &filters {
-        if let Filter::ModLoaderPrefer(_) = filter {
-            // ModLoaderPrefer has to be run last
+        if matches!(
+            filter,
+            Filter::ModLoaderPrefer(_) | Filter::GameVersionSet(_)
+        ) {
+            // ModLoaderPrefer and GameVersionSet have to be run last
             run_last.push((
                 filter,
                 filter.filter(download_files.clone().enumerate()).await?,
@@ -175,31 +267,269 @@ pub async fn select_latest(
         })
         .unwrap_or_default();
 
-    let download_files = download_files.into_iter().enumerate().filter_map(|(i, f)| {
-        if final_indices.contains(&i) {
-            Some((i, f))
-        } else {
-            None
+    let all_files = download_files.clone().collect_vec();
+    let mut download_files = download_files
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| final_indices.contains(i))
+        .collect_vec();
+
+    // Narrow down to the files covering the most game versions before preferring loaders
+    for (filter, _) in &run_last {
+        if let Filter::GameVersionSet(_) = filter {
+            let covering = filter.filter(download_files.iter().copied()).await?;
+            download_files.retain(|(i, _)| covering.contains(i));
         }
-    });
+    }
 
     let mut filter_results = vec![];
     for (filter, _) in run_last {
-        filter_results.push(filter.filter(download_files.clone()).await?)
+        if let Filter::ModLoaderPrefer(_) = filter {
+            filter_results.push(filter.filter(download_files.iter().copied()).await?)
+        }
     }
     let mut filter_results = filter_results.into_iter();
 
-    let final_index = filter_results
-        .next()
-        .and_then(|set_1| {
-            filter_results
-                .fold(set_1, |set_a, set_b| {
-                    set_a.intersection(&set_b).copied().collect_hashset()
-                })
-                .into_iter()
-                .min()
-        })
+    let candidates = match filter_results.next() {
+        Some(set_1) => filter_results.fold(set_1, |set_a, set_b| {
+            set_a.intersection(&set_b).copied().collect_hashset()
+        }),
+        // There's no `ModLoaderPrefer` filter
+        None => download_files.iter().map(|(i, _)| *i).collect_hashset(),
+    };
+    let final_index = candidates
+        .iter()
+        .copied()
+        .min()
         .ok_or(Error::IntersectFailure)?;
 
-    Ok(final_index)
+    Ok(prefer_native_loader(
+        &all_files,
+        &candidates,
+        final_index,
+        &filters,
+    ))
+}
+
+/// Get the filters that reject each of `download_files`, in the same order
+///
+/// Each filter is run on all the files separately, so `ModLoaderPrefer` rejects the files
+/// that don't have the most preferred loader out of all the files, even if those files are rejected by other filters.
+pub async fn rejections(
+    download_files: impl Iterator<Item = &Metadata> + Clone,
+    filters: &[Filter],
+) -> Result<Vec<Vec<Filter>>> {
+    let mut rejected_by = vec![Vec::new(); download_files.clone().count()];
+    for filter in filters {
+        let accepted = filter.filter(download_files.clone().enumerate()).await?;
+        for (i, rejected_by) in rejected_by.iter_mut().enumerate() {
+            if !accepted.contains(&i) {
+                rejected_by.push(filter.clone());
+            }
+        }
+    }
+    Ok(rejected_by)
+}
+
+/// Pick the file of the same release as `index` that supports the most preferred loader in `filters`
+///
+/// Releases sometimes have separate files for each loader, e.g. a Quilt profile accepting Fabric files
+/// should get the Quilt file of a release that has both, but still get a newer release that only has a Fabric file.
+/// Files are of the same release if they have the same version number, or title if they don't have one.
+fn prefer_native_loader(
+    download_files: &[&Metadata],
+    candidates: &HashSet<usize>,
+    index: usize,
+    filters: &[Filter],
+) -> usize {
+    let Some(loaders) = filters.iter().find_map(|filter| match filter {
+        Filter::ModLoaderPrefer(loaders) | Filter::ModLoaderAny(loaders) => Some(loaders),
+        _ => None,
+    }) else {
+        return index;
+    };
+    let rank = |file: &Metadata| {
+        loaders
+            .iter()
+            .position(|loader| file.loaders.contains(loader))
+            .unwrap_or(usize::MAX)
+    };
+    let release = |file: &Metadata| file.version.clone().unwrap_or_else(|| file.title.clone());
+
+    let chosen = download_files[index];
+    candidates
+        .iter()
+        .copied()
+        .filter(|&i| release(download_files[i]) == release(chosen))
+        .min_by_key(|&i| (rank(download_files[i]), i))
+        .unwrap_or(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::structs::{ContentType, ModLoader, Profile};
+
+    fn file(version: &str, game_versions: &[&str]) -> Metadata {
+        Metadata {
+            title: version.to_owned(),
+            description: String::new(),
+            filename: format!("mod-{version}.jar"),
+            channel: ReleaseChannel::Release,
+            game_versions: game_versions.iter().map(|&v| v.to_owned()).collect(),
+            loaders: Vec::new(),
+            version: Some(version.to_owned()),
+        }
+    }
+
+    fn loader_file(version: &str, loader: ModLoader) -> Metadata {
+        Metadata {
+            loaders: vec![loader],
+            filename: format!("mod-{loader}-{version}.jar").to_lowercase(),
+            ..file(version, &["1.20.1"])
+        }
+    }
+
+    async fn constrained(constraint: &str, files: &[Metadata]) -> Vec<usize> {
+        let filter = Filter::VersionConstraint(constraint.to_owned());
+        let mut indices = filter
+            .filter(files.iter().enumerate())
+            .await
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
+        indices.sort_unstable();
+        indices
+    }
+
+    #[test]
+    fn native_loader_of_same_release() {
+        // The newest release has both a Fabric and a Quilt file, and the Fabric file is listed first
+        let files = [
+            loader_file("1.1.0", ModLoader::Fabric),
+            loader_file("1.1.0", ModLoader::Quilt),
+            loader_file("1.0.0", ModLoader::Quilt),
+        ];
+        let files = files.iter().collect::<Vec<_>>();
+        let candidates = HashSet::from([0, 1, 2]);
+        let quilt = [Filter::ModLoaderPrefer(vec![
+            ModLoader::Quilt,
+            ModLoader::Fabric,
+        ])];
+        assert_eq!(prefer_native_loader(&files, &candidates, 0, &quilt), 1);
+
+        let fabric = [Filter::ModLoaderAny(vec![
+            ModLoader::Fabric,
+            ModLoader::Quilt,
+        ])];
+        assert_eq!(prefer_native_loader(&files, &candidates, 0, &fabric), 0);
+
+        // Files of other releases aren't picked, even if they're for a more preferred loader
+        let candidates = HashSet::from([0, 2]);
+        assert_eq!(prefer_native_loader(&files, &candidates, 0, &quilt), 0);
+        // Without any loader filters, the file is kept
+        assert_eq!(prefer_native_loader(&files, &candidates, 0, &[]), 0);
+    }
+
+    #[tokio::test]
+    async fn shader_pack_on_forge_profile() {
+        let profile = Profile::new(
+            "Profile".to_owned(),
+            "mods".into(),
+            vec!["1.20.1".to_owned()],
+            ModLoader::Forge,
+        );
+        // Shader packs are for shader loaders like Iris and OptiFine, which aren't mod loaders
+        let files = [
+            file("1.6.11", &["1.20.1", "1.20.2"]),
+            file("1.6.10", &["1.19.2"]),
+        ];
+
+        let filters = ContentType::ShaderPack.applicable_filters(profile.filters.clone());
+        assert_eq!(select_latest(files.iter(), filters).await.unwrap(), 0);
+        // The game version is still checked
+        let filters = ContentType::ShaderPack.applicable_filters(vec![
+            Filter::ModLoaderPrefer(vec![ModLoader::Forge]),
+            Filter::GameVersionStrict(vec!["1.19.2".to_owned()]),
+        ]);
+        assert_eq!(select_latest(files.iter(), filters).await.unwrap(), 1);
+
+        let filters = ContentType::Mod.applicable_filters(profile.filters);
+        assert!(select_latest(files.iter(), filters)
+            .await
+            .is_err_and(|err| err.is_incompatibility()));
+    }
+
+    #[test]
+    fn semver_version_formats() {
+        let version = |major, minor, patch| Some(Version::new(major, minor, patch));
+        assert_eq!(semver_version("0.5.3", &[]), version(0, 5, 3));
+        assert_eq!(semver_version("v0.5.3", &[]), version(0, 5, 3));
+        assert_eq!(semver_version("1.2", &[]), version(1, 2, 0));
+        assert_eq!(
+            semver_version("0.5.3+1.20.1", &["1.20.1".to_owned()]),
+            Version::parse("0.5.3+1.20.1").ok()
+        );
+        assert_eq!(
+            semver_version("mymod-1.20.1-0.5.3", &["1.20.1".to_owned()]),
+            version(0, 5, 3)
+        );
+        assert_eq!(
+            semver_version("mc1.20-2.1", &["1.20".to_owned()]),
+            version(2, 1, 0)
+        );
+        assert_eq!(semver_version("build 42", &[]), None);
+    }
+
+    #[tokio::test]
+    async fn caret_constraint() {
+        let files = [
+            file("0.4.9", &[]),
+            file("0.5.0", &[]),
+            file("0.5.3", &[]),
+            file("0.6.0", &[]),
+        ];
+        assert_eq!(constrained("^0.5", &files).await, [1, 2]);
+        assert_eq!(constrained("^0.5.1", &files).await, [2]);
+    }
+
+    #[tokio::test]
+    async fn tilde_constraint() {
+        let files = [
+            file("1.2.2", &["1.20.1"]),
+            file("1.20.1-1.2.3", &["1.20.1"]),
+            file("1.2.9", &["1.20.1"]),
+            file("1.3.0", &["1.20.1"]),
+        ];
+        assert_eq!(constrained("~1.2.3", &files).await, [1, 2]);
+    }
+
+    #[tokio::test]
+    async fn range_constraint() {
+        let files = [
+            file("0.9.9", &[]),
+            file("1.0.0", &[]),
+            file("1.9.0", &[]),
+            file("2.0.0", &[]),
+        ];
+        assert_eq!(constrained(">=1.0, <2.0", &files).await, [1, 2]);
+    }
+
+    #[tokio::test]
+    async fn substring_fallback() {
+        let files = [
+            file("build 41", &[]),
+            file("build 42", &[]),
+            file("1.0.0", &[]),
+        ];
+        // The constraint isn't a semver requirement
+        assert_eq!(constrained("build 4", &files).await, [0, 1]);
+        // The version numbers aren't semver
+        assert_eq!(constrained("42", &files).await, [1]);
+
+        let filter = Filter::VersionConstraint("^1".to_owned());
+        assert!(filter.is_substring_match(&files[0]));
+        assert!(!filter.is_substring_match(&files[2]));
+        assert!(!Filter::Title("build".to_owned()).is_substring_match(&files[0]));
+    }
 }
@@ -0,0 +1,245 @@
+use super::mod_downloadable::{resolve_all, Error};
+use crate::{
+    config::{
+        filters::FileSelection,
+        structs::{ModIdentifier, Profile},
+    },
+    iter_ext::IterExt as _,
+};
+
+/// The mods of a profile can't be ordered by [`install_order`] since their required dependencies form cycles
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("The required dependencies of these mods form cycles: {0:?}")]
+pub struct DependencyCycles(
+    /// The names of the mods in each cycle
+    pub Vec<Vec<String>>,
+);
+
+/// A mod in a [`DependencyGraph`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyNode {
+    /// The identifier of the mod, without any pin
+    pub identifier: ModIdentifier,
+    /// The name of the mod in the profile, or `None` if the mod is only a dependency that isn't in the profile
+    pub name: Option<String>,
+}
+
+/// A dependency of the mod at `from` on the mod at `to`, as indices into [`DependencyGraph::nodes`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DependencyEdge {
+    pub from: usize,
+    pub to: usize,
+    /// Whether the dependency is required, rather than optional
+    pub required: bool,
+}
+
+/// The dependency relationships between the mods of a profile
+#[derive(Debug)]
+pub struct DependencyGraph {
+    pub nodes: Vec<DependencyNode>,
+    pub edges: Vec<DependencyEdge>,
+    /// The cycles of required dependencies, each as the indices of the nodes in the cycle
+    pub cycles: Vec<Vec<usize>>,
+    /// The mods whose file could not be resolved, so their dependencies are unknown
+    pub errors: Vec<(String, Error)>,
+}
+
+impl DependencyGraph {
+    /// Get the indices of the nodes in an order where every node comes after its required dependencies
+    ///
+    /// Returns `None` if there are any cycles.
+    pub fn install_order(&self) -> Option<Vec<usize>> {
+        if !self.cycles.is_empty() {
+            return None;
+        }
+        let mut order = Vec::new();
+        let mut visited = vec![false; self.nodes.len()];
+        for node in 0..self.nodes.len() {
+            self.visit_post_order(node, &mut visited, &mut order);
+        }
+        Some(order)
+    }
+
+    fn visit_post_order(&self, node: usize, visited: &mut [bool], order: &mut Vec<usize>) {
+        if visited[node] {
+            return;
+        }
+        visited[node] = true;
+        for dependency in self.required_dependencies(node) {
+            self.visit_post_order(dependency, visited, order);
+        }
+        order.push(node);
+    }
+
+    fn required_dependencies(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        self.edges
+            .iter()
+            .filter(move |edge| edge.required && edge.from == node)
+            .map(|edge| edge.to)
+    }
+
+    /// Find the cycles of required dependencies using a depth first search
+    fn find_cycles(&self) -> Vec<Vec<usize>> {
+        let mut cycles = Vec::new();
+        // Whether each node has been completely searched
+        let mut done = vec![false; self.nodes.len()];
+        let mut path = Vec::new();
+        for node in 0..self.nodes.len() {
+            self.search_cycles(node, &mut done, &mut path, &mut cycles);
+        }
+        cycles
+    }
+
+    fn search_cycles(
+        &self,
+        node: usize,
+        done: &mut [bool],
+        path: &mut Vec<usize>,
+        cycles: &mut Vec<Vec<usize>>,
+    ) {
+        if done[node] {
+            return;
+        }
+        if let Some(start) = path.iter().position(|&n| n == node) {
+            cycles.push(path[start..].to_vec());
+            return;
+        }
+        path.push(node);
+        for dependency in self.required_dependencies(node).collect_vec() {
+            self.search_cycles(dependency, done, path, cycles);
+        }
+        path.pop();
+        done[node] = true;
+    }
+}
+
+/// Build the graph of the required dependencies between the enabled mods in `profile`,
+/// from the [dependencies recorded](crate::config::structs::Mod::required_dependencies) when they were added
+///
+/// Unlike [`dependency_graph`], nothing is resolved, so the graph doesn't reflect any changes since the mods were added.
+/// Dependencies that aren't in the profile are included as nodes without a name.
+pub fn stored_dependency_graph(profile: &Profile) -> DependencyGraph {
+    let mut nodes = profile
+        .enabled_mods()
+        .map(|mod_| DependencyNode {
+            identifier: mod_.identifier.unpinned(),
+            name: Some(mod_.name.clone()),
+        })
+        .collect_vec();
+    let mut edges = Vec::new();
+
+    for (from, mod_) in profile.enabled_mods().enumerate() {
+        for dependency in &mod_.required_dependencies {
+            let dependency = dependency.unpinned();
+            let to = match nodes.iter().position(|node| node.identifier == dependency) {
+                Some(to) => to,
+                None => {
+                    nodes.push(DependencyNode {
+                        identifier: dependency,
+                        name: None,
+                    });
+                    nodes.len() - 1
+                }
+            };
+            let edge = DependencyEdge {
+                from,
+                to,
+                required: true,
+            };
+            if !edges.contains(&edge) {
+                edges.push(edge);
+            }
+        }
+    }
+
+    let mut graph = DependencyGraph {
+        nodes,
+        edges,
+        cycles: Vec::new(),
+        errors: Vec::new(),
+    };
+    graph.cycles = graph.find_cycles();
+    graph
+}
+
+/// Get the names of the enabled mods in `profile` in an order where every mod comes after its required dependencies,
+/// e.g. to install libraries before the mods that use them
+///
+/// This uses the [stored dependency graph](stored_dependency_graph), so nothing is resolved.
+/// Returns [`DependencyCycles`] if the required dependencies form any cycles.
+pub fn install_order(profile: &Profile) -> Result<Vec<String>, DependencyCycles> {
+    let graph = stored_dependency_graph(profile);
+    let names = |nodes: &[usize]| {
+        nodes
+            .iter()
+            .filter_map(|&node| graph.nodes[node].name.clone())
+            .collect_vec()
+    };
+    graph
+        .install_order()
+        .map(|order| names(&order))
+        .ok_or_else(|| DependencyCycles(graph.cycles.iter().map(|cycle| names(cycle)).collect()))
+}
+
+/// Build the dependency graph of the enabled mods in `profile`, from the dependencies of their resolved files
+///
+/// Dependencies that aren't in the profile are included as nodes without a name.
+pub async fn dependency_graph(profile: &Profile, file_selection: FileSelection) -> DependencyGraph {
+    let plan = resolve_all(profile, file_selection).await;
+
+    let mut nodes = profile
+        .enabled_mods()
+        .map(|mod_| DependencyNode {
+            identifier: mod_.identifier.unpinned(),
+            name: Some(mod_.name.clone()),
+        })
+        .collect_vec();
+    let mut edges = Vec::new();
+
+    for resolved in &plan.mods {
+        let Some(from) = nodes
+            .iter()
+            .position(|node| node.name.as_ref() == Some(&resolved.name))
+        else {
+            continue;
+        };
+        let dependencies = resolved
+            .download_data
+            .dependencies
+            .iter()
+            .map(|dependency| (dependency, true))
+            .chain(
+                resolved
+                    .download_data
+                    .optional_dependencies
+                    .iter()
+                    .map(|dependency| (dependency, false)),
+            );
+        for (dependency, required) in dependencies {
+            let dependency = dependency.unpinned();
+            let to = match nodes.iter().position(|node| node.identifier == dependency) {
+                Some(to) => to,
+                None => {
+                    nodes.push(DependencyNode {
+                        identifier: dependency,
+                        name: None,
+                    });
+                    nodes.len() - 1
+                }
+            };
+            let edge = DependencyEdge { from, to, required };
+            if !edges.contains(&edge) {
+                edges.push(edge);
+            }
+        }
+    }
+
+    let mut graph = DependencyGraph {
+        nodes,
+        edges,
+        cycles: Vec::new(),
+        errors: plan.errors,
+    };
+    graph.cycles = graph.find_cycles();
+    graph
+}
@@ -1,26 +1,33 @@
 pub mod check;
+pub mod dependency_graph;
 pub mod mod_downloadable;
 pub mod modpack_downloadable;
 
 use crate::{
     config::{
         filters::ReleaseChannel,
-        structs::{ModIdentifier, ModLoader},
+        structs::{InstalledFile, ModIdentifier, ModLoader, ProviderKind},
     },
     iter_ext::IterExt as _,
     modpack::modrinth::structs::ModpackFile as ModpackModFile,
     version_ext::VersionExt,
 };
+use chrono::{DateTime, Utc};
 use ferinth::structures::version::{
     DependencyType as MRDependencyType, Version as MRVersion, VersionType,
 };
+#[cfg(feature = "curseforge")]
 use furse::structures::file_structs::{
-    File as CFFile, FileRelationType as CFFileRelationType, FileReleaseType,
+    File as CFFile, FileRelationType as CFFileRelationType, FileReleaseType, HashAlgo as CFHashAlgo,
 };
-use octocrab::models::repos::{Asset as GHAsset, Release as GHRelease};
+use octocrab::models::repos::{Asset as GHAsset, Content as GHContent, Release as GHRelease};
 use reqwest::{Client, Url};
+use sha1::{digest::DynDigest, Digest, Sha1};
+use sha2::Sha512;
+#[cfg(feature = "curseforge")]
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::{
-    fs::{create_dir_all, rename, OpenOptions},
+    fs::{create_dir_all, remove_file, rename, OpenOptions},
     io::{BufWriter, Write},
     path::{Path, PathBuf},
     str::FromStr,
@@ -31,6 +38,11 @@ use std::{
 pub enum Error {
     ReqwestError(#[from] reqwest::Error),
     IOError(#[from] std::io::Error),
+    #[error("The downloaded file's hash {actual} does not match the expected hash {expected}")]
+    HashMismatch {
+        expected: String,
+        actual: String,
+    },
 }
 type Result<T> = std::result::Result<T, Error>;
 
@@ -46,6 +58,9 @@ pub struct Metadata {
 
     pub game_versions: Vec<String>,
     pub loaders: Vec<ModLoader>,
+
+    /// The version number of the Modrinth Version, the tag of the GitHub Release, or the display name of the CurseForge File
+    pub version: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -59,8 +74,39 @@ pub struct DownloadData {
     pub length: usize,
     /// The dependencies this file has
     pub dependencies: Vec<ModIdentifier>,
+    /// The dependencies this file can optionally use
+    pub optional_dependencies: Vec<ModIdentifier>,
     /// Other mods this file is incompatible with
     pub conflicts: Vec<ModIdentifier>,
+    /// The ID of this file on its platform, which a mod can be pinned to
+    ///
+    /// This is the CurseForge file ID, Modrinth version ID, or GitHub release asset ID.
+    /// Files from modpacks don't have one.
+    pub file_id: Option<String>,
+    /// The hex encoded SHA-1 hash of the file, if the platform provides one
+    pub sha1: Option<String>,
+    /// The hex encoded SHA-512 hash of the file, if the platform provides one
+    ///
+    /// Modrinth provides it, while CurseForge only provides SHA-1 and MD5 hashes.
+    pub sha512: Option<String>,
+    /// When this file was published
+    pub published: Option<DateTime<Utc>>,
+    /// The version number of the Modrinth version, the display name of the CurseForge file,
+    /// or the tag of the GitHub release this file is from
+    pub version: Option<String>,
+    /// The loader that this file was chosen for, out of the loaders the profile accepts
+    ///
+    /// This is only known for files resolved using filters, see [`check::select_latest`].
+    pub loader: Option<ModLoader>,
+    /// The game version that this file was chosen for, out of the game versions the profile accepts
+    ///
+    /// This is only known for files resolved using filters, like [`DownloadData::loader`].
+    pub game_version: Option<String>,
+    /// The other files of the Modrinth version that this file is the primary file of, e.g. translations or addons
+    ///
+    /// These should be downloaded along with this file if the profile [includes optional files](crate::config::structs::Profile::include_optional_files),
+    /// otherwise [`resolve_all`](mod_downloadable::resolve_all) moves them to [`ResolvedMod::skipped_files`](mod_downloadable::ResolvedMod::skipped_files).
+    pub optional_files: Vec<DownloadData>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -68,12 +114,70 @@ pub struct DownloadData {
 /// Contains the mod ID and file ID
 pub struct DistributionDeniedError(pub i32, pub i32);
 
+impl DistributionDeniedError {
+    /// The URL of the project's page on CurseForge, where the user can download the file manually
+    pub fn project_url(&self) -> String {
+        format!(
+            "{}/projects/{}",
+            ProviderKind::CurseForge
+                .base_url()
+                .expect("CurseForge has a website"),
+            self.0
+        )
+    }
+}
+
+#[cfg(feature = "curseforge")]
+static CURSEFORGE_EDGE_FALLBACK: AtomicBool = AtomicBool::new(false);
+
+/// Set whether CurseForge files without a download URL should be downloaded from CurseForge's CDN
+/// instead of returning [`DistributionDeniedError`]
+///
+/// This is disabled by default. The URL is constructed using [`curseforge_edge_url`], so it's only a best effort.
+#[cfg(feature = "curseforge")]
+pub fn set_curseforge_edge_fallback(enabled: bool) {
+    CURSEFORGE_EDGE_FALLBACK.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether CurseForge files without a download URL are downloaded from CurseForge's CDN
+#[cfg(feature = "curseforge")]
+pub fn curseforge_edge_fallback() -> bool {
+    CURSEFORGE_EDGE_FALLBACK.load(Ordering::Relaxed)
+}
+
+/// Construct the URL of the CurseForge file with `file_id` and `file_name` on CurseForge's CDN
+///
+/// CurseForge's API doesn't provide the download URLs of files from projects that deny third party distribution,
+/// but these files are still at this URL.
+#[cfg(feature = "curseforge")]
+pub fn curseforge_edge_url(file_id: i32, file_name: &str) -> Option<Url> {
+    Url::parse(&format!(
+        "https://mediafilez.forgecdn.net/files/{}/{}/{file_name}",
+        file_id / 1000,
+        file_id % 1000,
+    ))
+    .ok()
+}
+
+/// The hosts of the platforms' CDNs, whose URLs are rewritten to a profile's
+/// [download mirror](crate::config::structs::Profile::download_mirror)
+pub const MIRRORED_HOSTS: &[&str] = &[
+    "cdn.modrinth.com",
+    "edge.forgecdn.net",
+    "mediafilez.forgecdn.net",
+];
+
+/// Convert the CurseForge `file` into its metadata and download data
+///
+/// Returns [`DistributionDeniedError`] if the file's download URL is null,
+/// unless the [edge fallback](set_curseforge_edge_fallback) is enabled.
+#[cfg(feature = "curseforge")]
 pub fn try_from_cf_file(
     file: CFFile,
 ) -> std::result::Result<(Metadata, DownloadData), DistributionDeniedError> {
     Ok((
         Metadata {
-            title: file.display_name,
+            title: file.display_name.clone(),
             description: String::new(), // Changelog requires a separate request
             filename: file.file_name.clone(),
             channel: match file.release_type {
@@ -87,10 +191,16 @@ pub fn try_from_cf_file(
                 .filter_map(|s| ModLoader::from_str(s).ok())
                 .collect_vec(),
             game_versions: file.game_versions,
+            version: Some(file.display_name.clone()),
         },
         DownloadData {
             download_url: file
                 .download_url
+                .or_else(|| {
+                    curseforge_edge_fallback()
+                        .then(|| curseforge_edge_url(file.id, &file.file_name))
+                        .flatten()
+                })
                 .ok_or(DistributionDeniedError(file.mod_id, file.id))?,
             output: file.file_name.as_str().into(),
             length: file.file_length,
@@ -105,6 +215,17 @@ pub fn try_from_cf_file(
                     }
                 })
                 .collect_vec(),
+            optional_dependencies: file
+                .dependencies
+                .iter()
+                .filter_map(|d| {
+                    if d.relation_type == CFFileRelationType::OptionalDependency {
+                        Some(ModIdentifier::CurseForgeProject(d.mod_id))
+                    } else {
+                        None
+                    }
+                })
+                .collect_vec(),
             conflicts: file
                 .dependencies
                 .iter()
@@ -116,16 +237,55 @@ pub fn try_from_cf_file(
                     }
                 })
                 .collect_vec(),
+            file_id: Some(file.id.to_string()),
+            sha1: file
+                .hashes
+                .into_iter()
+                .find(|hash| hash.algo == CFHashAlgo::Sha1)
+                .map(|hash| hash.value),
+            sha512: None,
+            published: Some(file.file_date),
+            version: Some(file.display_name),
+            loader: None,
+            game_version: None,
+            optional_files: Vec::new(),
         },
     ))
 }
 
+/// Convert the Modrinth `version` to platform agnostic structs
+///
+/// Only the version's primary file is used, or the first file if none of them are marked as primary.
+/// The version's other files are its [optional files](DownloadData::optional_files),
+/// except for [secondary artifacts](is_secondary_artifact) such as sources JARs, which are never downloaded.
 pub fn from_mr_version(version: MRVersion) -> (Metadata, DownloadData) {
+    let file = version.get_version_file().clone();
+    let optional_files = version
+        .files
+        .iter()
+        .filter(|optional| optional.url != file.url && !is_secondary_artifact(&optional.filename))
+        .map(|optional| DownloadData {
+            download_url: optional.url.clone(),
+            output: optional.filename.clone().into(),
+            length: optional.size,
+            dependencies: Vec::new(),
+            optional_dependencies: Vec::new(),
+            conflicts: Vec::new(),
+            file_id: Some(version.id.clone()),
+            sha1: Some(optional.hashes.sha1.clone()),
+            sha512: Some(optional.hashes.sha512.clone()),
+            published: Some(version.date_published),
+            version: Some(version.version_number.clone()),
+            loader: None,
+            game_version: None,
+            optional_files: Vec::new(),
+        })
+        .collect_vec();
     (
         Metadata {
             title: version.name.clone(),
             description: version.changelog.as_ref().cloned().unwrap_or_default(),
-            filename: version.get_version_file().filename.clone(),
+            filename: file.filename.clone(),
             channel: match version.version_type {
                 VersionType::Release => ReleaseChannel::Release,
                 VersionType::Beta => ReleaseChannel::Beta,
@@ -138,11 +298,12 @@ pub fn from_mr_version(version: MRVersion) -> (Metadata, DownloadData) {
                 .collect_vec(),
 
             game_versions: version.game_versions.clone(),
+            version: Some(version.version_number.clone()),
         },
         DownloadData {
-            download_url: version.get_version_file().url.clone(),
-            output: version.get_version_file().filename.as_str().into(),
-            length: version.get_version_file().size,
+            download_url: file.url,
+            output: file.filename.into(),
+            length: file.size,
             dependencies: version
                 .dependencies
                 .clone()
@@ -164,6 +325,12 @@ pub fn from_mr_version(version: MRVersion) -> (Metadata, DownloadData) {
                     }
                 })
                 .collect_vec(),
+            optional_dependencies: version
+                .dependencies
+                .iter()
+                .filter(|d| d.dependency_type == MRDependencyType::Optional)
+                .filter_map(|d| d.project_id.clone().map(ModIdentifier::ModrinthProject))
+                .collect_vec(),
             conflicts: version
                 .dependencies
                 .into_iter()
@@ -184,6 +351,14 @@ pub fn from_mr_version(version: MRVersion) -> (Metadata, DownloadData) {
                     }
                 })
                 .collect_vec(),
+            file_id: Some(version.id),
+            sha1: Some(file.hashes.sha1),
+            sha512: Some(file.hashes.sha512),
+            published: Some(version.date_published),
+            version: Some(version.version_number),
+            loader: None,
+            game_version: None,
+            optional_files,
         },
     )
 }
@@ -198,16 +373,33 @@ pub fn from_modpack_file(file: ModpackModFile) -> DownloadData {
         output: file.path,
         length: file.file_size,
         dependencies: Vec::new(),
+        optional_dependencies: Vec::new(),
         conflicts: Vec::new(),
+        file_id: None,
+        sha1: Some(file.hashes.sha1),
+        sha512: Some(file.hashes.sha512),
+        published: None,
+        version: None,
+        loader: None,
+        game_version: None,
+        optional_files: Vec::new(),
     }
 }
 
+/// Convert the GitHub `releases` to platform agnostic structs
+///
+/// The secondary artifacts of each release, like sources JARs, are put after its other assets,
+/// so that they are only selected if the filters reject the mod's own JAR.
+/// Use a [`Filename`](crate::config::filters::Filter::Filename) filter to choose between the other assets.
 pub fn from_gh_releases(
     releases: impl IntoIterator<Item = GHRelease>,
 ) -> Vec<(Metadata, DownloadData)> {
     releases
         .into_iter()
-        .flat_map(|release| {
+        .flat_map(|mut release| {
+            release
+                .assets
+                .sort_by_key(|asset| is_secondary_artifact(&asset.name));
             release.assets.into_iter().map(move |asset| {
                 (
                     Metadata {
@@ -218,27 +410,26 @@ pub fn from_gh_releases(
                         } else {
                             ReleaseChannel::Release
                         },
-                        game_versions: asset
-                            .name
-                            .trim_end_matches(".jar")
-                            .split(['-', '_', '+'])
-                            .map(|s| s.trim_start_matches("mc"))
-                            .map(ToOwned::to_owned)
-                            .collect_vec(),
-                        loaders: asset
-                            .name
-                            .trim_end_matches(".jar")
-                            .split(['-', '_', '+'])
-                            .filter_map(|s| ModLoader::from_str(s).ok())
-                            .collect_vec(),
+                        game_versions: filename_game_versions(&asset.name),
+                        loaders: filename_loaders(&asset.name),
                         filename: asset.name.clone(),
+                        version: Some(release.tag_name.clone()),
                     },
                     DownloadData {
                         download_url: asset.browser_download_url,
                         output: asset.name.into(),
                         length: asset.size as usize,
                         dependencies: Vec::new(),
+                        optional_dependencies: Vec::new(),
                         conflicts: Vec::new(),
+                        file_id: Some(asset.id.to_string()),
+                        sha1: None,
+                        sha512: None,
+                        published: release.published_at.or(Some(asset.created_at)),
+                        version: Some(release.tag_name.clone()),
+                        loader: None,
+                        game_version: None,
+                        optional_files: Vec::new(),
                     },
                 )
             })
@@ -246,13 +437,116 @@ pub fn from_gh_releases(
         .collect_vec()
 }
 
+/// Keep the `files` whose filenames match the `asset_filter` regex, see [`Mod::asset_filter`](crate::config::structs::Mod::asset_filter)
+pub fn filter_assets(
+    files: Vec<(Metadata, DownloadData)>,
+    asset_filter: &str,
+) -> std::result::Result<Vec<(Metadata, DownloadData)>, regex::Error> {
+    let asset_filter = regex::Regex::new(asset_filter)?;
+    Ok(files
+        .into_iter()
+        .filter(|(metadata, _)| asset_filter.is_match(&metadata.filename))
+        .collect_vec())
+}
+
+/// Convert the JAR files in a GitHub repository directory's `contents` to platform agnostic structs
+///
+/// Like release assets, the game versions and mod loaders are guessed from the filenames.
+pub fn from_gh_contents(
+    contents: impl IntoIterator<Item = GHContent>,
+) -> Vec<(Metadata, DownloadData)> {
+    contents
+        .into_iter()
+        .filter(|content| content.r#type == "file" && content.name.ends_with(".jar"))
+        .filter_map(|content| {
+            let version = crate::misc::parse_version_from_filename(&content.name);
+            Some((
+                Metadata {
+                    title: content.name.clone(),
+                    description: String::new(),
+                    channel: ReleaseChannel::Release,
+                    game_versions: filename_game_versions(&content.name),
+                    loaders: filename_loaders(&content.name),
+                    filename: content.name.clone(),
+                    version: version.clone(),
+                },
+                DownloadData {
+                    download_url: content.download_url?.parse().ok()?,
+                    output: content.name.into(),
+                    length: content.size as usize,
+                    dependencies: Vec::new(),
+                    optional_dependencies: Vec::new(),
+                    conflicts: Vec::new(),
+                    file_id: None,
+                    sha1: None,
+                    sha512: None,
+                    published: None,
+                    version,
+                    loader: None,
+                    game_version: None,
+                    optional_files: Vec::new(),
+                },
+            ))
+        })
+        .collect_vec()
+}
+
+/// Guess the game versions a file is for from its `filename`
+fn filename_game_versions(filename: &str) -> Vec<String> {
+    filename
+        .trim_end_matches(".jar")
+        .split(['-', '_', '+'])
+        .map(|s| s.trim_start_matches("mc"))
+        .map(ToOwned::to_owned)
+        .collect_vec()
+}
+
+/// The classifiers at the end of the filenames of JARs that are built alongside a mod, but aren't the mod itself
+const SECONDARY_CLASSIFIERS: [&str; 4] = ["sources", "javadoc", "dev", "api"];
+
+/// Whether `filename` is a secondary artifact of a build, like `example-1.0-sources.jar`, rather than the mod itself
+///
+/// Only a classifier directly after the version is recognised,
+/// so that mods whose name ends like a classifier, e.g. `fabric-api.jar`, aren't mistaken for one.
+pub fn is_secondary_artifact(filename: &str) -> bool {
+    let Some((rest, classifier)) = filename
+        .strip_suffix(".jar")
+        .and_then(|stem| stem.rsplit_once('-'))
+    else {
+        return false;
+    };
+    let version = rest.rsplit_once('-').map_or(rest, |(_, version)| version);
+    version.contains(|c: char| c.is_ascii_digit())
+        && SECONDARY_CLASSIFIERS
+            .iter()
+            .any(|secondary| classifier.eq_ignore_ascii_case(secondary))
+}
+
+/// Guess the mod loaders a file is for from its `filename`
+fn filename_loaders(filename: &str) -> Vec<ModLoader> {
+    filename
+        .trim_end_matches(".jar")
+        .split(['-', '_', '+'])
+        .filter_map(|s| ModLoader::from_str(s).ok())
+        .collect_vec()
+}
+
 pub fn from_gh_asset(asset: GHAsset) -> DownloadData {
     DownloadData {
+        version: crate::misc::parse_version_from_filename(&asset.name),
         download_url: asset.browser_download_url,
         output: asset.name.into(),
         length: asset.size as usize,
         dependencies: Vec::new(),
+        optional_dependencies: Vec::new(),
         conflicts: Vec::new(),
+        file_id: Some(asset.id.to_string()),
+        sha1: None,
+        sha512: None,
+        published: Some(asset.created_at),
+        loader: None,
+        game_version: None,
+        optional_files: Vec::new(),
     }
 }
 
@@ -294,6 +588,103 @@ impl DownloadData {
         Ok((size, filename))
     }
 
+    /// Consumes `self` and downloads the file to the `output_dir` like [`DownloadData::download`],
+    /// while computing its hash as it's written
+    ///
+    /// The SHA-512 hash is used if the platform provides one, otherwise the SHA-1 hash is.
+    /// If the platform provided a hash and it doesn't match, the partially downloaded file is deleted
+    /// and [`Error::HashMismatch`] is returned.
+    ///
+    /// Returns the number of bytes written, the filename, and the computed hash.
+    pub async fn download_verified(
+        self,
+        client: Client,
+        output_dir: impl AsRef<Path>,
+        update: impl Fn(usize) + Send,
+    ) -> Result<(usize, String, String)> {
+        let filename = self.filename();
+        let out_file_path = output_dir.as_ref().join(&self.output);
+        let temp_file_path = out_file_path.with_extension("part");
+        if let Some(up_dir) = out_file_path.parent() {
+            create_dir_all(up_dir)?;
+        }
+
+        // The whole file has to be hashed, so don't resume previous partial downloads
+        let mut temp_file = BufWriter::with_capacity(
+            self.length,
+            OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&temp_file_path)?,
+        );
+
+        let mut response = client.get(self.download_url).send().await?;
+
+        let (expected, mut hasher): (_, Box<dyn DynDigest + Send>) = match self.sha512 {
+            Some(sha512) => (Some(sha512), Box::new(Sha512::new())),
+            None => (self.sha1, Box::new(Sha1::new())),
+        };
+        let mut written = 0;
+        while let Some(chunk) = response.chunk().await? {
+            temp_file.write_all(&chunk)?;
+            hasher.update(&chunk);
+            written += chunk.len();
+            update(chunk.len());
+        }
+        temp_file.flush()?;
+        drop(temp_file);
+
+        let actual = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        if let Some(expected) = expected {
+            if !expected.eq_ignore_ascii_case(&actual) {
+                remove_file(temp_file_path)?;
+                return Err(Error::HashMismatch { expected, actual });
+            }
+        }
+        rename(temp_file_path, out_file_path)?;
+        Ok((written, filename, actual))
+    }
+
+    /// Get the record of this file to add to a mod's history once it's installed
+    ///
+    /// Returns `None` if the file doesn't have a file ID to pin to.
+    pub fn installed_file(&self) -> Option<InstalledFile> {
+        Some(InstalledFile {
+            file_id: self.file_id.clone()?,
+            output: self.output.clone(),
+        })
+    }
+
+    /// Whether this file is newer than `other`
+    ///
+    /// The publish dates are compared if both files have them, since version numbers don't always sort correctly.
+    /// Otherwise the numbers in the version numbers are compared, and the files are considered the same age if neither works.
+    /// Version numbers are [parsed from the filenames](crate::misc::parse_version_from_filename) if the platform didn't provide them.
+    pub fn is_newer_than(&self, other: &DownloadData) -> bool {
+        let version = |file: &DownloadData| {
+            file.version
+                .clone()
+                .or_else(|| crate::misc::parse_version_from_filename(&file.filename()))
+        };
+        match (
+            self.published,
+            other.published,
+            &version(self),
+            &version(other),
+        ) {
+            (Some(published), Some(other_published), _, _) => published > other_published,
+            (_, _, Some(version), Some(other_version)) => {
+                version_numbers(version) > version_numbers(other_version)
+            }
+            _ => false,
+        }
+    }
+
     pub fn filename(&self) -> String {
         self.output
             .file_name()
@@ -302,3 +693,133 @@ impl DownloadData {
             .to_string()
     }
 }
+
+/// Get the numbers in `version`, so that `1.10.0` is greater than `1.9.2`
+fn version_numbers(version: &str) -> Vec<u64> {
+    version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|n| n.parse().ok())
+        .collect_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(filename: &str, version: Option<&str>, published: Option<&str>) -> DownloadData {
+        DownloadData {
+            download_url: format!("https://example.com/{filename}").parse().unwrap(),
+            output: filename.into(),
+            length: 0,
+            dependencies: Vec::new(),
+            optional_dependencies: Vec::new(),
+            conflicts: Vec::new(),
+            file_id: None,
+            sha1: None,
+            sha512: None,
+            published: published.map(|published| published.parse().unwrap()),
+            version: version.map(ToOwned::to_owned),
+            loader: None,
+            game_version: None,
+            optional_files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn modrinth_version_files() {
+        let file = |filename: &str, primary: bool| {
+            serde_json::json!({
+                "hashes": { "sha512": format!("{filename}-sha512"), "sha1": format!("{filename}-sha1") },
+                "url": format!("https://cdn.modrinth.com/data/AANobbMI/versions/OihdIimA/{filename}"),
+                "filename": filename,
+                "primary": primary,
+                "size": 1024,
+                "file_type": null,
+            })
+        };
+        let version: MRVersion = serde_json::from_value(serde_json::json!({
+            "name": "Sodium 0.5.3",
+            "version_number": "mc1.20.1-0.5.3",
+            "changelog": null,
+            "dependencies": [],
+            "game_versions": ["1.20.1"],
+            "version_type": "release",
+            "loaders": ["fabric", "quilt"],
+            "featured": true,
+            "status": "listed",
+            "requested_status": null,
+            "id": "OihdIimA",
+            "project_id": "AANobbMI",
+            "author_id": "DzLrfrbK",
+            "date_published": "2023-09-20T00:00:00Z",
+            "downloads": 0,
+            "files": [
+                file("sodium-fabric-mc1.20.1-0.5.3-sources.jar", false),
+                file("sodium-fabric-mc1.20.1-0.5.3.jar", true),
+                file("sodium-fabric-mc1.20.1-0.5.3-lang.zip", false),
+                file("sodium-fabric-mc1.20.1-0.5.3-dev.jar", false),
+            ],
+        }))
+        .unwrap();
+
+        let (metadata, download_data) = from_mr_version(version);
+        assert_eq!(metadata.filename, "sodium-fabric-mc1.20.1-0.5.3.jar");
+        assert_eq!(metadata.loaders, [ModLoader::Fabric, ModLoader::Quilt]);
+        assert_eq!(download_data.filename(), "sodium-fabric-mc1.20.1-0.5.3.jar");
+        assert_eq!(
+            download_data.sha1.as_deref(),
+            Some("sodium-fabric-mc1.20.1-0.5.3.jar-sha1")
+        );
+        assert_eq!(
+            download_data.sha512.as_deref(),
+            Some("sodium-fabric-mc1.20.1-0.5.3.jar-sha512")
+        );
+        assert_eq!(
+            download_data
+                .optional_files
+                .iter()
+                .map(DownloadData::filename)
+                .collect_vec(),
+            ["sodium-fabric-mc1.20.1-0.5.3-lang.zip"]
+        );
+    }
+
+    #[test]
+    fn secondary_artifacts() {
+        assert!(is_secondary_artifact("example-1.0-sources.jar"));
+        assert!(is_secondary_artifact("example-mc1.20.1-2.3.4-dev.jar"));
+        assert!(is_secondary_artifact("lib-0.5.0-api.jar"));
+        // Names that end like a classifier aren't secondary artifacts
+        assert!(!is_secondary_artifact("fabric-api.jar"));
+        assert!(!is_secondary_artifact("fabric-api-0.92.0+1.20.1.jar"));
+        assert!(!is_secondary_artifact("example-1.0-slim.jar"));
+        assert!(!is_secondary_artifact("example-1.0-sources.zip"));
+    }
+
+    #[test]
+    fn newer_by_publish_date() {
+        // A backport of an older release line was published after the newer release
+        let release = file("mod-2.0.0.jar", Some("2.0.0"), Some("2024-01-10T00:00:00Z"));
+        let backport = file("mod-1.9.1.jar", Some("1.9.1"), Some("2024-02-20T00:00:00Z"));
+        assert!(backport.is_newer_than(&release));
+        assert!(!release.is_newer_than(&backport));
+    }
+
+    #[test]
+    fn newer_by_version_number() {
+        // The version numbers are compared if either file doesn't have a publish date
+        let old = file("mod-1.9.2.jar", Some("1.9.2"), Some("2024-02-20T00:00:00Z"));
+        let new = file("mod-1.10.0.jar", Some("1.10.0"), None);
+        assert!(new.is_newer_than(&old));
+        assert!(!old.is_newer_than(&new));
+
+        // Or parsed from the filenames if the platform didn't provide them
+        let old = file("mod-fabric-mc1.20.1-0.5.3.jar", None, None);
+        let new = file("mod-fabric-mc1.20.1-0.5.11.jar", None, None);
+        assert!(new.is_newer_than(&old));
+
+        let unknown = file("mod.jar", None, None);
+        assert!(!unknown.is_newer_than(&old));
+        assert!(!old.is_newer_than(&unknown));
+    }
+}
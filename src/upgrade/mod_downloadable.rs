@@ -1,16 +1,37 @@
+#[cfg(feature = "curseforge")]
+use super::try_from_cf_file;
 use super::{
-    from_gh_asset, from_gh_releases, from_mr_version, try_from_cf_file, DistributionDeniedError,
-    DownloadData,
+    filter_assets, from_gh_asset, from_gh_contents, from_gh_releases, from_mr_version,
+    DistributionDeniedError, DownloadData, Metadata,
 };
+#[cfg(feature = "curseforge")]
+use crate::CURSEFORGE_API;
 use crate::{
     config::{
-        filters::Filter,
-        structs::{Mod, ModIdentifier},
+        filters::{FileSelection, Filter, FilterKind, ProfileParameters as _, ReleaseChannel},
+        structs::{
+            InstalledFile, Mod, ModIdentifier, OutputDirError, Profile, ProfileLocked, ProviderKind,
+        },
     },
     iter_ext::IterExt as _,
-    CURSEFORGE_API, GITHUB_API, MODRINTH_API,
+    metadata, GITHUB_API, MODRINTH_API,
+};
+use chrono::{DateTime, Utc};
+use ferinth::structures::{project::Project, version::Version};
+use futures_util::{
+    future::{join_all, try_join_all},
+    stream, StreamExt as _,
+};
+use reqwest::StatusCode;
+use sha1::{Digest, Sha1};
+use sha2::Sha512;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    fs::{read, read_dir, remove_file},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
 };
-use std::cmp::Reverse;
 
 #[derive(Debug, thiserror::Error)]
 #[error(transparent)]
@@ -21,70 +42,1438 @@ pub enum Error {
     InvalidPinID(#[from] std::num::ParseIntError),
     #[error("Modrinth: {0}")]
     ModrinthError(#[from] ferinth::Error),
+    #[cfg(feature = "curseforge")]
     #[error("CurseForge: {0}")]
     CurseForgeError(#[from] furse::Error),
+    #[error("CurseForge support is not enabled in this build")]
+    CurseForgeDisabled,
     #[error("GitHub: {0:#?}")]
-    GitHubError(#[from] octocrab::Error),
+    GitHubError(Box<octocrab::Error>),
+    ProfileLocked(#[from] ProfileLocked),
+    #[error(
+        "{0} is not pinned to a file using an immutable ID, so it can't be resolved reproducibly"
+    )]
+    NonReproducibleIdentifier(String),
+    #[error("None of the release assets match the asset filter {0}")]
+    NoMatchingAssets(String),
 }
 type Result<T> = std::result::Result<T, Error>;
 
+// Boxed since octocrab's error is much larger than the others
+impl From<octocrab::Error> for Error {
+    fn from(err: octocrab::Error) -> Self {
+        Self::GitHubError(Box::new(err))
+    }
+}
+
+impl From<metadata::Error> for Error {
+    fn from(err: metadata::Error) -> Self {
+        match err {
+            metadata::Error::ModrinthError(err) => Self::ModrinthError(err),
+            #[cfg(feature = "curseforge")]
+            metadata::Error::CurseForgeError(err) => Self::CurseForgeError(err),
+            metadata::Error::CurseForgeDisabled => Self::CurseForgeDisabled,
+            metadata::Error::GitHubError(err) => Self::GitHubError(err),
+        }
+    }
+}
+
+/// The maximum number of IDs to request from Modrinth at once, which keeps the URL well under its length limit
+const MODRINTH_BATCH_SIZE: usize = 250;
+
+impl Error {
+    /// Whether this error means that the file is unavailable from the mod's platform,
+    /// so it could be resolved from a fallback instead
+    pub fn is_unavailable(&self) -> bool {
+        match self {
+            Error::DistributionDenied(_) | Error::CurseForgeDisabled => true,
+            Error::CheckError(err) => err.is_incompatibility(),
+            _ => false,
+        }
+    }
+
+    /// Whether this error means that the project or file doesn't exist on its platform
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Error::ModrinthError(ferinth::Error::ReqwestError(err)) => {
+                err.status() == Some(StatusCode::NOT_FOUND)
+            }
+            #[cfg(feature = "curseforge")]
+            Error::CurseForgeError(furse::Error::ReqwestError(err)) => {
+                err.status() == Some(StatusCode::NOT_FOUND)
+            }
+            Error::GitHubError(err) => matches!(
+                &**err,
+                octocrab::Error::GitHub { source, .. } if source.status_code == StatusCode::NOT_FOUND
+            ),
+            _ => false,
+        }
+    }
+}
+
+/// Whether the file a mod is pinned to can still be downloaded, see [`verify_pins`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinStatus {
+    /// The file exists and can be downloaded
+    Valid,
+    /// The file or its project was deleted
+    Deleted,
+    /// The file exists but can't be downloaded, e.g. if distribution is denied on CurseForge
+    Unavailable,
+}
+
+/// The file resolved for a mod in a [`ResolutionPlan`]
+#[derive(Debug, Clone)]
+pub struct ResolvedMod {
+    /// The name of the mod in the profile
+    pub name: String,
+    /// The identifier the file was resolved from, which is the mod's fallback if its primary identifier was unavailable
+    pub source: ModIdentifier,
+    /// The platform the mod is tracked on, which is the platform of its primary identifier
+    pub tracked_by: ProviderKind,
+    /// The platform the file was downloaded from, which differs from `tracked_by` if the file was resolved from the mod's fallback
+    pub served_by: ProviderKind,
+    pub download_data: DownloadData,
+    /// The directory the file should be downloaded to, which depends on the mod's content type
+    pub destination: PathBuf,
+    /// Whether the file differs from the one already in the output directory, or isn't there at all
+    pub changed: bool,
+    /// Why the file was resolved, only recorded by [`resolve_all_traced`]
+    pub trace: Option<ResolutionTrace>,
+    /// The optional files of the version that aren't downloaded because the profile doesn't
+    /// [include optional files](Profile::include_optional_files), so that the user can be offered them
+    pub skipped_files: Vec<DownloadData>,
+}
+
+/// Why a file was resolved for a mod, see [`resolve_all_traced`]
+#[derive(Debug, Clone)]
+pub struct ResolutionTrace {
+    /// The files that were considered, in order of preference, which is newest first
+    ///
+    /// Pinned mods don't consider any files.
+    pub considered: Vec<ConsideredFile>,
+    pub reason: SelectionReason,
+}
+
+/// A file that was considered when resolving a mod, and the filters that rejected it
+#[derive(Debug, Clone)]
+pub struct ConsideredFile {
+    pub filename: String,
+    /// The version number of the Modrinth version, the display name of the CurseForge file,
+    /// or the tag of the GitHub release this file is from
+    pub version: Option<String>,
+    /// The filters that rejected this file, it passed all of them if this is empty
+    pub rejected_by: Vec<Filter>,
+    /// Whether a [`Filter::VersionConstraint`] was matched as a substring of this file's version number
+    /// instead of as a semver requirement, see [`Filter::is_substring_match`]
+    pub substring_matched: bool,
+}
+
+/// Why the resolved file was chosen out of the compatible ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionReason {
+    /// The mod is pinned to the file
+    Pinned,
+    /// The author recommends the file, see [`FileSelection`]
+    Recommended,
+    /// The file is the newest one that passed the filters
+    ///
+    /// Out of the files of the newest release, the one for the most preferred loader is chosen.
+    Newest,
+}
+
+/// What installing a profile would do, resolved without downloading or modifying anything
+#[derive(Debug)]
+pub struct ResolutionPlan {
+    pub mods: Vec<ResolvedMod>,
+    /// The mods that could not be resolved, with their errors
+    pub errors: Vec<(String, Error)>,
+    /// The required dependencies of the resolved files that aren't in the profile
+    ///
+    /// Dependencies shared by several mods are only listed once.
+    pub missing_dependencies: Vec<ModIdentifier>,
+}
+
+/// The result of resolving the upgrade of a mod using [`resolve_upgrades`]
+#[derive(Debug)]
+pub struct UpgradeResult {
+    /// The name of the mod in the profile
+    pub name: String,
+    /// The file currently installed for the mod, from its history
+    pub current: Option<InstalledFile>,
+    /// The file the mod would be upgraded to
+    pub target: Result<DownloadData>,
+    /// The platform the mod is tracked on, which is the platform of its primary identifier
+    pub tracked_by: ProviderKind,
+    /// The platform the target file was resolved from, which differs from `tracked_by` if it was resolved from the mod's fallback
+    ///
+    /// This is the same as `tracked_by` if the target could not be resolved.
+    pub served_by: ProviderKind,
+}
+
+impl UpgradeResult {
+    /// Whether the target file is different from the current one
+    ///
+    /// Returns false if the target could not be resolved.
+    pub fn is_upgrade(&self) -> bool {
+        self.target.as_ref().is_ok_and(|target| {
+            self.current.as_ref().map(|current| &current.file_id) != target.file_id.as_ref()
+        })
+    }
+}
+
 impl Mod {
+    /// Resolve the file to download for this mod using `profile_filters` and the mod's own filters
+    ///
+    /// The profile filters of the kinds in the mod's `ignored_filters` are skipped.
+    /// `file_selection` decides whether the author's recommended file is preferred over the newest compatible one.
+    /// If the file is unavailable and the mod has a fallback, the file is resolved from the fallback instead.
     pub async fn fetch_download_file(
         &self,
-        mut profile_filters: Vec<Filter>,
+        profile_filters: Vec<Filter>,
+        file_selection: FileSelection,
     ) -> Result<DownloadData> {
-        match &self.identifier {
-            ModIdentifier::PinnedCurseForgeProject(mod_id, pin) => {
-                Ok(try_from_cf_file(CURSEFORGE_API.get_mod_file(*mod_id, *pin).await?)?.1)
+        self.fetch_download_file_with_source(profile_filters, file_selection)
+            .await
+            .map(|(_, download_data)| download_data)
+    }
+
+    /// Resolve the file to download for this mod like [`Mod::fetch_download_file`],
+    /// along with the identifier that the file was resolved from
+    pub async fn fetch_download_file_with_source(
+        &self,
+        profile_filters: Vec<Filter>,
+        file_selection: FileSelection,
+    ) -> Result<(ModIdentifier, DownloadData)> {
+        self.fetch_download_file_cached(profile_filters, file_selection, &HashMap::new(), false)
+            .await
+            .map(|(source, download_data, _)| (source, download_data))
+    }
+
+    /// Resolve the file to download for this mod like [`Mod::fetch_download_file_with_source`],
+    /// along with a [`ResolutionTrace`] of why the file was chosen
+    ///
+    /// This runs each filter on all the files again, so it should only be used when the trace is needed.
+    pub async fn fetch_download_file_traced(
+        &self,
+        profile_filters: Vec<Filter>,
+        file_selection: FileSelection,
+    ) -> Result<(ModIdentifier, DownloadData, ResolutionTrace)> {
+        self.fetch_download_file_cached(profile_filters, file_selection, &HashMap::new(), true)
+            .await
+            .map(|(source, download_data, trace)| {
+                (
+                    source,
+                    download_data,
+                    trace.expect("The trace was requested"),
+                )
+            })
+    }
+
+    /// Get the game versions supported by any of this mod's files that pass `profile_filters`
+    /// and the mod's own filters, ignoring the game version filters
+    ///
+    /// Returns `None` if the mod is pinned, since its file never changes.
+    pub async fn supported_game_versions(
+        &self,
+        profile_filters: Vec<Filter>,
+    ) -> Result<Option<HashSet<String>>> {
+        if self.identifier.is_pinned() {
+            return Ok(None);
+        }
+        let filters = self
+            .combined_filters(profile_filters, self.identifier.provider())
+            .into_iter()
+            .filter_map(|filter| match filter {
+                Filter::GameVersionStrict(_)
+                | Filter::GameVersionMinor(_)
+                | Filter::GameVersionSet(_) => None,
+                // Preferring a loader would reject the files of the other loaders
+                Filter::ModLoaderPrefer(loaders) => Some(Filter::ModLoaderAny(loaders)),
+                filter => Some(filter),
+            })
+            .collect_vec();
+        let (download_files, _) = self
+            .list_files(
+                &self.identifier,
+                &filters,
+                FileSelection::Latest,
+                &HashMap::new(),
+            )
+            .await?;
+        let rejections =
+            super::check::rejections(download_files.iter().map(|(m, _)| m), &filters).await?;
+
+        Ok(Some(
+            download_files
+                .into_iter()
+                .zip(rejections)
+                .filter(|(_, rejected_by)| rejected_by.is_empty())
+                .flat_map(|((metadata, _), _)| metadata.game_versions)
+                .collect(),
+        ))
+    }
+
+    /// Resolve the file to download like [`Mod::fetch_download_file_with_source`],
+    /// using the versions in `modrinth_versions` instead of requesting them if they are there,
+    /// and recording a [`ResolutionTrace`] if `trace` is true
+    async fn fetch_download_file_cached(
+        &self,
+        profile_filters: Vec<Filter>,
+        file_selection: FileSelection,
+        modrinth_versions: &HashMap<String, Vec<Version>>,
+        trace: bool,
+    ) -> Result<(ModIdentifier, DownloadData, Option<ResolutionTrace>)> {
+        match self
+            .fetch_download_file_from(
+                &self.identifier,
+                profile_filters.clone(),
+                file_selection,
+                modrinth_versions,
+                trace,
+            )
+            .await
+        {
+            Err(err) if err.is_unavailable() => match &self.fallback {
+                Some(fallback) => {
+                    let (download_data, trace) = self
+                        .fetch_download_file_from(
+                            fallback,
+                            profile_filters,
+                            file_selection,
+                            modrinth_versions,
+                            trace,
+                        )
+                        .await?;
+                    Ok((fallback.clone(), download_data, trace))
+                }
+                None => Err(err),
+            },
+            result => {
+                result.map(|(download_data, trace)| (self.identifier.clone(), download_data, trace))
             }
-            ModIdentifier::PinnedModrinthProject(_, pin) => {
-                Ok(from_mr_version(MODRINTH_API.get_version(pin).await?).1)
+        }
+    }
+
+    /// Combine `profile_filters` with this mod's own filters, keeping the ones that apply to `provider`
+    fn combined_filters(
+        &self,
+        mut profile_filters: Vec<Filter>,
+        provider: ProviderKind,
+    ) -> Vec<Filter> {
+        let filters = if self.override_filters {
+            self.filters.clone()
+        } else {
+            profile_filters.retain(|filter| !self.ignored_filters.contains(&filter.kind()));
+            profile_filters.extend(self.filters.clone());
+            profile_filters
+        }
+        .into_iter()
+        .filter_map(|filter| filter.for_provider(provider))
+        .collect_vec();
+        self.content_type.applicable_filters(filters)
+    }
+
+    /// List the files of the unpinned `id`, along with the indices of the files the author recommends
+    #[cfg_attr(not(feature = "curseforge"), allow(unused_variables))]
+    async fn list_files(
+        &self,
+        id: &ModIdentifier,
+        filters: &[Filter],
+        file_selection: FileSelection,
+        modrinth_versions: &HashMap<String, Vec<Version>>,
+    ) -> Result<(Vec<(Metadata, DownloadData)>, Vec<usize>)> {
+        // Also get the indices of the files the author recommends, in order of preference
+        Ok(match id {
+            #[cfg(not(feature = "curseforge"))]
+            ModIdentifier::CurseForgeProject(_) => return Err(Error::CurseForgeDisabled),
+            #[cfg(feature = "curseforge")]
+            ModIdentifier::CurseForgeProject(id) => {
+                let mut files = CURSEFORGE_API.get_mod_files(*id).await?;
+                files.sort_unstable_by_key(|f| Reverse(f.file_date));
+                let recommended = if file_selection != FileSelection::Latest {
+                    let main_file_id = metadata::curseforge_project(*id).await?.main_file_id;
+                    files
+                        .iter()
+                        .position(|f| f.id == main_file_id)
+                        .into_iter()
+                        .collect_vec()
+                } else {
+                    Vec::new()
+                };
+                (
+                    files
+                        .into_iter()
+                        .map(|f| try_from_cf_file(f).map_err(Into::into))
+                        .collect::<Result<Vec<_>>>()?,
+                    recommended,
+                )
+            }
+            ModIdentifier::ModrinthProject(id) => {
+                let versions = match modrinth_versions.get(id) {
+                    Some(versions) => versions.clone(),
+                    None => MODRINTH_API.list_versions(id).await?,
+                };
+                let recommended = featured_versions(&versions, file_selection);
+                (
+                    versions.into_iter().map(from_mr_version).collect_vec(),
+                    recommended,
+                )
             }
-            ModIdentifier::PinnedGitHubRepository((owner, repo), pin) => Ok(from_gh_asset(
-                GITHUB_API
+            ModIdentifier::GitHubRepository(owner, repo) => {
+                let mut files = GITHUB_API
                     .repos(owner, repo)
-                    .release_assets()
-                    .get(*pin as u64)
-                    .await?,
-            )),
-            id => {
-                let download_files = match &id {
-                    ModIdentifier::CurseForgeProject(id) => {
-                        let mut files = CURSEFORGE_API.get_mod_files(*id).await?;
-                        files.sort_unstable_by_key(|f| Reverse(f.file_date));
-                        files
-                            .into_iter()
-                            .map(|f| try_from_cf_file(f).map_err(Into::into))
-                            .collect::<Result<Vec<_>>>()?
+                    .releases()
+                    .list()
+                    .send()
+                    .await
+                    .map(|r| from_gh_releases(r.items))?;
+                if let Some(asset_filter) = &self.asset_filter {
+                    files = filter_assets(files, asset_filter)
+                        .map_err(|err| Error::CheckError(err.into()))?;
+                    if files.is_empty() {
+                        return Err(Error::NoMatchingAssets(asset_filter.clone()));
                     }
-                    ModIdentifier::ModrinthProject(id) => MODRINTH_API
-                        .list_versions(id)
-                        .await?
-                        .into_iter()
-                        .map(from_mr_version)
-                        .collect_vec(),
-                    ModIdentifier::GitHubRepository(owner, repo) => GITHUB_API
+                }
+                if self.allow_prereleases {
+                    for (metadata, _) in &mut files {
+                        metadata.channel = ReleaseChannel::Release;
+                    }
+                }
+                match &self.github_contents_path {
+                    // Fall back to the repository's contents if none of the releases are compatible
+                    Some(path)
+                        if super::check::select_latest(
+                            files.iter().map(|(m, _)| m),
+                            filters.to_vec(),
+                        )
+                        .await
+                        .is_err() =>
+                    {
+                        (
+                            GITHUB_API
+                                .repos(owner, repo)
+                                .get_content()
+                                .path(path)
+                                .send()
+                                .await
+                                .map(|c| from_gh_contents(c.items))?,
+                            Vec::new(),
+                        )
+                    }
+                    _ => (files, Vec::new()),
+                }
+            }
+            _ => unreachable!(),
+        })
+    }
+
+    // Only CurseForge has recommended files
+    #[cfg_attr(not(feature = "curseforge"), allow(unused_variables))]
+    async fn fetch_download_file_from(
+        &self,
+        identifier: &ModIdentifier,
+        profile_filters: Vec<Filter>,
+        file_selection: FileSelection,
+        modrinth_versions: &HashMap<String, Vec<Version>>,
+        trace: bool,
+    ) -> Result<(DownloadData, Option<ResolutionTrace>)> {
+        let pinned_trace = trace.then_some(ResolutionTrace {
+            considered: Vec::new(),
+            reason: SelectionReason::Pinned,
+        });
+        match identifier {
+            #[cfg(not(feature = "curseforge"))]
+            ModIdentifier::CurseForgeProject(_) | ModIdentifier::PinnedCurseForgeProject(..) => {
+                Err(Error::CurseForgeDisabled)
+            }
+            #[cfg(feature = "curseforge")]
+            ModIdentifier::PinnedCurseForgeProject(mod_id, pin) => Ok((
+                try_from_cf_file(CURSEFORGE_API.get_mod_file(*mod_id, *pin).await?)?.1,
+                pinned_trace,
+            )),
+            ModIdentifier::PinnedModrinthProject(_, pin) => Ok((
+                from_mr_version(MODRINTH_API.get_version(pin).await?).1,
+                pinned_trace,
+            )),
+            ModIdentifier::PinnedGitHubRepository((owner, repo), pin) => Ok((
+                from_gh_asset(
+                    GITHUB_API
                         .repos(owner, repo)
-                        .releases()
-                        .list()
-                        .send()
+                        .release_assets()
+                        .get(*pin as u64)
+                        .await?,
+                ),
+                pinned_trace,
+            )),
+            id => {
+                let filters = self.combined_filters(profile_filters, id.provider());
+                let (download_files, recommended) = self
+                    .list_files(id, &filters, file_selection, modrinth_versions)
+                    .await?;
+
+                let (index, reason) = select_file(&download_files, &recommended, &filters).await?;
+                let trace = if trace {
+                    let rejections =
+                        super::check::rejections(download_files.iter().map(|(m, _)| m), &filters)
+                            .await?;
+                    Some(ResolutionTrace {
+                        considered: download_files
+                            .iter()
+                            .zip(rejections)
+                            .map(|((metadata, _), rejected_by)| ConsideredFile {
+                                filename: metadata.filename.clone(),
+                                version: metadata.version.clone(),
+                                rejected_by,
+                                substring_matched: filters
+                                    .iter()
+                                    .any(|filter| filter.is_substring_match(metadata)),
+                            })
+                            .collect_vec(),
+                        reason,
+                    })
+                } else {
+                    None
+                };
+                let (metadata, mut download_data) = download_files.into_iter().nth(index).unwrap();
+                // Record the most preferred loader that the file supports
+                download_data.loader = filters
+                    .mod_loaders()
+                    .into_iter()
+                    .flatten()
+                    .find(|loader| metadata.loaders.contains(loader))
+                    .or(metadata.loaders.first())
+                    .copied();
+                // Record the first of the profile's game versions that the file supports
+                download_data.game_version = filters
+                    .game_versions()
+                    .into_iter()
+                    .flatten()
+                    .find(|version| metadata.game_versions.contains(version))
+                    .cloned();
+                Ok((download_data, trace))
+            }
+        }
+    }
+}
+
+/// Get the indices of the Modrinth `versions` that the author has featured, if `file_selection` is [`FileSelection::Featured`]
+fn featured_versions(versions: &[Version], file_selection: FileSelection) -> Vec<usize> {
+    if file_selection == FileSelection::Featured {
+        versions
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.featured)
+            .map(|(i, _)| i)
+            .collect_vec()
+    } else {
+        Vec::new()
+    }
+}
+
+/// Select the first of the `recommended` files that passes the `filters`,
+/// otherwise fall back to the newest of `download_files` that does
+///
+/// Returns the index of the selected file in `download_files`, and why it was selected.
+async fn select_file(
+    download_files: &[(Metadata, DownloadData)],
+    recommended: &[usize],
+    filters: &[Filter],
+) -> super::check::Result<(usize, SelectionReason)> {
+    if !recommended.is_empty() {
+        if let Ok(i) = super::check::select_latest(
+            recommended.iter().map(|&i| &download_files[i].0),
+            filters.to_vec(),
+        )
+        .await
+        {
+            return Ok((recommended[i], SelectionReason::Recommended));
+        }
+    }
+    Ok((
+        super::check::select_latest(download_files.iter().map(|(m, _)| m), filters.to_vec())
+            .await?,
+        SelectionReason::Newest,
+    ))
+}
+
+/// Get the enabled mods in `profile` that don't have any file compatible with the `target` game version
+///
+/// The mods are resolved concurrently, with their game version filters replaced by `target`.
+/// Pinned mods are never considered broken.
+pub async fn breakage_on_version<'a>(profile: &'a Profile, target: &str) -> Result<Vec<&'a Mod>> {
+    let with_target = |mut filters: Vec<Filter>| {
+        if let Some(versions) = filters.game_versions_mut() {
+            *versions = vec![target.to_owned()];
+        }
+        filters
+    };
+    let mut profile_filters = with_target(profile.filters.clone());
+    if profile_filters.game_versions().is_none() {
+        profile_filters.push(Filter::GameVersionStrict(vec![target.to_owned()]));
+    }
+
+    let broken = try_join_all(profile.enabled_mods().map(|mod_| {
+        let profile_filters = profile_filters.clone();
+        let mut with_target_mod = mod_.clone();
+        with_target_mod.filters = with_target(with_target_mod.filters);
+        async move {
+            match with_target_mod
+                .fetch_download_file(profile_filters, FileSelection::Latest)
+                .await
+            {
+                Ok(_) => Ok(None),
+                Err(Error::CheckError(err)) if err.is_incompatibility() => Ok(Some(mod_)),
+                Err(err) => Err(err),
+            }
+        }
+    }))
+    .await?;
+
+    Ok(broken.into_iter().flatten().collect_vec())
+}
+
+/// Get the newest game version that every enabled, unpinned mod in `profile` has a compatible file for
+///
+/// This intersects the [game versions the mods support](Mod::supported_game_versions),
+/// and orders them using the [list of game versions](crate::game_version::list).
+/// Returns `None` if the mods don't all support any game version, or if every enabled mod is pinned.
+pub async fn max_common_game_version(profile: &Profile) -> Result<Option<String>> {
+    let supported = try_join_all(
+        profile
+            .enabled_mods()
+            .map(|mod_| mod_.supported_game_versions(profile.filters.clone())),
+    )
+    .await?;
+    let Some(common) = supported
+        .into_iter()
+        .flatten()
+        .reduce(|common, versions| &common & &versions)
+    else {
+        return Ok(None);
+    };
+
+    Ok(crate::game_version::list()
+        .await?
+        .iter()
+        .filter(|version| common.contains(&version.version))
+        .max_by_key(|version| version.date)
+        .map(|version| version.version.clone()))
+}
+
+/// Get the enabled, unpinned mods in `profile` whose newest files no longer support the profile's game versions,
+/// along with the newest file of each of them
+///
+/// A mod has moved on if the file resolved without any game version filters differs from the one
+/// resolved with the profile's filters, or if no file supports the profile's game versions at all.
+/// The mods are resolved concurrently. Nothing is returned if the profile doesn't filter by game version.
+pub async fn mods_moved_on(
+    profile: &Profile,
+    file_selection: FileSelection,
+) -> Result<Vec<(&Mod, DownloadData)>> {
+    if profile.filters.game_versions().is_none() {
+        return Ok(Vec::new());
+    }
+    let without_game_versions = |filters: &[Filter]| {
+        filters
+            .iter()
+            .filter(|filter| {
+                !matches!(
+                    filter,
+                    Filter::GameVersionStrict(_)
+                        | Filter::GameVersionMinor(_)
+                        | Filter::GameVersionSet(_)
+                )
+            })
+            .cloned()
+            .collect_vec()
+    };
+
+    let moved_on = try_join_all(
+        profile
+            .enabled_mods()
+            .filter(|mod_| !mod_.identifier.is_pinned())
+            .map(|mod_| {
+                let profile_filters = profile.filters.clone();
+                let mut newest_mod = mod_.clone();
+                newest_mod.filters = without_game_versions(&newest_mod.filters);
+                async move {
+                    let newest = newest_mod
+                        .fetch_download_file(
+                            without_game_versions(&profile_filters),
+                            file_selection,
+                        )
+                        .await?;
+                    match mod_
+                        .fetch_download_file(profile_filters, file_selection)
                         .await
-                        .map(|r| from_gh_releases(r.items))?,
-                    _ => unreachable!(),
+                    {
+                        Ok(current) if current.file_id == newest.file_id => Ok(None),
+                        Ok(_) => Ok(Some((mod_, newest))),
+                        Err(Error::CheckError(err)) if err.is_incompatibility() => {
+                            Ok(Some((mod_, newest)))
+                        }
+                        Err(err) => Err(err),
+                    }
+                }
+            }),
+    )
+    .await?;
+
+    Ok(moved_on.into_iter().flatten().collect_vec())
+}
+
+/// Check whether any of the mods in `profile` have moved on from the profile's game versions,
+/// which suggests that the profile should be updated to a newer game version
+///
+/// Use [`mods_moved_on`] to find which mods have moved on.
+pub async fn is_game_version_deprecated(
+    profile: &Profile,
+    file_selection: FileSelection,
+) -> Result<bool> {
+    Ok(!mods_moved_on(profile, file_selection).await?.is_empty())
+}
+
+/// Check whether the files that the enabled, pinned mods in `profile` are pinned to can still be downloaded
+///
+/// Authors can delete files, which breaks a pinned profile without any changes to it.
+/// The files are checked concurrently, and the [status](PinStatus) of each pinned mod is returned with its name.
+/// Mods whose file is unavailable but can be resolved from their fallback are valid.
+/// Errors other than the file not existing or being unavailable, such as network errors, are returned.
+pub async fn verify_pins(profile: &Profile) -> Result<Vec<(String, PinStatus)>> {
+    try_join_all(
+        profile
+            .enabled_mods()
+            .filter(|mod_| mod_.identifier.is_pinned())
+            .map(|mod_| async move {
+                let status = match mod_
+                    .fetch_download_file(Vec::new(), FileSelection::Latest)
+                    .await
+                {
+                    Ok(_) => PinStatus::Valid,
+                    Err(err) if err.is_not_found() => PinStatus::Deleted,
+                    Err(err) if err.is_unavailable() => PinStatus::Unavailable,
+                    Err(err) => return Err(err),
                 };
+                Ok((mod_.name.clone(), status))
+            }),
+    )
+    .await
+}
+
+/// Resolve the currently compatible file of every enabled, unpinned mod in `profile`, and pin the mods to them
+///
+/// This freezes the profile so that upgrading doesn't change any files.
+/// The mods are resolved concurrently, and the ones that failed to resolve are returned with their errors.
+/// Returns [`Error::ProfileLocked`] without resolving anything if the profile is locked.
+pub async fn pin_all(
+    profile: &mut Profile,
+    file_selection: FileSelection,
+) -> Result<Vec<(String, Error)>> {
+    profile.check_unlocked()?;
+    let results = join_all(
+        profile
+            .mods
+            .iter()
+            .filter(|mod_| mod_.enabled && !mod_.identifier.is_pinned())
+            .map(|mod_| {
+                let filters = profile.filters.clone();
+                async move {
+                    (
+                        mod_.name.clone(),
+                        mod_.fetch_download_file_with_source(filters, file_selection)
+                            .await,
+                    )
+                }
+            }),
+    )
+    .await;
+
+    let mut errors = Vec::new();
+    for (name, result) in results {
+        match result {
+            Ok((source, download_data)) => {
+                let mod_ = profile
+                    .mods
+                    .iter_mut()
+                    .find(|mod_| mod_.name == name)
+                    .expect("Mod was removed while resolving");
+                let Some(pinned) = download_data
+                    .file_id
+                    .and_then(|file_id| source.pinned_to(&file_id))
+                else {
+                    continue;
+                };
+                // Pin the fallback instead if the file was resolved from it
+                if source == mod_.identifier {
+                    mod_.identifier = pinned;
+                } else {
+                    mod_.fallback = Some(pinned);
+                }
+            }
+            Err(err) => errors.push((name, err)),
+        }
+    }
+    Ok(errors)
+}
+
+/// Get the game version and mod loader filters of `mod_` in `profile` that the file it is pinned to doesn't satisfy
+///
+/// Pinning overrides the filters, so this can be used to warn the user that the pinned file may not work.
+/// Returns an empty list if the mod isn't pinned, or is pinned to a GitHub release asset since their compatibility isn't known.
+pub async fn pinned_file_conflicts(profile: &Profile, mod_: &Mod) -> Result<Vec<Filter>> {
+    let metadata = match &mod_.identifier {
+        #[cfg(feature = "curseforge")]
+        ModIdentifier::PinnedCurseForgeProject(mod_id, pin) => {
+            try_from_cf_file(CURSEFORGE_API.get_mod_file(*mod_id, *pin).await?)?.0
+        }
+        ModIdentifier::PinnedModrinthProject(_, pin) => {
+            from_mr_version(MODRINTH_API.get_version(pin).await?).0
+        }
+        _ => return Ok(Vec::new()),
+    };
+    let filters = mod_
+        .combined_filters(profile.filters.clone(), mod_.identifier.provider())
+        .into_iter()
+        .filter(|filter| {
+            matches!(
+                filter.kind(),
+                FilterKind::GameVersion | FilterKind::ModLoader
+            )
+        })
+        .collect_vec();
+    Ok(
+        super::check::rejections(std::iter::once(&metadata), &filters)
+            .await?
+            .into_iter()
+            .flatten()
+            .collect(),
+    )
+}
+
+/// Get the current owner and name of the GitHub repository `owner/repo` if it was renamed or transferred
+///
+/// GitHub redirects requests for the old location of a repository, which are followed to find its new location.
+/// Returns `None` if the repository hasn't moved.
+pub async fn moved_github_repo(owner: &str, repo: &str) -> Result<Option<(String, String)>> {
+    let repository = metadata::github_repository(owner, repo).await?;
+    let new_owner = repository
+        .owner
+        .map_or_else(|| owner.to_owned(), |author| author.login);
+    // Repository names are case insensitive
+    Ok(
+        (!new_owner.eq_ignore_ascii_case(owner) || !repository.name.eq_ignore_ascii_case(repo))
+            .then_some((new_owner, repository.name)),
+    )
+}
+
+/// Update the identifiers and fallbacks of the mods in `profile` whose GitHub repositories were renamed or transferred
+///
+/// Resolving files from the old locations works as long as GitHub keeps redirecting them,
+/// but the redirects break if another repository takes the old name.
+/// The repositories are checked concurrently. Returns the names of the mods that were updated,
+/// and the repositories that failed to be checked with their errors.
+pub async fn follow_github_redirects(profile: &mut Profile) -> (Vec<String>, Vec<(String, Error)>) {
+    let repos = profile
+        .mods
+        .iter()
+        .flat_map(|mod_| [Some(&mod_.identifier), mod_.fallback.as_ref()])
+        .flatten()
+        .filter_map(ModIdentifier::github_repo)
+        .map(|(owner, repo)| (owner.to_owned(), repo.to_owned()))
+        .collect_hashset();
+    let results = join_all(repos.into_iter().map(|(owner, repo)| async move {
+        let result = moved_github_repo(&owner, &repo).await;
+        ((owner, repo), result)
+    }))
+    .await;
+
+    let mut moved = HashMap::new();
+    let mut errors = Vec::new();
+    for (repo, result) in results {
+        match result {
+            Ok(Some(new_repo)) => {
+                moved.insert(repo, new_repo);
+            }
+            Ok(None) => {}
+            Err(err) => errors.push((format!("{}/{}", repo.0, repo.1), err)),
+        }
+    }
+
+    let mut updated = Vec::new();
+    for mod_ in &mut profile.mods {
+        let mut changed = false;
+        for id in [Some(&mut mod_.identifier), mod_.fallback.as_mut()]
+            .into_iter()
+            .flatten()
+        {
+            let Some((owner, repo)) = id
+                .github_repo()
+                .and_then(|(owner, repo)| moved.get(&(owner.to_owned(), repo.to_owned())))
+            else {
+                continue;
+            };
+            // The slug of a GitHub mod is its repository's name
+            if mod_.slug.as_ref().is_some_and(|slug| {
+                id.github_repo()
+                    .is_some_and(|(_, old)| slug.eq_ignore_ascii_case(old))
+            }) {
+                mod_.slug = Some(repo.to_lowercase());
+            }
+            *id = id.with_github_repo(owner.clone(), repo.clone());
+            changed = true;
+        }
+        if changed {
+            updated.push(mod_.name.clone());
+        }
+    }
+    (updated, errors)
+}
+
+/// Resolve the file of every enabled mod in `profile` into a [`ResolutionPlan`]
+///
+/// The mods are resolved concurrently, and nothing is downloaded or written.
+/// The download URLs are rewritten to the profile's download mirror if it has one.
+/// The resolved files are compared with the ones in the profile's output directory to determine if they changed.
+/// The versions of Modrinth mods are requested together using [`modrinth_versions_batched`].
+pub async fn resolve_all(profile: &Profile, file_selection: FileSelection) -> ResolutionPlan {
+    resolve_all_with_trace(profile, file_selection, false).await
+}
+
+/// Resolve the file of every enabled mod in `profile` like [`resolve_all`],
+/// but return [`Error::NonReproducibleIdentifier`] without resolving anything if any of them isn't [reproducible](Mod::is_reproducible)
+///
+/// This ensures that the profile installs exactly the same files every time, e.g. when enforced in CI.
+/// Profiles can be made reproducible using [`pin_all`], and profiles added from a lockfile using
+/// [`add_many_pinned`](crate::add::add_many_pinned) are reproducible if the lockfile uses project IDs.
+pub async fn resolve_all_reproducible(
+    profile: &Profile,
+    file_selection: FileSelection,
+) -> Result<ResolutionPlan> {
+    if let Some(mod_) = profile.enabled_mods().find(|mod_| !mod_.is_reproducible()) {
+        return Err(Error::NonReproducibleIdentifier(mod_.name.clone()));
+    }
+    Ok(resolve_all(profile, file_selection).await)
+}
+
+/// Resolve the file of every enabled mod in `profile` like [`resolve_all`],
+/// and record a [`ResolutionTrace`] of why each file was chosen
+///
+/// This runs each filter on all the files again, so it should only be used when the traces are needed.
+pub async fn resolve_all_traced(
+    profile: &Profile,
+    file_selection: FileSelection,
+) -> ResolutionPlan {
+    resolve_all_with_trace(profile, file_selection, true).await
+}
+
+async fn resolve_all_with_trace(
+    profile: &Profile,
+    file_selection: FileSelection,
+    trace: bool,
+) -> ResolutionPlan {
+    let modrinth_versions = prefetch_modrinth_versions(profile).await;
+    let modrinth_versions = &modrinth_versions;
+    let results = join_all(profile.enabled_mods().map(|mod_| {
+        let filters = profile.filters.clone();
+        async move {
+            (
+                mod_.name.clone(),
+                mod_.identifier.provider(),
+                profile.output_dir_for(mod_.content_type),
+                mod_.fetch_download_file_cached(filters, file_selection, modrinth_versions, trace)
+                    .await,
+            )
+        }
+    }))
+    .await;
+
+    let mut plan = ResolutionPlan {
+        mods: Vec::new(),
+        errors: Vec::new(),
+        missing_dependencies: Vec::new(),
+    };
+    for (name, tracked_by, destination, result) in results {
+        match result {
+            Ok((source, mut download_data, trace)) => {
+                for dependency in &download_data.dependencies {
+                    let dependency = dependency.unpinned();
+                    if !plan.missing_dependencies.contains(&dependency)
+                        && !profile
+                            .mods
+                            .iter()
+                            .any(|mod_| mod_.identifier.unpinned() == dependency)
+                    {
+                        plan.missing_dependencies.push(dependency);
+                    }
+                }
+                download_data.download_url =
+                    profile.rewrite_download_url(download_data.download_url);
+                for optional in &mut download_data.optional_files {
+                    optional.download_url =
+                        profile.rewrite_download_url(optional.download_url.clone());
+                }
+                let skipped_files = if profile.include_optional_files {
+                    Vec::new()
+                } else {
+                    std::mem::take(&mut download_data.optional_files)
+                };
+                plan.mods.push(ResolvedMod {
+                    skipped_files,
+                    changed: is_changed(&destination, &download_data),
+                    name,
+                    tracked_by,
+                    served_by: source.provider(),
+                    source,
+                    download_data,
+                    destination,
+                    trace,
+                });
+            }
+            Err(err) => plan.errors.push((name, err)),
+        }
+    }
+    plan
+}
 
-                let index = super::check::select_latest(
-                    download_files.iter().map(|(m, _)| m),
-                    if self.override_filters {
-                        self.filters.clone()
-                    } else {
-                        profile_filters.extend(self.filters.clone());
-                        profile_filters
+/// Resolve the upgrades of every enabled mod in `profile`, with at most `max_concurrency` mods from each platform being resolved at once
+///
+/// If `since` is provided, only the mods whose target file was published after it are returned,
+/// along with the mods that failed to resolve and those whose platform doesn't provide publish dates.
+/// The profile is not modified, and the results are in the same order as the mods.
+/// The download URLs are rewritten to the profile's download mirror if it has one.
+/// The versions of Modrinth mods are requested together using [`modrinth_versions_batched`].
+pub async fn resolve_upgrades(
+    profile: &Profile,
+    file_selection: FileSelection,
+    max_concurrency: usize,
+    since: Option<DateTime<Utc>>,
+) -> Vec<UpgradeResult> {
+    resolve_upgrades_with_limits(
+        profile,
+        file_selection,
+        ConcurrencyLimits {
+            modrinth: max_concurrency,
+            curseforge: max_concurrency,
+            github: max_concurrency,
+        },
+        since,
+    )
+    .await
+}
+
+/// How many mods from each platform are resolved at once by [`resolve_upgrades_with_limits`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConcurrencyLimits {
+    pub modrinth: usize,
+    pub curseforge: usize,
+    /// GitHub only allows 60 requests an hour without a token, so this is lower by default
+    pub github: usize,
+}
+
+impl Default for ConcurrencyLimits {
+    fn default() -> Self {
+        Self {
+            modrinth: 16,
+            curseforge: 16,
+            github: 4,
+        }
+    }
+}
+
+impl ConcurrencyLimits {
+    /// Get the limit for mods from `provider`, which is at least 1
+    pub fn for_provider(&self, provider: ProviderKind) -> usize {
+        match provider {
+            ProviderKind::Modrinth => self.modrinth,
+            ProviderKind::CurseForge => self.curseforge,
+            ProviderKind::GitHub => self.github,
+            // Mods can't be from these platforms
+            ProviderKind::GitLab | ProviderKind::Url => 1,
+        }
+        .max(1)
+    }
+}
+
+/// Resolve the upgrades of every enabled mod in `profile` like [`resolve_upgrades`],
+/// with separate limits on how many mods from each platform are resolved at once
+///
+/// The mods of each platform are resolved concurrently with each other,
+/// so a low limit for GitHub doesn't slow down resolving the Modrinth and CurseForge mods.
+/// Mods are limited by the platform of their primary identifier, even if they're resolved from a fallback.
+pub async fn resolve_upgrades_with_limits(
+    profile: &Profile,
+    file_selection: FileSelection,
+    limits: ConcurrencyLimits,
+    since: Option<DateTime<Utc>>,
+) -> Vec<UpgradeResult> {
+    let modrinth_versions = prefetch_modrinth_versions(profile).await;
+    let modrinth_versions = &modrinth_versions;
+
+    let by_provider = [
+        ProviderKind::Modrinth,
+        ProviderKind::CurseForge,
+        ProviderKind::GitHub,
+    ]
+    .map(|provider| {
+        stream::iter(
+            profile
+                .enabled_mods()
+                .enumerate()
+                .filter(move |(_, mod_)| mod_.identifier.provider() == provider),
+        )
+        .map(|(i, mod_)| {
+            let filters = profile.filters.clone();
+            async move {
+                let tracked_by = mod_.identifier.provider();
+                let mut served_by = tracked_by;
+                let target = mod_
+                    .fetch_download_file_cached(filters, file_selection, modrinth_versions, false)
+                    .await
+                    .map(|(source, mut download_data, _)| {
+                        served_by = source.provider();
+                        download_data.download_url =
+                            profile.rewrite_download_url(download_data.download_url);
+                        for optional in &mut download_data.optional_files {
+                            optional.download_url =
+                                profile.rewrite_download_url(optional.download_url.clone());
+                        }
+                        download_data
+                    });
+                (
+                    i,
+                    UpgradeResult {
+                        name: mod_.name.clone(),
+                        current: mod_.history.last().cloned(),
+                        target,
+                        tracked_by,
+                        served_by,
                     },
                 )
-                .await?;
-                Ok(download_files.into_iter().nth(index).unwrap().1)
             }
+        })
+        .buffered(limits.for_provider(provider))
+        .collect::<Vec<_>>()
+    });
+
+    let mut results = join_all(by_provider)
+        .await
+        .into_iter()
+        .flatten()
+        .collect_vec();
+    results.sort_unstable_by_key(|(i, _)| *i);
+    results
+        .into_iter()
+        .map(|(_, result)| result)
+        .filter(|result| {
+            let published = result
+                .target
+                .as_ref()
+                .ok()
+                .and_then(|target| target.published);
+            match (since, published) {
+                (Some(since), Some(published)) => published > since,
+                _ => true,
+            }
+        })
+        .collect_vec()
+}
+
+/// Get the versions of the Modrinth projects of the enabled mods in `profile` and their fallbacks using [`modrinth_versions_batched`]
+///
+/// If the batch requests fail, nothing is returned and the versions are requested for each mod instead.
+async fn prefetch_modrinth_versions(profile: &Profile) -> HashMap<String, Vec<Version>> {
+    let modrinth_ids = profile
+        .enabled_mods()
+        .flat_map(|mod_| [Some(&mod_.identifier), mod_.fallback.as_ref()])
+        .flatten()
+        .filter_map(|id| match id {
+            ModIdentifier::ModrinthProject(id) => Some(id.as_str()),
+            _ => None,
+        })
+        .collect_hashset()
+        .into_iter()
+        .collect_vec();
+    modrinth_versions_batched(&modrinth_ids)
+        .await
+        .unwrap_or_default()
+}
+
+/// Get the versions of each of the Modrinth projects of `project_ids`, newest first, keyed by project ID
+///
+/// Instead of one request for every project, the projects and then their versions are requested in batches of
+/// [`MODRINTH_BATCH_SIZE`], so this saves the most requests for profiles with many projects that have few versions.
+pub async fn modrinth_versions_batched(
+    project_ids: &[&str],
+) -> Result<HashMap<String, Vec<Version>>> {
+    let projects = try_join_all(
+        project_ids
+            .chunks(MODRINTH_BATCH_SIZE)
+            .map(|chunk| MODRINTH_API.get_multiple_projects(chunk)),
+    )
+    .await?
+    .into_iter()
+    .flatten()
+    .collect_vec();
+    metadata::cache_modrinth_projects(&projects);
+    Ok(modrinth_versions_of(&projects).await?)
+}
+
+/// Get the versions of each of the Modrinth `projects`, newest first, keyed by project ID,
+/// requesting them in batches of [`MODRINTH_BATCH_SIZE`]
+pub(crate) async fn modrinth_versions_of(
+    projects: &[Project],
+) -> ferinth::Result<HashMap<String, Vec<Version>>> {
+    let version_ids = projects
+        .iter()
+        .flat_map(|project| &project.versions)
+        .map(AsRef::as_ref)
+        .collect_vec();
+    let mut versions = HashMap::<_, _>::from_iter(
+        projects
+            .iter()
+            .map(|project| (project.id.clone(), Vec::new())),
+    );
+    for version in try_join_all(
+        version_ids
+            .chunks(MODRINTH_BATCH_SIZE)
+            .map(|chunk| MODRINTH_API.get_multiple_versions(chunk)),
+    )
+    .await?
+    .into_iter()
+    .flatten()
+    {
+        versions
+            .entry(version.project_id.clone())
+            .or_insert_with(Vec::new)
+            .push(version);
+    }
+    for versions in versions.values_mut() {
+        versions.sort_unstable_by_key(|version| Reverse(version.date_published));
+    }
+    Ok(versions)
+}
+
+/// The outcome of downloading a [`ResolutionPlan`] with [`download_plan`]
+#[derive(Debug, Default)]
+pub struct DownloadReport {
+    /// The filenames of the files that were downloaded
+    pub downloaded: Vec<String>,
+    /// The filenames of the files that were already in their destination with the same hash, so weren't downloaded again
+    pub unchanged: Vec<String>,
+    /// The stale files that were removed since they're no longer in the plan
+    pub removed: Vec<PathBuf>,
+    /// The files that could not be downloaded, with their errors
+    pub errors: Vec<(String, super::Error)>,
+    /// Whether the downloads were cancelled before all of them were started
+    pub cancelled: bool,
+}
+
+/// Download the files in `plan` that have changed, and remove the stale JAR files that are no longer in it
+///
+/// Up to `max_concurrency` files are downloaded at once, and their hashes are verified as they're written,
+/// see [`DownloadData::download_verified`]. The optional files of the plan's mods are downloaded too.
+/// `progress` is called with the filename and the length of each chunk as it's downloaded.
+///
+/// Setting `cancel` stops new downloads from being started, while the ones in progress are finished.
+/// Nothing is removed if the downloads were cancelled or any of them failed, so that the profile isn't left without a file.
+/// Stale files are the JAR files directly in the plan's destination directories that aren't in the plan or any mod's
+/// history, so the [manually managed mods](Profile::user_mods_path) are never removed.
+/// Returns an error if [the output directory can't be written to](Profile::check_output_writable),
+/// in which case nothing is downloaded, or if a stale file could not be removed.
+pub async fn download_plan(
+    profile: &Profile,
+    plan: &ResolutionPlan,
+    client: reqwest::Client,
+    max_concurrency: usize,
+    progress: impl Fn(&str, usize) + Sync,
+    cancel: &AtomicBool,
+) -> std::io::Result<DownloadReport> {
+    if let Err(err) = profile.check_output_writable() {
+        return Err(match err {
+            OutputDirError::IOError(err) => err,
+            OutputDirError::PermissionDenied(_) => {
+                std::io::Error::new(std::io::ErrorKind::PermissionDenied, err)
+            }
+            err => std::io::Error::other(err),
+        });
+    }
+
+    let files = plan
+        .mods
+        .iter()
+        .flat_map(|resolved| {
+            std::iter::once(&resolved.download_data)
+                .chain(&resolved.download_data.optional_files)
+                .map(|download_data| (&resolved.destination, download_data))
+        })
+        .collect_vec();
+
+    let mut report = DownloadReport::default();
+    let progress = &progress;
+    let results = stream::iter(&files)
+        .map(|&(destination, download_data)| {
+            let client = client.clone();
+            async move {
+                let filename = download_data.filename();
+                if cancel.load(Ordering::Relaxed) {
+                    return (filename, None);
+                }
+                if !is_changed(destination, download_data) {
+                    return (filename, Some(Ok(false)));
+                }
+                let result = download_data
+                    .clone()
+                    .download_verified(client, destination, |chunk| progress(&filename, chunk))
+                    .await
+                    .map(|_| true);
+                (filename, Some(result))
+            }
+        })
+        .buffer_unordered(max_concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
+    for (filename, result) in results {
+        match result {
+            Some(Ok(true)) => report.downloaded.push(filename),
+            Some(Ok(false)) => report.unchanged.push(filename),
+            Some(Err(err)) => report.errors.push((filename, err)),
+            None => report.cancelled = true,
+        }
+    }
+    if report.cancelled || !report.errors.is_empty() {
+        return Ok(report);
+    }
+
+    let keep = files
+        .iter()
+        .map(|(destination, download_data)| destination.join(&download_data.output))
+        .chain(profile.mods.iter().flat_map(|mod_| {
+            let destination = profile.output_dir_for(mod_.content_type);
+            mod_.history
+                .iter()
+                .map(move |file| destination.join(&file.output))
+        }))
+        .collect::<HashSet<_>>();
+    let destinations = files
+        .iter()
+        .map(|(destination, _)| *destination)
+        .collect::<HashSet<_>>();
+    for destination in destinations {
+        for entry in read_dir(destination)? {
+            let path = entry?.path();
+            if path.is_file()
+                && path.extension().is_some_and(|extension| extension == "jar")
+                && !keep.contains(&path)
+                && !profile.is_user_mod(&path)
+            {
+                remove_file(&path)?;
+                report.removed.push(path);
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Check whether `download_data` differs from the file already in the `destination` directory
+///
+/// The hash is compared if the platform provides one, preferring SHA-512 like [`DownloadData::download_verified`],
+/// otherwise only the length is.
+fn is_changed(destination: &Path, download_data: &DownloadData) -> bool {
+    let Ok(existing) = read(destination.join(&download_data.output)) else {
+        return true;
+    };
+    match (&download_data.sha512, &download_data.sha1) {
+        (Some(sha512), _) => {
+            !sha512.eq_ignore_ascii_case(&format!("{:x}", Sha512::digest(&existing)))
         }
+        (None, Some(sha1)) => !sha1.eq_ignore_ascii_case(&format!("{:x}", Sha1::digest(&existing))),
+        (None, None) => existing.len() != download_data.length,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::structs::ModLoader;
+
+    /// A Fabric 1.20.1 version published on `published`
+    fn version(id: &str, published: &str, featured: bool) -> Version {
+        serde_json::from_value(serde_json::json!({
+            "name": id,
+            "version_number": id,
+            "changelog": null,
+            "dependencies": [],
+            "game_versions": ["1.20.1"],
+            "version_type": "release",
+            "loaders": ["fabric"],
+            "featured": featured,
+            "status": "listed",
+            "requested_status": null,
+            "id": id,
+            "project_id": "project",
+            "author_id": "author",
+            "date_published": published,
+            "downloads": 0,
+            "files": [{
+                "hashes": { "sha512": "", "sha1": "" },
+                "url": format!("https://cdn.modrinth.com/data/project/versions/{id}/{id}.jar"),
+                "filename": format!("{id}.jar"),
+                "primary": true,
+                "size": 1024,
+                "file_type": null,
+            }],
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn featured_over_newer() {
+        let filters = [
+            Filter::ModLoaderPrefer(vec![ModLoader::Fabric]),
+            Filter::GameVersionStrict(vec!["1.20.1".to_owned()]),
+        ];
+        let mut versions = vec![
+            version("newer", "2024-06-01T00:00:00Z", false),
+            version("featured", "2023-06-01T00:00:00Z", true),
+        ];
+        let files = versions.iter().cloned().map(from_mr_version).collect_vec();
+
+        let featured = featured_versions(&versions, FileSelection::Featured);
+        assert_eq!(featured, [1]);
+        assert_eq!(
+            select_file(&files, &featured, &filters).await.unwrap(),
+            (1, SelectionReason::Recommended)
+        );
+        // Featured versions are only preferred if they're selected
+        for file_selection in [FileSelection::Latest, FileSelection::Recommended] {
+            let featured = featured_versions(&versions, file_selection);
+            assert_eq!(
+                select_file(&files, &featured, &filters).await.unwrap(),
+                (0, SelectionReason::Newest)
+            );
+        }
+
+        // The newest version is used if the featured one isn't compatible
+        versions[1].game_versions = vec!["1.19.2".to_owned()];
+        let files = versions.iter().cloned().map(from_mr_version).collect_vec();
+        let featured = featured_versions(&versions, FileSelection::Featured);
+        assert_eq!(
+            select_file(&files, &featured, &filters).await.unwrap(),
+            (0, SelectionReason::Newest)
+        );
+    }
+
+    #[cfg(not(feature = "curseforge"))]
+    #[tokio::test]
+    async fn curseforge_game_versions_without_feature() {
+        let mod_ = Mod::new(
+            "Sodium".to_owned(),
+            ModIdentifier::CurseForgeProject(394468),
+            Vec::new(),
+            false,
+        );
+        let result = mod_.supported_game_versions(Vec::new()).await;
+        assert!(matches!(result, Err(Error::CurseForgeDisabled)));
     }
 }
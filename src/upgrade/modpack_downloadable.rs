@@ -1,6 +1,9 @@
-use super::{from_mr_version, try_from_cf_file, DistributionDeniedError};
-use crate::{config::structs::ModpackIdentifier, CURSEFORGE_API, HOME, MODRINTH_API};
-use reqwest::Client;
+#[cfg(feature = "curseforge")]
+use super::try_from_cf_file;
+use super::{from_mr_version, DistributionDeniedError};
+#[cfg(feature = "curseforge")]
+use crate::CURSEFORGE_API;
+use crate::{config::structs::ModpackIdentifier, HOME, HTTP_CLIENT, MODRINTH_API};
 use std::{fs::create_dir_all, path::PathBuf};
 
 #[derive(Debug, thiserror::Error)]
@@ -10,7 +13,10 @@ pub enum Error {
     /// However, they will have to manually update the modpack file.
     DistributionDenied(#[from] DistributionDeniedError),
     ModrinthError(#[from] ferinth::Error),
+    #[cfg(feature = "curseforge")]
     CurseForgeError(#[from] furse::Error),
+    #[error("CurseForge support is not enabled in this build")]
+    CurseForgeDisabled,
     ReqwestError(#[from] reqwest::Error),
     DownloadError(#[from] super::Error),
     IOError(#[from] std::io::Error),
@@ -24,6 +30,9 @@ impl ModpackIdentifier {
         update: impl Fn(usize) + Send,
     ) -> Result<PathBuf> {
         let (_, download_data) = match self {
+            #[cfg(not(feature = "curseforge"))]
+            ModpackIdentifier::CurseForgeModpack(_) => return Err(Error::CurseForgeDisabled),
+            #[cfg(feature = "curseforge")]
             ModpackIdentifier::CurseForgeModpack(id) => {
                 try_from_cf_file(CURSEFORGE_API.get_mod_files(*id).await?.swap_remove(0))?
             }
@@ -38,7 +47,7 @@ impl ModpackIdentifier {
             create_dir_all(&cache_dir)?;
             total(download_data.length);
             download_data
-                .download(Client::new(), &cache_dir, update)
+                .download(HTTP_CLIENT.clone(), &cache_dir, update)
                 .await?;
         }
 
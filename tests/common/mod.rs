@@ -0,0 +1,256 @@
+//! Fixtures shared by the integration tests, and a mock of GitHub's API for adding GitHub repositories without network access
+
+// Each test crate only uses some of these
+#![allow(dead_code)]
+
+use libium::config::structs::{ModLoader, Profile};
+use regex::Regex;
+use serde_json::{json, Value};
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+const RATE_LIMITED: &str =
+    r#"{"message":"API rate limit exceeded","documentation_url":"https://docs.github.com/rest"}"#;
+const NOT_FOUND: &str =
+    r#"{"message":"Not Found","documentation_url":"https://docs.github.com/rest"}"#;
+
+/// Respond to a GraphQL `query` for repositories with a release of a Fabric mod for 1.20.1 in each of them
+fn repositories(query: &str) -> String {
+    let repository =
+        Regex::new(r#"_(\d+): repository\(owner: "([^"]*)", name: "([^"]*)"\)"#).unwrap();
+    let data = repository
+        .captures_iter(query)
+        .map(|captures| {
+            let (_, [i, owner, name]) = captures.extract();
+            (
+                format!("_{i}"),
+                json!({
+                    "owner": { "login": owner },
+                    "name": name,
+                    "isArchived": false,
+                    "licenseInfo": null,
+                    "releases": { "nodes": [{
+                        "name": "v1.0",
+                        "description": "",
+                        "isPrerelease": false,
+                        "releaseAssets": { "nodes": [{ "name": format!("{name}-fabric-1.20.1-1.0.jar") }] },
+                    }] },
+                }),
+            )
+        })
+        .collect::<serde_json::Map<_, _>>();
+    json!({ "data": data }).to_string()
+}
+
+/// A Fabric 1.20.1 profile called `name` that outputs to `output_dir`
+pub fn profile(name: &str, output_dir: impl Into<std::path::PathBuf>) -> Profile {
+    Profile::new(
+        name.to_owned(),
+        output_dir.into(),
+        vec!["1.20.1".to_owned()],
+        ModLoader::Fabric,
+    )
+}
+
+/// A mock of GitHub's API, and the requests it received
+pub struct MockGitHub {
+    pub url: String,
+    /// The number of GraphQL queries received
+    pub queries: Arc<AtomicUsize>,
+    /// The values of the `User-Agent` headers of the requests received
+    pub user_agents: Arc<Mutex<Vec<String>>>,
+    /// The most requests that were being responded to at once
+    pub max_in_flight: Arc<AtomicUsize>,
+}
+
+/// A repository called `owner/name` in the format of GitHub's REST API
+fn repository(id: usize, owner: &str, name: &str) -> String {
+    let user_url = format!("https://api.github.com/users/{owner}");
+    json!({
+        "id": id,
+        "name": name,
+        "full_name": format!("{owner}/{name}"),
+        "url": format!("https://api.github.com/repos/{owner}/{name}"),
+        "owner": {
+            "login": owner,
+            "id": 1,
+            "node_id": "",
+            "avatar_url": "https://avatars.githubusercontent.com/u/1",
+            "gravatar_id": "",
+            "url": user_url,
+            "html_url": format!("https://github.com/{owner}"),
+            "followers_url": format!("{user_url}/followers"),
+            "following_url": format!("{user_url}/following"),
+            "gists_url": format!("{user_url}/gists"),
+            "starred_url": format!("{user_url}/starred"),
+            "subscriptions_url": format!("{user_url}/subscriptions"),
+            "organizations_url": format!("{user_url}/orgs"),
+            "repos_url": format!("{user_url}/repos"),
+            "events_url": format!("{user_url}/events"),
+            "received_events_url": format!("{user_url}/received_events"),
+            "type": "User",
+            "site_admin": false,
+        },
+    })
+    .to_string()
+}
+
+/// Serve the GitHub API on a random port, responding to the first `failures` GraphQL queries with 429
+pub fn mock_github(failures: usize) -> MockGitHub {
+    serve_github(failures, &[], Duration::ZERO)
+}
+
+/// Serve the GitHub API like [`mock_github`], in which the repositories of `renames` were renamed or transferred
+///
+/// Each rename is a pair of the old and new `owner/name` of a repository.
+/// Like GitHub, requests for the old repository are redirected with a 301 to the repository's ID,
+/// and other repositories are returned as they were requested.
+pub fn mock_github_with_renames(failures: usize, renames: &[(&str, &str)]) -> MockGitHub {
+    serve_github(failures, renames, Duration::ZERO)
+}
+
+/// Serve the GitHub API like [`mock_github`], waiting for `latency` before responding to each request
+/// so that concurrent requests overlap
+pub fn mock_github_with_latency(latency: Duration) -> MockGitHub {
+    serve_github(0, &[], latency)
+}
+
+fn serve_github(failures: usize, renames: &[(&str, &str)], latency: Duration) -> MockGitHub {
+    let renames = Arc::new(
+        renames
+            .iter()
+            .map(|&(old, new)| (old.to_lowercase(), new.to_owned()))
+            .collect::<Vec<_>>(),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mock = MockGitHub {
+        url: format!("http://{}/", listener.local_addr().unwrap()),
+        queries: Arc::new(AtomicUsize::new(0)),
+        user_agents: Arc::new(Mutex::new(Vec::new())),
+        max_in_flight: Arc::new(AtomicUsize::new(0)),
+    };
+    let counter = Arc::clone(&mock.queries);
+    let user_agents = Arc::clone(&mock.user_agents);
+    let max_in_flight = Arc::clone(&mock.max_in_flight);
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let url = mock.url.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let (counter, user_agents, renames, url) = (
+                Arc::clone(&counter),
+                Arc::clone(&user_agents),
+                Arc::clone(&renames),
+                url.clone(),
+            );
+            let (in_flight, max_in_flight) = (Arc::clone(&in_flight), Arc::clone(&max_in_flight));
+            thread::spawn(move || {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = header.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        } else if name.eq_ignore_ascii_case("user-agent") {
+                            user_agents.lock().unwrap().push(value.trim().to_owned());
+                        }
+                    }
+                }
+                let mut body = Vec::new();
+                reader
+                    .by_ref()
+                    .take(content_length)
+                    .read_to_end(&mut body)
+                    .unwrap();
+
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(current, Ordering::SeqCst);
+                thread::sleep(latency);
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                let path = request_line.split(' ').nth(1).unwrap_or_default();
+                let repo_path = path
+                    .strip_prefix("/repos/")
+                    .filter(|repo| repo.matches('/').count() == 1);
+                let renamed = repo_path.and_then(|repo| {
+                    renames
+                        .iter()
+                        .position(|(old, _)| *old == repo.to_lowercase())
+                });
+                let (status, body) = if let Some(i) = renamed {
+                    write!(
+                        stream,
+                        "HTTP/1.1 301 Moved Permanently\r\nLocation: {url}repositories/{i}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    )
+                    .unwrap();
+                    return;
+                } else if let Some(i) = path
+                    .strip_prefix("/repositories/")
+                    .and_then(|i| i.parse::<usize>().ok())
+                {
+                    let (owner, name) = renames[i].1.split_once('/').unwrap();
+                    ("200 OK", repository(i, owner, name))
+                } else if let Some((owner, name)) = repo_path.and_then(|repo| repo.split_once('/'))
+                {
+                    ("200 OK", repository(1000, owner, name))
+                } else if request_line.starts_with("POST /graphql") {
+                    if counter.fetch_add(1, Ordering::SeqCst) < failures {
+                        ("429 Too Many Requests", RATE_LIMITED.to_owned())
+                    } else {
+                        let request: Value = serde_json::from_slice(&body).unwrap();
+                        ("200 OK", repositories(request["query"].as_str().unwrap()))
+                    }
+                } else {
+                    ("404 Not Found", NOT_FOUND.to_owned())
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len(),
+                )
+                .unwrap();
+            });
+        }
+    });
+    mock
+}
+
+/// Serve `body` in response to every request on a random port, and return the URL of the server
+pub fn serve_file(body: &'static [u8]) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len(),
+            )
+            .unwrap();
+            stream.write_all(body).unwrap();
+        }
+    });
+    url
+}
@@ -0,0 +1,40 @@
+//! Limiting how many mods from each platform are resolved at once, through a slow mock of the GitHub API
+
+mod common;
+
+use libium::{
+    config::{filters::FileSelection, structs::ModIdentifier},
+    upgrade::mod_downloadable::{resolve_upgrades_with_limits, ConcurrencyLimits},
+};
+use std::{sync::atomic::Ordering, time::Duration};
+
+#[tokio::test]
+async fn limits_requests_in_flight() {
+    let github = common::mock_github_with_latency(Duration::from_millis(100));
+    libium::set_github_api_url(github.url).unwrap();
+
+    let mut profile = common::profile("Profile", "mods");
+    for i in 0..6 {
+        profile.push_mod(
+            format!("Mod {i}"),
+            ModIdentifier::GitHubRepository("owner".to_owned(), format!("mod{i}")),
+            false,
+            Vec::new(),
+        );
+    }
+    let results = resolve_upgrades_with_limits(
+        &profile,
+        FileSelection::default(),
+        ConcurrencyLimits {
+            github: 2,
+            ..Default::default()
+        },
+        None,
+    )
+    .await;
+
+    // The mock doesn't list releases, so every mod fails to resolve after one request
+    assert_eq!(results.len(), 6);
+    assert!(results.iter().all(|result| result.target.is_err()));
+    assert_eq!(github.max_in_flight.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,119 @@
+//! Writing a config and reading it back, and the settings of each type of content in a profile
+
+mod common;
+
+use libium::config::{
+    filters::{Filter, FilterKind},
+    read_existing_config,
+    structs::{Config, ContentType, ModIdentifier, ModLoader},
+    write_config,
+};
+use std::{fs, path::PathBuf};
+
+#[test]
+fn asset_filter_round_trip() {
+    let dir = std::env::temp_dir().join(format!("libium-config-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config.json");
+
+    let mut profile = common::profile("Profile", "mods");
+    profile
+        .push_mod(
+            "Create Addons".to_owned(),
+            ModIdentifier::GitHubRepository("owner".to_owned(), "addons".to_owned()),
+            false,
+            Vec::new(),
+        )
+        .asset_filter = Some(r"^create-extra-.*\.jar$".to_owned());
+    profile.push_mod(
+        "Sodium".to_owned(),
+        ModIdentifier::ModrinthProject("AANobbMI".to_owned()),
+        false,
+        Vec::new(),
+    );
+    let config = Config {
+        profiles: vec![profile],
+        ..Default::default()
+    };
+    write_config(&path, &config).unwrap();
+    let written = fs::read_to_string(&path).unwrap();
+    let read = read_existing_config(&path).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let mods = &read.profiles[0].mods;
+    assert_eq!(
+        mods[0].asset_filter.as_deref(),
+        Some(r"^create-extra-.*\.jar$")
+    );
+    assert_eq!(mods[1].asset_filter, None);
+    // Mods without an asset filter don't write one
+    assert_eq!(written.matches("asset_filter").count(), 1);
+}
+
+#[test]
+fn content_type_filters() {
+    let filters = vec![
+        Filter::ModLoaderPrefer(vec![ModLoader::Fabric]),
+        Filter::ModLoaderAny(vec![ModLoader::Fabric, ModLoader::Quilt]),
+        Filter::GameVersionStrict(vec!["1.20.1".to_owned()]),
+    ];
+    let kinds = |content_type: ContentType| {
+        content_type
+            .applicable_filters(filters.clone())
+            .iter()
+            .map(Filter::kind)
+            .collect::<Vec<_>>()
+    };
+    // Data packs can contain mod loader specific content, unlike resource packs and shaders
+    for content_type in [ContentType::Mod, ContentType::DataPack] {
+        assert_eq!(
+            kinds(content_type),
+            [
+                FilterKind::ModLoader,
+                FilterKind::ModLoader,
+                FilterKind::GameVersion
+            ],
+            "{content_type}"
+        );
+    }
+    for content_type in [ContentType::ResourcePack, ContentType::ShaderPack] {
+        assert_eq!(
+            kinds(content_type),
+            [FilterKind::GameVersion],
+            "{content_type}"
+        );
+    }
+}
+
+#[test]
+fn content_type_output_dirs() {
+    let mut profile = common::profile("Profile", ".minecraft/mods");
+    let dirs = |profile: &libium::config::structs::Profile| {
+        [
+            ContentType::Mod,
+            ContentType::ResourcePack,
+            ContentType::ShaderPack,
+            ContentType::DataPack,
+        ]
+        .map(|content_type| profile.output_dir_for(content_type))
+    };
+    // The directories default to siblings of the output directory
+    assert_eq!(
+        dirs(&profile),
+        [
+            ".minecraft/mods",
+            ".minecraft/resourcepacks",
+            ".minecraft/shaderpacks",
+            ".minecraft/datapacks",
+        ]
+        .map(PathBuf::from)
+    );
+
+    profile.resourcepacks_dir = Some("packs".into());
+    profile.shaderpacks_dir = Some("shaders".into());
+    profile.datapacks_dir = Some("world/datapacks".into());
+    assert_eq!(
+        dirs(&profile),
+        [".minecraft/mods", "packs", "shaders", "world/datapacks"].map(PathBuf::from)
+    );
+}
@@ -0,0 +1,93 @@
+//! Downloading a resolution plan into an output directory that can't be written to,
+//! and verifying the hashes of downloaded files
+
+mod common;
+
+use libium::upgrade::{
+    mod_downloadable::{download_plan, ResolutionPlan},
+    DownloadData, Error,
+};
+use sha1::{Digest, Sha1};
+use sha2::Sha512;
+use std::{fs, sync::atomic::AtomicBool};
+
+const CONTENTS: &[u8] = b"The contents of the JAR file";
+
+fn download_data(url: String, sha1: Option<String>, sha512: Option<String>) -> DownloadData {
+    DownloadData {
+        download_url: url.parse().unwrap(),
+        output: "example.jar".into(),
+        length: CONTENTS.len(),
+        dependencies: Vec::new(),
+        optional_dependencies: Vec::new(),
+        conflicts: Vec::new(),
+        file_id: None,
+        sha1,
+        sha512,
+        published: None,
+        version: None,
+        loader: None,
+        game_version: None,
+        optional_files: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn output_dir_is_checked_before_downloading() {
+    let file = std::env::temp_dir().join(format!("libium-output-{}", std::process::id()));
+    fs::write(&file, "").unwrap();
+
+    let profile = common::profile("Profile", file.clone());
+    let plan = ResolutionPlan {
+        mods: Vec::new(),
+        errors: Vec::new(),
+        missing_dependencies: Vec::new(),
+    };
+    let result = download_plan(
+        &profile,
+        &plan,
+        reqwest::Client::new(),
+        1,
+        |_, _| {},
+        &AtomicBool::new(false),
+    )
+    .await;
+    fs::remove_file(&file).unwrap();
+
+    let err = result.unwrap_err();
+    assert!(err.to_string().contains("is not a directory"), "{err}");
+}
+
+#[tokio::test]
+async fn sha512_preferred_over_sha1() {
+    let url = common::serve_file(CONTENTS);
+    let dir = std::env::temp_dir().join(format!("libium-verified-{}", std::process::id()));
+    let sha1 = format!("{:x}", Sha1::digest(CONTENTS));
+    let sha512 = format!("{:x}", Sha512::digest(CONTENTS));
+
+    // The SHA-1 hash is wrong, but it isn't checked since there's a SHA-512 hash
+    let (written, _, hash) = download_data(url.clone(), Some("0".repeat(40)), Some(sha512.clone()))
+        .download_verified(reqwest::Client::new(), &dir, |_| {})
+        .await
+        .unwrap();
+    assert_eq!(written, CONTENTS.len());
+    assert_eq!(hash, sha512);
+    assert_eq!(fs::read(dir.join("example.jar")).unwrap(), CONTENTS);
+
+    let result = download_data(url.clone(), Some(sha1.clone()), Some("0".repeat(128)))
+        .download_verified(reqwest::Client::new(), &dir, |_| {})
+        .await;
+    assert!(matches!(
+        result,
+        Err(Error::HashMismatch { actual, .. }) if actual == sha512
+    ));
+    assert!(!dir.join("example.part").exists());
+
+    // Platforms without SHA-512 hashes fall back to SHA-1
+    let (_, _, hash) = download_data(url, Some(sha1.clone()), None)
+        .download_verified(reqwest::Client::new(), &dir, |_| {})
+        .await
+        .unwrap();
+    assert_eq!(hash, sha1);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,39 @@
+//! Resolving against a frozen list of game versions instead of fetching it
+
+use libium::{
+    game_version::{self, latest_including_snapshots, latest_stable},
+    search::{Error, SearchFilters},
+};
+
+#[tokio::test]
+async fn frozen_game_versions() {
+    game_version::set_override_from_file("tests/fixtures/game_versions.json").unwrap();
+    // The override can only be set once
+    assert!(game_version::set_override_from_file("tests/fixtures/game_versions.json").is_err());
+
+    // 1.99 isn't a real version, so the list can't have been fetched
+    let versions = game_version::list().await.unwrap();
+    assert_eq!(
+        versions
+            .iter()
+            .map(|version| version.version.as_str())
+            .collect::<Vec<_>>(),
+        ["1.99", "99w14a", "1.21", "1.20.1"]
+    );
+    assert_eq!(latest_stable(versions).unwrap().version, "1.99");
+    assert_eq!(
+        latest_including_snapshots(versions).unwrap().version,
+        "1.99"
+    );
+
+    let filters = |version: &str| SearchFilters {
+        game_versions: vec![version.to_owned()],
+        ..Default::default()
+    };
+    assert!(filters("1.20.1").facets().await.is_ok());
+    // 1.20.2 is a real version, but it isn't in the frozen list
+    assert!(matches!(
+        filters("1.20.2").facets().await,
+        Err(Error::UnknownGameVersion(version)) if version == "1.20.2"
+    ));
+}
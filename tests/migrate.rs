@@ -0,0 +1,99 @@
+//! Migrating a config written before the config had a schema version
+
+use libium::config::{
+    migrate, read_and_persist_config, read_existing_config, structs::ModIdentifier, SCHEMA_VERSION,
+};
+use serde_json::{json, Value};
+use std::fs;
+
+#[test]
+fn migrate_v0_config() {
+    let value: Value = serde_json::from_str(include_str!("fixtures/config_v0.json")).unwrap();
+    let config = migrate(value).unwrap();
+
+    assert_eq!(config.schema_version, SCHEMA_VERSION);
+    assert_eq!(config.active_profile, 1);
+    assert_eq!(config.profiles.len(), 2);
+
+    let quilt = &config.profiles[0];
+    assert_eq!(quilt.name, "Quilt");
+    assert_eq!(
+        serde_json::to_value(&quilt.filters).unwrap(),
+        json!([
+            { "ModLoaderPrefer": ["Quilt", "Fabric"] },
+            { "GameVersionStrict": ["1.19.2"] },
+        ])
+    );
+    assert_eq!(
+        quilt
+            .mods
+            .iter()
+            .map(|mod_| &mod_.identifier)
+            .collect::<Vec<_>>(),
+        [
+            &ModIdentifier::ModrinthProject("AANobbMI".to_owned()),
+            &ModIdentifier::CurseForgeProject(238222),
+        ]
+    );
+
+    let forge = &config.profiles[1];
+    assert_eq!(
+        serde_json::to_value(&forge.filters).unwrap(),
+        json!([
+            { "ModLoaderPrefer": ["Forge"] },
+            { "GameVersionStrict": ["1.20.1"] },
+        ])
+    );
+    assert_eq!(
+        forge.mods[0].identifier,
+        ModIdentifier::GitHubRepository("CaffeineMC".to_owned(), "sodium".to_owned())
+    );
+
+    // The old fields are removed, and the schema version is written
+    let migrated = serde_json::to_value(&config).unwrap();
+    assert_eq!(migrated["schema_version"], json!(SCHEMA_VERSION));
+    assert!(migrated["profiles"][0].get("game_version").is_none());
+    assert!(migrated["profiles"][0].get("mod_loader").is_none());
+}
+
+#[test]
+fn migrate_current_config() {
+    let value = json!({
+        "schema_version": SCHEMA_VERSION,
+        "profiles": [{
+            "name": "Fabric",
+            "output_dir": "/home/user/.minecraft/mods",
+            "filters": [{ "ModLoaderPrefer": ["Fabric"] }],
+            "mods": [],
+        }],
+    });
+    let config = migrate(value).unwrap();
+
+    assert_eq!(config.schema_version, SCHEMA_VERSION);
+    assert_eq!(
+        serde_json::to_value(&config.profiles[0].filters).unwrap(),
+        json!([{ "ModLoaderPrefer": ["Fabric"] }])
+    );
+}
+
+#[test]
+fn persist_migrated_config() {
+    let path = std::env::temp_dir().join(format!("libium-migrate-{}.json", std::process::id()));
+    fs::write(&path, include_str!("fixtures/config_v0.json")).unwrap();
+
+    // Reading the config only migrates it in memory
+    let config = read_existing_config(&path).unwrap();
+    assert_eq!(config.schema_version, SCHEMA_VERSION);
+    let written: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert!(written.get("schema_version").is_none());
+
+    let persisted = read_and_persist_config(&path).unwrap();
+    let written: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(written["schema_version"], json!(SCHEMA_VERSION));
+    assert!(written["profiles"][0].get("game_version").is_none());
+    assert_eq!(
+        serde_json::to_value(&persisted.profiles[0].filters).unwrap(),
+        written["profiles"][0]["filters"]
+    );
+}
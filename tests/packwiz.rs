@@ -0,0 +1,171 @@
+//! Exporting a profile as a packwiz pack and importing the pack back into a profile
+
+mod common;
+
+use libium::{
+    config::structs::{ContentType, ModIdentifier, ModOrigin},
+    modpack::packwiz::{export_plan_packwiz, import_packwiz},
+    upgrade::{
+        mod_downloadable::{ResolutionPlan, ResolvedMod},
+        DownloadData,
+    },
+};
+use std::{fs, path::PathBuf};
+
+fn resolved(
+    name: &str,
+    source: ModIdentifier,
+    filename: &str,
+    file_id: &str,
+    sha1: Option<&str>,
+) -> ResolvedMod {
+    let provider = source.provider();
+    ResolvedMod {
+        name: name.to_owned(),
+        source,
+        tracked_by: provider,
+        served_by: provider,
+        download_data: DownloadData {
+            download_url: format!("https://cdn.example.com/{filename}")
+                .parse()
+                .unwrap(),
+            output: filename.into(),
+            length: 1024,
+            dependencies: Vec::new(),
+            optional_dependencies: Vec::new(),
+            conflicts: Vec::new(),
+            file_id: Some(file_id.to_owned()),
+            sha1: sha1.map(ToOwned::to_owned),
+            sha512: None,
+            published: None,
+            version: None,
+            loader: None,
+            game_version: None,
+            optional_files: Vec::new(),
+        },
+        destination: PathBuf::from("mods"),
+        changed: true,
+        trace: None,
+        skipped_files: Vec::new(),
+    }
+}
+
+#[test]
+fn export_then_import() {
+    let dir = std::env::temp_dir().join(format!("libium-packwiz-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+
+    let mut exported = common::profile("Pack", "mods");
+    exported.loader_version = Some("0.15.11".to_owned());
+    exported.push_mod(
+        "Sodium".to_owned(),
+        ModIdentifier::ModrinthProject("AANobbMI".to_owned()),
+        false,
+        Vec::new(),
+    );
+    exported.push_mod(
+        "Just Enough Items".to_owned(),
+        ModIdentifier::CurseForgeProject(238222),
+        false,
+        Vec::new(),
+    );
+    exported
+        .push_mod(
+            "Fresh Animations".to_owned(),
+            ModIdentifier::ModrinthProject("50dA9Sha".to_owned()),
+            false,
+            Vec::new(),
+        )
+        .content_type = ContentType::ResourcePack;
+    exported.push_mod(
+        "Example".to_owned(),
+        ModIdentifier::GitHubRepository("owner".to_owned(), "example".to_owned()),
+        false,
+        Vec::new(),
+    );
+    let plan = ResolutionPlan {
+        mods: vec![
+            resolved(
+                "Sodium",
+                ModIdentifier::ModrinthProject("AANobbMI".to_owned()),
+                "sodium-fabric-mc1.20.1-0.5.3.jar",
+                "OihdIimA",
+                Some("36b3c5d1e2b8e4c2a5b0b0e1d4a1f1c7f6a2b3c4"),
+            ),
+            resolved(
+                "Just Enough Items",
+                ModIdentifier::CurseForgeProject(238222),
+                "jei-1.20.1-fabric-15.2.0.27.jar",
+                "4712866",
+                Some("9d6b7a4c3e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b"),
+            ),
+            resolved(
+                "Fresh Animations",
+                ModIdentifier::ModrinthProject("50dA9Sha".to_owned()),
+                "FreshAnimations_v1.9.1.zip",
+                "n8mC0jCx",
+                Some("0a1b2c3d4e5f60718293a4b5c6d7e8f901234567"),
+            ),
+            // GitHub releases don't have hashes
+            resolved(
+                "Example",
+                ModIdentifier::GitHubRepository("owner".to_owned(), "example".to_owned()),
+                "example-1.0.jar",
+                "123456",
+                None,
+            ),
+        ],
+        errors: Vec::new(),
+        missing_dependencies: Vec::new(),
+    };
+    let summary = export_plan_packwiz(&exported, plan, &dir).unwrap();
+    assert_eq!(
+        summary.exported,
+        ["Sodium", "Just Enough Items", "Fresh Animations"]
+    );
+    assert_eq!(summary.without_hash, ["Example"]);
+    let pack: toml::Table =
+        toml::from_str(&fs::read_to_string(dir.join("pack.toml")).unwrap()).unwrap();
+    assert_eq!(pack["versions"]["minecraft"].as_str(), Some("1.20.1"));
+    assert_eq!(pack["versions"]["fabric"].as_str(), Some("0.15.11"));
+
+    let mut imported = common::profile("Imported", "mods");
+    let summary = import_packwiz(&dir, &mut imported, &[]).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(
+        summary.imported,
+        ["Sodium", "Just Enough Items", "Fresh Animations"]
+    );
+    assert!(summary.unmapped.is_empty());
+
+    let identifiers = imported
+        .mods
+        .iter()
+        .map(|mod_| (mod_.name.as_str(), &mod_.identifier, mod_.content_type))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        identifiers,
+        [
+            (
+                "Sodium",
+                &ModIdentifier::PinnedModrinthProject("AANobbMI".to_owned(), "OihdIimA".to_owned()),
+                ContentType::Mod
+            ),
+            (
+                "Just Enough Items",
+                &ModIdentifier::PinnedCurseForgeProject(238222, 4712866),
+                ContentType::Mod
+            ),
+            (
+                "Fresh Animations",
+                &ModIdentifier::PinnedModrinthProject("50dA9Sha".to_owned(), "n8mC0jCx".to_owned()),
+                ContentType::ResourcePack
+            ),
+        ]
+    );
+    assert!(imported.mods.iter().all(|mod_| mod_.origin
+        == Some(ModOrigin::Modpack {
+            name: "Pack".to_owned(),
+            version: None
+        })));
+}
@@ -0,0 +1,39 @@
+//! Following GitHub's redirects for repositories that were renamed or transferred
+
+mod common;
+
+use libium::{config::structs::ModIdentifier, upgrade::mod_downloadable::follow_github_redirects};
+
+#[tokio::test]
+async fn renamed_repository_updated() {
+    let github = common::mock_github_with_renames(0, &[("owner/old-name", "new-owner/new-name")]);
+    libium::set_github_api_url(github.url).unwrap();
+
+    let mut profile = common::profile("Profile", "mods");
+    let renamed = profile.push_mod(
+        "Renamed".to_owned(),
+        ModIdentifier::GitHubRepository("owner".to_owned(), "old-name".to_owned()),
+        false,
+        Vec::new(),
+    );
+    renamed.slug = Some("old-name".to_owned());
+    profile.push_mod(
+        "Unchanged".to_owned(),
+        ModIdentifier::GitHubRepository("owner".to_owned(), "unchanged".to_owned()),
+        false,
+        Vec::new(),
+    );
+
+    let (updated, errors) = follow_github_redirects(&mut profile).await;
+    assert!(errors.is_empty(), "{errors:?}");
+    assert_eq!(updated, ["Renamed"]);
+    assert_eq!(
+        profile.mods[0].identifier,
+        ModIdentifier::GitHubRepository("new-owner".to_owned(), "new-name".to_owned())
+    );
+    assert_eq!(profile.mods[0].slug.as_deref(), Some("new-name"));
+    assert_eq!(
+        profile.mods[1].identifier,
+        ModIdentifier::GitHubRepository("owner".to_owned(), "unchanged".to_owned())
+    );
+}
@@ -0,0 +1,92 @@
+//! Resuming adds and config writes that were interrupted
+
+mod common;
+
+use libium::{
+    add::{add_resumable, parse_id},
+    config::{read_existing_config, structs::Config, write_config},
+};
+use std::{fs, io, sync::atomic::Ordering};
+
+#[tokio::test]
+async fn resumes_interrupted_add() {
+    let github = common::mock_github(0);
+    libium::set_github_api_url(github.url).unwrap();
+    let queue_path = std::env::temp_dir().join(format!("libium-queue-{}", std::process::id()));
+    let _ = fs::remove_file(&queue_path);
+    let identifiers = (0..25)
+        .map(|i| parse_id(format!("owner/mod{i}")).unwrap())
+        .collect::<Vec<_>>();
+
+    let mut profile = common::profile("Profile", "mods");
+    // The process is killed while saving the second chunk, so only the first chunk was saved
+    let mut saved = None;
+    let result = add_resumable(
+        &mut profile,
+        identifiers.clone(),
+        true,
+        false,
+        Vec::new(),
+        &queue_path,
+        |profile| match saved {
+            None => {
+                saved = Some(profile.clone());
+                Ok(())
+            }
+            Some(_) => Err(io::Error::other("Interrupted")),
+        },
+    )
+    .await;
+    assert!(result.is_err());
+    let mut profile = saved.unwrap();
+    assert_eq!(profile.mods.len(), 20);
+    assert_eq!(fs::read_to_string(&queue_path).unwrap().lines().count(), 20);
+    assert_eq!(github.queries.load(Ordering::SeqCst), 2);
+
+    // Resuming only adds the identifiers that weren't saved
+    let (mut added, errors) = add_resumable(
+        &mut profile,
+        identifiers,
+        true,
+        false,
+        Vec::new(),
+        &queue_path,
+        |_| Ok(()),
+    )
+    .await
+    .unwrap();
+    assert!(errors.is_empty(), "{errors:?}");
+    added.sort_unstable();
+    assert_eq!(
+        added,
+        (20..25)
+            .map(|i| format!("owner/mod{i}"))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(profile.mods.len(), 25);
+    assert_eq!(github.queries.load(Ordering::SeqCst), 3);
+    assert!(!queue_path.exists());
+}
+
+#[test]
+fn interrupted_write_keeps_config() {
+    let path = std::env::temp_dir().join(format!("libium-config-{}.json", std::process::id()));
+    let mut config = Config::default();
+    config.profiles.push(common::profile("Profile", "mods"));
+    write_config(&path, &config).unwrap();
+
+    // The process was killed while writing the temporary file
+    let temp_path = path.with_extension("part");
+    fs::write(&temp_path, r#"{"profiles": [{"name": "Prof"#).unwrap();
+    assert_eq!(read_existing_config(&path).unwrap().profiles.len(), 1);
+
+    // The next write replaces the half-written file
+    config.profiles[0].name = "Renamed".to_owned();
+    write_config(&path, &config).unwrap();
+    assert!(!temp_path.exists());
+    assert_eq!(
+        read_existing_config(&path).unwrap().profiles[0].name,
+        "Renamed"
+    );
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,36 @@
+//! Adding a GitHub repository through a mock of the GitHub API that is rate limited at first
+
+mod common;
+
+use libium::add::{add_with_policy, parse_id, RetryPolicy};
+use std::sync::atomic::Ordering;
+
+#[tokio::test]
+async fn retries_rate_limited_requests() {
+    let github = common::mock_github(1);
+    libium::set_github_api_url(github.url).unwrap();
+    libium::set_user_agent("retry-test/1.0".to_owned()).unwrap();
+
+    let mut profile = common::profile("Profile", "mods");
+    let (added, errors) = add_with_policy(
+        &mut profile,
+        vec![parse_id("owner/example".to_owned()).unwrap()],
+        true,
+        false,
+        Vec::new(),
+        RetryPolicy::immediate(3),
+    )
+    .await
+    .unwrap();
+
+    assert!(errors.is_empty(), "{errors:?}");
+    assert_eq!(added, ["owner/example"]);
+    assert_eq!(github.queries.load(Ordering::SeqCst), 2);
+    assert_eq!(profile.mods.len(), 1);
+    // Only the custom user agent is sent, once with each request
+    let user_agents = github.user_agents.lock().unwrap();
+    assert!(!user_agents.is_empty());
+    assert!(user_agents
+        .iter()
+        .all(|user_agent| user_agent == "retry-test/1.0"));
+}
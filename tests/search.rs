@@ -0,0 +1,21 @@
+//! Comparing a mod across platforms through a mock of the GitHub API
+
+mod common;
+
+use libium::{config::structs::ProviderKind, search::cross_provider_report};
+use std::collections::HashSet;
+
+#[tokio::test]
+async fn only_allowed_platforms_reported() {
+    let github = common::mock_github(0);
+    libium::set_github_api_url(github.url).unwrap();
+
+    let mut profile = common::profile("Profile", "mods");
+    profile.allowed_providers = HashSet::from([ProviderKind::GitHub]);
+    let report = cross_provider_report(&profile, "example").await;
+
+    assert!(matches!(report.modrinth, Ok(None)));
+    assert!(matches!(report.curseforge, Ok(None)));
+    // The mock doesn't implement searching, so GitHub is the only platform that was searched
+    assert!(report.github.is_err());
+}